@@ -22,6 +22,23 @@ pub enum AssemblyItem<'ast> {
     HexLiteral,
 }
 
+impl<'ast> AssemblyItem<'ast> {
+    /// Name of the variant.
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            AssemblyItem::Identifier(_)                   => "Identifier",
+            AssemblyItem::FunctionalAssemblyExpression(_) => "FunctionalAssemblyExpression",
+            AssemblyItem::InlineAssemblyBlock(_)          => "InlineAssemblyBlock",
+            AssemblyItem::AssemblyLocalBinding(_)         => "AssemblyLocalBinding",
+            AssemblyItem::AssemblyAssignment(_)           => "AssemblyAssignment",
+            AssemblyItem::AssemblyLabel(_)                => "AssemblyLabel",
+            AssemblyItem::NumberLiteral(_)                => "NumberLiteral",
+            AssemblyItem::StringLiteral                   => "StringLiteral",
+            AssemblyItem::HexLiteral                      => "HexLiteral",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct AssemblyLocalBinding<'ast> {
     pub id: IdentifierNode<'ast>,
@@ -0,0 +1,703 @@
+//! Construction of the AST by hand, for tools that generate Solidity,
+//! such as extracting interfaces or generating proxies.
+//!
+//! ```rust
+//! # extern crate lunarity_ast as ast;
+//! # use ast::*;
+//! # fn main() {
+//! let program = Program::build(|b| {
+//!     let getter = b.function(
+//!         Some("get"),
+//!         b.list(None),
+//!         Some(FunctionVisibility::External),
+//!         Some(StateMutability::View),
+//!         b.list(Some(b.parameter(b.elementary(ElementaryTypeName::Uint(32)), None))),
+//!         None,
+//!     );
+//!
+//!     b.list(Some(b.contract("Getter", &[], Some(getter))))
+//! });
+//!
+//! assert_eq!(program.body().iter().count(), 1);
+//! # }
+//! ```
+//!
+//! All nodes are located at `0:0`, unless the builder is switched to
+//! a synthetic location with `AstBuilder::at`. Identifiers and literals
+//! are copied onto the `Arena`, so they can be built from any `&str`.
+
+use toolshed::list::List;
+use toolshed::Arena;
+
+use {*};
+
+/// Builds nodes of the AST on an `Arena`, see module docs.
+#[derive(Clone, Copy)]
+pub struct AstBuilder<'ast> {
+    arena: &'ast Arena,
+    start: u32,
+    end: u32,
+}
+
+impl<'ast> Program<'ast> {
+    /// Build a `Program` with an `AstBuilder` on a new `Arena`.
+    pub fn build<F>(f: F) -> Self
+    where
+        F: for<'b> FnOnce(AstBuilder<'b>) -> SourceUnitList<'b>,
+    {
+        let arena = Arena::new();
+        let body = f(AstBuilder::new(&arena)).into_unsafe();
+
+        Program::new(body, arena)
+    }
+
+    /// Get an `AstBuilder` for nodes to be put into this `Program`.
+    #[inline]
+    pub fn builder(&'ast self) -> AstBuilder<'ast> {
+        AstBuilder::new(self.arena())
+    }
+}
+
+impl<'ast> AstBuilder<'ast> {
+    #[inline]
+    pub fn new(arena: &'ast Arena) -> Self {
+        AstBuilder {
+            arena,
+            start: 0,
+            end: 0,
+        }
+    }
+
+    /// Get a builder that locates all nodes it builds at `start:end`.
+    #[inline]
+    pub fn at(self, start: u32, end: u32) -> Self {
+        AstBuilder {
+            start,
+            end,
+            ..self
+        }
+    }
+
+    #[inline]
+    pub fn arena(&self) -> &'ast Arena {
+        self.arena
+    }
+
+    /// Allocate a node of any kind.
+    #[inline]
+    pub fn node<T, I>(&self, value: I) -> Node<'ast, T>
+    where
+        T: 'ast + Copy,
+        I: Into<T>,
+    {
+        Node::new(self.arena.alloc(NodeInner::new(self.start, self.end, value.into())))
+    }
+
+    #[inline]
+    pub fn list<T, I>(&self, nodes: I) -> NodeList<'ast, T>
+    where
+        T: 'ast + Copy,
+        I: IntoIterator<Item = Node<'ast, T>>,
+    {
+        List::from_iter(self.arena, nodes)
+    }
+
+    #[inline]
+    pub fn str(&self, value: &str) -> &'ast str {
+        self.arena.alloc_str(value)
+    }
+
+    #[inline]
+    pub fn identifier(&self, name: &str) -> IdentifierNode<'ast> {
+        self.node(self.str(name))
+    }
+
+    pub fn identifiers(&self, names: &[&str]) -> IdentifierList<'ast> {
+        self.list(names.iter().map(|name| self.identifier(name)))
+    }
+
+    /// String literal of the value, quoted and escaped.
+    pub fn string_literal(&self, value: &str) -> StringLiteralNode<'ast> {
+        self.node(self.quote(value))
+    }
+
+    fn quote(&self, value: &str) -> &'ast str {
+        let mut literal = String::with_capacity(value.len() + 2);
+
+        literal.push('"');
+
+        for ch in value.chars() {
+            match ch {
+                '"' | '\\' => {
+                    literal.push('\\');
+                    literal.push(ch);
+                },
+                '\n' => literal.push_str("\\n"),
+                _    => literal.push(ch),
+            }
+        }
+
+        literal.push('"');
+
+        self.str(&literal)
+    }
+
+    #[inline]
+    pub fn flag(&self) -> FlagNode<'ast> {
+        self.node(Flag)
+    }
+
+    fn flag_if(&self, set: bool) -> Option<FlagNode<'ast>> {
+        if set {
+            Some(self.flag())
+        } else {
+            None
+        }
+    }
+
+    // Source units
+
+    pub fn pragma(&self, version: &str) -> SourceUnitNode<'ast> {
+        self.node(PragmaDirective {
+            version: self.str(version),
+        })
+    }
+
+    /// `import "source";`
+    pub fn import(&self, source: &str) -> SourceUnitNode<'ast> {
+        self.node(ImportDirective::Global {
+            source: self.string_literal(source),
+            alias: None,
+        })
+    }
+
+    /// `import { symbol as alias, ... } from "source";`
+    pub fn import_from(&self, imports: &[(&str, Option<&str>)], source: &str) -> SourceUnitNode<'ast> {
+        let imports = imports.iter().map(|&(symbol, alias)| self.node(Import {
+            symbol: self.identifier(symbol),
+            alias: alias.map(|alias| self.identifier(alias)),
+        }));
+
+        self.node(ImportDirective::ManyFrom {
+            imports: self.list(imports),
+            source: self.string_literal(source),
+        })
+    }
+
+    pub fn contract<I>(&self, name: &str, inherits: &[&str], body: I) -> SourceUnitNode<'ast>
+    where
+        I: IntoIterator<Item = ContractPartNode<'ast>>,
+    {
+        self.node(ContractDefinition {
+            doc: None,
+            name: self.identifier(name),
+            inherits: self.identifiers(inherits),
+            body: self.list(body),
+        })
+    }
+
+    // Contract parts
+
+    pub fn state_variable(
+        &self,
+        type_name: TypeNameNode<'ast>,
+        visibility: Option<StateVariableVisibility>,
+        name: &str,
+        init: Option<ExpressionNode<'ast>>,
+    ) -> ContractPartNode<'ast> {
+        self.node(StateVariableDeclaration {
+            doc: None,
+            type_name,
+            visibility: visibility.map(|visibility| self.node(visibility)),
+            constant: None,
+            name: self.identifier(name),
+            init,
+        })
+    }
+
+    pub fn using_for(&self, library: &str, type_name: Option<TypeNameNode<'ast>>) -> ContractPartNode<'ast> {
+        self.node(UsingForDeclaration {
+            id: self.identifier(library),
+            type_name,
+        })
+    }
+
+    pub fn struct_definition<I>(&self, name: &str, body: I) -> ContractPartNode<'ast>
+    where
+        I: IntoIterator<Item = VariableDeclarationNode<'ast>>,
+    {
+        self.node(StructDefinition {
+            name: self.identifier(name),
+            body: self.list(body),
+        })
+    }
+
+    pub fn modifier(&self, name: &str, params: ParameterList<'ast>, block: BlockNode<'ast>) -> ContractPartNode<'ast> {
+        self.node(ModifierDefinition {
+            doc: None,
+            name: self.identifier(name),
+            params,
+            block,
+        })
+    }
+
+    /// Function without modifiers, a `None` name makes it the fallback
+    /// function and a `None` block makes it abstract.
+    pub fn function(
+        &self,
+        name: Option<&str>,
+        params: ParameterList<'ast>,
+        visibility: Option<FunctionVisibility>,
+        mutability: Option<StateMutability>,
+        returns: ParameterList<'ast>,
+        block: Option<BlockNode<'ast>>,
+    ) -> ContractPartNode<'ast> {
+        self.node(FunctionDefinition {
+            doc: None,
+            name: name.map(|name| self.identifier(name)),
+            params,
+            visibility: visibility.map(|visibility| self.node(visibility)),
+            mutability: mutability.map(|mutability| self.node(mutability)),
+            modifiers: NodeList::empty(),
+            returns,
+            block,
+        })
+    }
+
+    pub fn event(&self, name: &str, params: IndexedParameterList<'ast>, anonymous: bool) -> ContractPartNode<'ast> {
+        self.node(EventDefinition {
+            doc: None,
+            anonymous: self.flag_if(anonymous),
+            name: self.identifier(name),
+            params,
+        })
+    }
+
+    pub fn enum_definition(&self, name: &str, variants: &[&str]) -> ContractPartNode<'ast> {
+        self.node(EnumDefinition {
+            name: self.identifier(name),
+            variants: self.identifiers(variants),
+        })
+    }
+
+    pub fn parameter(&self, type_name: TypeNameNode<'ast>, name: Option<&str>) -> Node<'ast, Parameter<'ast>> {
+        self.node(Parameter {
+            type_name,
+            name: name.map(|name| self.identifier(name)),
+        })
+    }
+
+    pub fn indexed_parameter(
+        &self,
+        type_name: TypeNameNode<'ast>,
+        indexed: bool,
+        name: Option<&str>,
+    ) -> Node<'ast, IndexedParameter<'ast>> {
+        self.node(IndexedParameter {
+            type_name,
+            indexed: self.flag_if(indexed),
+            name: name.map(|name| self.identifier(name)),
+        })
+    }
+
+    pub fn modifier_invocation<I>(&self, name: &str, arguments: I) -> Node<'ast, ModifierInvocation<'ast>>
+    where
+        I: IntoIterator<Item = ExpressionNode<'ast>>,
+    {
+        self.node(ModifierInvocation {
+            id: self.identifier(name),
+            arguments: self.list(arguments),
+        })
+    }
+
+    // Type names
+
+    pub fn elementary(&self, elementary: ElementaryTypeName) -> TypeNameNode<'ast> {
+        self.node(elementary)
+    }
+
+    pub fn user_defined(&self, name: &str) -> TypeNameNode<'ast> {
+        self.node(self.str(name))
+    }
+
+    pub fn mapping(&self, from: ElementaryTypeName, to: TypeNameNode<'ast>) -> TypeNameNode<'ast> {
+        self.node(Mapping {
+            from: self.node(from),
+            to,
+        })
+    }
+
+    pub fn variable_declaration(
+        &self,
+        type_name: TypeNameNode<'ast>,
+        location: Option<StorageLocation>,
+        name: &str,
+    ) -> VariableDeclarationNode<'ast> {
+        self.node(VariableDeclaration {
+            type_name,
+            location: location.map(|location| self.node(location)),
+            id: self.identifier(name),
+        })
+    }
+
+    // Statements
+
+    pub fn block<I>(&self, body: I) -> BlockNode<'ast>
+    where
+        I: IntoIterator<Item = StatementNode<'ast>>,
+    {
+        self.node(Block {
+            body: self.list(body),
+        })
+    }
+
+    pub fn block_statement<I>(&self, body: I) -> StatementNode<'ast>
+    where
+        I: IntoIterator<Item = StatementNode<'ast>>,
+    {
+        self.node(Block {
+            body: self.list(body),
+        })
+    }
+
+    pub fn expression_statement(&self, expression: ExpressionNode<'ast>) -> StatementNode<'ast> {
+        self.node(expression)
+    }
+
+    pub fn if_statement(
+        &self,
+        test: ExpressionNode<'ast>,
+        consequent: StatementNode<'ast>,
+        alternate: Option<StatementNode<'ast>>,
+    ) -> StatementNode<'ast> {
+        self.node(IfStatement {
+            test,
+            consequent,
+            alternate,
+        })
+    }
+
+    pub fn while_statement(&self, test: ExpressionNode<'ast>, body: StatementNode<'ast>) -> StatementNode<'ast> {
+        self.node(WhileStatement {
+            test,
+            body,
+        })
+    }
+
+    pub fn do_while_statement(&self, body: StatementNode<'ast>, test: ExpressionNode<'ast>) -> StatementNode<'ast> {
+        self.node(DoWhileStatement {
+            body,
+            test,
+        })
+    }
+
+    pub fn for_statement(
+        &self,
+        init: Option<SimpleStatementNode<'ast>>,
+        test: Option<ExpressionNode<'ast>>,
+        update: Option<ExpressionNode<'ast>>,
+        body: StatementNode<'ast>,
+    ) -> StatementNode<'ast> {
+        self.node(ForStatement {
+            init,
+            test,
+            update,
+            body,
+        })
+    }
+
+    pub fn return_statement(&self, value: Option<ExpressionNode<'ast>>) -> StatementNode<'ast> {
+        self.node(ReturnStatement {
+            value,
+        })
+    }
+
+    /// `type_name name = init;`
+    pub fn variable_definition(
+        &self,
+        declaration: VariableDeclarationNode<'ast>,
+        init: Option<ExpressionNode<'ast>>,
+    ) -> StatementNode<'ast> {
+        self.node(VariableDefinitionStatement {
+            declaration,
+            init,
+        })
+    }
+
+    /// `var (a, , b) = init;`, with `None` for the skipped names.
+    pub fn inferred_definition(&self, ids: &[Option<&str>], init: ExpressionNode<'ast>) -> StatementNode<'ast> {
+        let ids = ids.iter().map(|id| id.map(|id| self.identifier(id)));
+
+        self.node(InferredDefinitionStatement {
+            ids: List::from_iter(self.arena, ids),
+            init,
+        })
+    }
+
+    /// Expression to be used in the initialization of a `for` loop.
+    pub fn simple_statement<I>(&self, statement: I) -> SimpleStatementNode<'ast>
+    where
+        I: Into<SimpleStatement<'ast>>,
+    {
+        self.node(statement)
+    }
+
+    /// `_;`, only available in modifiers.
+    pub fn placeholder(&self) -> StatementNode<'ast> {
+        self.node(Placeholder)
+    }
+
+    pub fn break_statement(&self) -> StatementNode<'ast> {
+        self.node(BreakStatement)
+    }
+
+    pub fn continue_statement(&self) -> StatementNode<'ast> {
+        self.node(ContinueStatement)
+    }
+
+    pub fn throw_statement(&self) -> StatementNode<'ast> {
+        self.node(ThrowStatement)
+    }
+
+    pub fn inline_assembly<I>(&self, items: I) -> StatementNode<'ast>
+    where
+        I: IntoIterator<Item = AssemblyItemNode<'ast>>,
+    {
+        self.node(InlineAssemblyStatement {
+            string: None,
+            block: self.node(InlineAssemblyBlock {
+                items: self.list(items),
+            }),
+        })
+    }
+
+    // Inline assembly
+
+    pub fn assembly_identifier(&self, name: &str) -> AssemblyItemNode<'ast> {
+        self.node(self.str(name))
+    }
+
+    pub fn assembly_block<I>(&self, items: I) -> AssemblyItemNode<'ast>
+    where
+        I: IntoIterator<Item = AssemblyItemNode<'ast>>,
+    {
+        self.node(InlineAssemblyBlock {
+            items: self.list(items),
+        })
+    }
+
+    /// `name(arguments...)`
+    pub fn assembly_call<I>(&self, name: &str, arguments: I) -> FunctionalAssemblyExpressionNode<'ast>
+    where
+        I: IntoIterator<Item = AssemblyItemNode<'ast>>,
+    {
+        self.node(FunctionalAssemblyExpression {
+            id: self.identifier(name),
+            arguments: self.list(arguments),
+        })
+    }
+
+    /// `name := init`
+    pub fn assembly_assignment(&self, name: &str, init: FunctionalAssemblyExpressionNode<'ast>) -> AssemblyItemNode<'ast> {
+        self.node(AssemblyAssignment {
+            id: self.identifier(name),
+            init,
+        })
+    }
+
+    /// `let name := init`
+    pub fn assembly_local_binding(&self, name: &str, init: FunctionalAssemblyExpressionNode<'ast>) -> AssemblyItemNode<'ast> {
+        self.node(AssemblyLocalBinding {
+            id: self.identifier(name),
+            init,
+        })
+    }
+
+    pub fn assembly_label(&self, name: &str) -> AssemblyItemNode<'ast> {
+        self.node(AssemblyLabel {
+            id: self.identifier(name),
+        })
+    }
+
+    // Expressions
+
+    pub fn this(&self) -> ExpressionNode<'ast> {
+        self.node(ThisExpression)
+    }
+
+    pub fn identifier_expression(&self, name: &str) -> ExpressionNode<'ast> {
+        self.node(self.str(name))
+    }
+
+    pub fn bool(&self, value: bool) -> ExpressionNode<'ast> {
+        self.node(Primitive::Bool(value))
+    }
+
+    /// Integer or rational number, depending on whether the value has a `.`
+    /// or an exponent, as it would be written in the source.
+    pub fn number(&self, value: &str, unit: NumberUnit) -> ExpressionNode<'ast> {
+        let rational = value.bytes().any(|byte| byte == b'.' || byte == b'e' || byte == b'E');
+
+        self.node(match rational {
+            true  => Primitive::RationalNumber(self.str(value), unit),
+            false => Primitive::IntegerNumber(self.str(value), unit),
+        })
+    }
+
+    /// Hex number, with the `0x` prefix.
+    pub fn hex_number(&self, value: &str) -> ExpressionNode<'ast> {
+        self.node(Primitive::HexNumber(self.str(value)))
+    }
+
+    /// String of the value, quoted and escaped.
+    pub fn string(&self, value: &str) -> ExpressionNode<'ast> {
+        self.node(Primitive::String(self.quote(value)))
+    }
+
+    pub fn elementary_expression(&self, elementary: ElementaryTypeName) -> ExpressionNode<'ast> {
+        self.node(elementary)
+    }
+
+    pub fn prefix(&self, operator: PrefixOperator, operand: ExpressionNode<'ast>) -> ExpressionNode<'ast> {
+        self.node(PrefixExpression {
+            operator: self.node(operator),
+            operand,
+        })
+    }
+
+    pub fn postfix(&self, operand: ExpressionNode<'ast>, operator: PostfixOperator) -> ExpressionNode<'ast> {
+        self.node(PostfixExpression {
+            operand,
+            operator: self.node(operator),
+        })
+    }
+
+    pub fn binary(
+        &self,
+        left: ExpressionNode<'ast>,
+        operator: BinaryOperator,
+        right: ExpressionNode<'ast>,
+    ) -> ExpressionNode<'ast> {
+        self.node(BinaryExpression {
+            left,
+            operator: self.node(operator),
+            right,
+        })
+    }
+
+    pub fn assignment(
+        &self,
+        left: ExpressionNode<'ast>,
+        operator: AssignmentOperator,
+        right: ExpressionNode<'ast>,
+    ) -> ExpressionNode<'ast> {
+        self.node(AssignmentExpression {
+            left,
+            operator: self.node(operator),
+            right,
+        })
+    }
+
+    pub fn tuple<I>(&self, expressions: I) -> ExpressionNode<'ast>
+    where
+        I: IntoIterator<Item = ExpressionNode<'ast>>,
+    {
+        self.node(TupleExpression {
+            expressions: self.list(expressions),
+        })
+    }
+
+    pub fn call<I>(&self, callee: ExpressionNode<'ast>, arguments: I) -> ExpressionNode<'ast>
+    where
+        I: IntoIterator<Item = ExpressionNode<'ast>>,
+    {
+        self.node(CallExpression {
+            callee,
+            arguments: self.list(arguments),
+        })
+    }
+
+    pub fn member(&self, object: ExpressionNode<'ast>, member: &str) -> ExpressionNode<'ast> {
+        self.node(MemberAccessExpression {
+            object,
+            member: self.identifier(member),
+        })
+    }
+
+    pub fn index(&self, array: ExpressionNode<'ast>, index: Option<ExpressionNode<'ast>>) -> ExpressionNode<'ast> {
+        self.node(IndexAccessExpression {
+            array,
+            index,
+        })
+    }
+
+    pub fn conditional(
+        &self,
+        test: ExpressionNode<'ast>,
+        consequent: ExpressionNode<'ast>,
+        alternate: ExpressionNode<'ast>,
+    ) -> ExpressionNode<'ast> {
+        self.node(ConditionalExpression {
+            test,
+            consequent,
+            alternate,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn build_program() {
+        let program = Program::build(|b| {
+            let balance = b.identifier_expression("balance");
+            let increment = b.assignment(balance, AssignmentOperator::Addition, b.number("1", NumberUnit::None));
+            let function = b.function(
+                Some("bump"),
+                b.list(None),
+                Some(FunctionVisibility::Public),
+                None,
+                b.list(None),
+                Some(b.block(vec![b.expression_statement(increment)])),
+            );
+            let contract = b.at(10, 20).contract("Counter", &["Base"], vec![function]);
+
+            b.list(vec![b.pragma("solidity ^0.4.17"), contract])
+        });
+
+        let units: Vec<_> = program.body().iter().cloned().collect();
+
+        assert_eq!(units.len(), 2);
+        assert_eq!((units[0].start, units[0].end), (0, 0));
+        assert_eq!((units[1].start, units[1].end), (10, 20));
+
+        let contract = match units[1].value {
+            SourceUnit::ContractDefinition(contract) => contract,
+            _ => panic!("Expected a contract"),
+        };
+
+        assert_eq!(contract.name.value, "Counter");
+        assert_eq!(contract.inherits.iter().map(|id| id.value).collect::<Vec<_>>(), vec!["Base"]);
+        assert_eq!(contract.body.iter().count(), 1);
+    }
+
+    #[test]
+    fn literals() {
+        let arena = Arena::new();
+        let b = AstBuilder::new(&arena);
+
+        assert_eq!(b.string("a \"b\"\n").value, Expression::PrimitiveExpression(Primitive::String(r#""a \"b\"\n""#)));
+        assert_eq!(b.number("1.5", NumberUnit::None).value, Expression::PrimitiveExpression(Primitive::RationalNumber("1.5", NumberUnit::None)));
+        assert_eq!(b.number("2", NumberUnit::Ether(EtherUnit::Ether)).value, Expression::PrimitiveExpression(Primitive::IntegerNumber("2", NumberUnit::Ether(EtherUnit::Ether))));
+        assert_eq!(b.string_literal("./a.sol").value, r#""./a.sol""#);
+
+        match b.inferred_definition(&[Some("a"), None, Some("b")], b.this()).value {
+            Statement::InferredDefinitionStatement(definition) => {
+                assert_eq!(definition.ids.iter().map(|id| id.map(|id| id.value)).collect::<Vec<_>>(), vec![Some("a"), None, Some("b")]);
+            },
+            _ => panic!("Expected an inferred definition"),
+        }
+    }
+}
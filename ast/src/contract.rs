@@ -2,6 +2,7 @@ use {*};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct ContractDefinition<'ast> {
+    pub doc: Option<DocCommentNode<'ast>>,
     pub name: IdentifierNode<'ast>,
     pub inherits: IdentifierList<'ast>,
     pub body: ContractPartList<'ast>,
@@ -16,10 +17,30 @@ pub enum ContractPart<'ast> {
     FunctionDefinition(FunctionDefinition<'ast>),
     EventDefinition(EventDefinition<'ast>),
     EnumDefinition(EnumDefinition<'ast>),
+
+    /// Source that failed to parse, only produced when parsing with error recovery
+    Error,
+}
+
+impl<'ast> ContractPart<'ast> {
+    /// Name of the variant.
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            ContractPart::StateVariableDeclaration(_) => "StateVariableDeclaration",
+            ContractPart::UsingForDeclaration(_)      => "UsingForDeclaration",
+            ContractPart::StructDefinition(_)         => "StructDefinition",
+            ContractPart::ModifierDefinition(_)       => "ModifierDefinition",
+            ContractPart::FunctionDefinition(_)       => "FunctionDefinition",
+            ContractPart::EventDefinition(_)          => "EventDefinition",
+            ContractPart::EnumDefinition(_)           => "EnumDefinition",
+            ContractPart::Error                       => "Error",
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct StateVariableDeclaration<'ast> {
+    pub doc: Option<DocCommentNode<'ast>>,
     pub type_name: TypeNameNode<'ast>,
     pub visibility: Option<Node<'ast, StateVariableVisibility>>,
     pub constant: Option<Node<'ast, Flag>>,
@@ -34,6 +55,18 @@ pub enum StateVariableVisibility {
     Private,
 }
 
+impl StateVariableVisibility {
+    /// Keyword of this visibility in Solidity source.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            StateVariableVisibility::Public   => "public",
+            StateVariableVisibility::Internal => "internal",
+            StateVariableVisibility::Private  => "private",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct UsingForDeclaration<'ast> {
     pub id: IdentifierNode<'ast>,
@@ -48,6 +81,7 @@ pub struct StructDefinition<'ast> {
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct ModifierDefinition<'ast> {
+    pub doc: Option<DocCommentNode<'ast>>,
     pub name: IdentifierNode<'ast>,
     pub params: ParameterList<'ast>,
     pub block: BlockNode<'ast>,
@@ -55,6 +89,7 @@ pub struct ModifierDefinition<'ast> {
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct EventDefinition<'ast> {
+    pub doc: Option<DocCommentNode<'ast>>,
     pub anonymous: Option<FlagNode<'ast>>,
     pub name: IdentifierNode<'ast>,
     pub params: IndexedParameterList<'ast>,
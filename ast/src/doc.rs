@@ -0,0 +1,44 @@
+use {*};
+
+/// NatSpec documentation, either a run of `///` line comments or
+/// a single `/** */` block comment, preceding a definition.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DocComment<'ast> {
+    pub tags: DocTagList<'ast>,
+}
+
+/// A single NatSpec tag. Text that doesn't follow any tag is
+/// treated as `@notice`, same as solc does it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DocTag<'ast> {
+    pub kind: DocTagKind<'ast>,
+
+    /// Text of the tag with comment markers stripped, multiple
+    /// lines joined by `\n`.
+    pub text: &'ast str,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DocTagKind<'ast> {
+    Title,
+    Author,
+    Notice,
+    Dev,
+
+    /// `@param` with the name of the parameter
+    Param(Identifier<'ast>),
+    Return,
+
+    /// `@inheritdoc` with the name of the base contract
+    Inheritdoc(Identifier<'ast>),
+
+    /// `@custom:name` with the name following the colon
+    Custom(&'ast str),
+
+    /// Any other tag, with its name
+    Unknown(&'ast str),
+}
+
+pub type DocCommentNode<'ast> = Node<'ast, DocComment<'ast>>;
+pub type DocTagNode<'ast> = Node<'ast, DocTag<'ast>>;
+pub type DocTagList<'ast> = NodeList<'ast, DocTag<'ast>>;
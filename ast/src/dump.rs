@@ -0,0 +1,554 @@
+//! Indented dump of the AST for debugging and snapshot tests.
+//!
+//! Every node gets a line with its kind, span and key fields, indented
+//! by its depth, with its children following it:
+//!
+//! ```text
+//! ContractDefinition 0..45 name=Foo
+//!   FunctionDefinition 15..43 name=bar visibility=public
+//!     Block 39..43
+//! ```
+//!
+//! The kinds are the same as in `AstIndex`, plus `DocTag` for the tags of
+//! doc comments. Identifiers, operators, flags, visibility, mutability and
+//! storage locations are shown as fields of the node they belong to, instead
+//! of lines of their own.
+
+use std::fmt::{self, Write};
+use std::ptr;
+
+use {*};
+use visit::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DumpOptions<'a> {
+    /// Show spans as one-based `line:column-line:column` instead of byte
+    /// offsets, requires the `Program` to have its source.
+    pub line_col: bool,
+
+    /// Depth of the deepest nodes shown, zero shows only the top nodes.
+    pub max_depth: usize,
+
+    /// Show only the subtrees of the nodes of this kind, such as
+    /// `"FunctionDefinition"`. The depth then counts from them.
+    pub subtree: Option<&'a str>,
+}
+
+impl<'a> Default for DumpOptions<'a> {
+    fn default() -> Self {
+        DumpOptions {
+            line_col: false,
+            max_depth: usize::MAX,
+            subtree: None,
+        }
+    }
+}
+
+impl<'ast> Program<'ast> {
+    /// Dump the AST as an indented tree, see `dump` module.
+    pub fn dump(&self) -> String {
+        self.dump_with_options(DumpOptions::default())
+    }
+
+    /// Same as `dump`, with non-default `DumpOptions`.
+    pub fn dump_with_options(&self, options: DumpOptions) -> String {
+        let source = self.source();
+        let lines = match options.line_col && !source.is_empty() {
+            true  => Some(LineIndex::new(source)),
+            false => None,
+        };
+        let mut out = String::new();
+
+        // Depth of the root of the subtree being dumped
+        let mut root = None;
+
+        for entry in entries(self) {
+            if matches!(root, Some(root) if entry.depth <= root) {
+                root = None;
+            }
+
+            let in_subtree = match options.subtree {
+                Some(subtree) => subtree == entry.kind,
+                None          => true,
+            };
+
+            if root.is_none() && in_subtree {
+                root = Some(entry.depth);
+            }
+
+            let depth = match root {
+                Some(root) => entry.depth - root,
+                None       => continue,
+            };
+
+            if depth > options.max_depth {
+                continue;
+            }
+
+            for _ in 0..depth {
+                out.push_str("  ");
+            }
+
+            out.push_str(entry.kind);
+
+            let _ = match lines {
+                Some(ref lines) => {
+                    let start = lines.line_col_chars(entry.start.saturating_sub(self.offset));
+                    let end = lines.line_col_chars(entry.end.saturating_sub(self.offset));
+
+                    write!(out, " {}:{}-{}:{}", start.line + 1, start.column + 1, end.line + 1, end.column + 1)
+                },
+                None => write!(out, " {}..{}", entry.start, entry.end),
+            };
+
+            for &(name, ref value) in &entry.fields {
+                let _ = match *value {
+                    Value::Flag             => write!(out, " {}", name),
+                    Value::Text(ref text)   => write!(out, " {}={}", name, text),
+                    Value::Quoted(ref text) => write!(out, " {}={:?}", name, text),
+                    Value::List(ref items)  => write!(out, " {}=[{}]", name, items.join(", ")),
+                };
+            }
+
+            out.push('\n');
+        }
+
+        out
+    }
+}
+
+/// A node of the AST as shown by `dump` and matched by `Query`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    /// Same as the kinds of `AstIndex`.
+    pub kind: &'static str,
+    pub start: u32,
+    pub end: u32,
+    pub depth: usize,
+    pub fields: Vec<(&'static str, Value)>,
+}
+
+impl Entry {
+    pub fn field(&self, name: &str) -> Option<&Value> {
+        self.fields.iter().find(|&&(field, _)| field == name).map(|(_, value)| value)
+    }
+}
+
+/// Value of a field of an `Entry`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Flag,
+    Text(String),
+    /// Text shown quoted in the dump, such as documentation.
+    Quoted(String),
+    List(Vec<String>),
+}
+
+/// Walk the `Program` and get the entries of all its nodes, in pre-order.
+pub fn entries<'ast>(program: &Program<'ast>) -> Vec<Entry> {
+    let mut collector = Collector {
+        entries: Vec::new(),
+        depth: 0,
+        returns: NodeList::empty(),
+    };
+
+    collector.visit_program(program);
+    collector.entries
+}
+
+/// Fields of an `Entry` being built.
+#[derive(Default)]
+struct Fields(Vec<(&'static str, Value)>);
+
+impl Fields {
+    fn add<V: fmt::Display>(&mut self, name: &'static str, value: V) -> &mut Self {
+        self.0.push((name, Value::Text(value.to_string())));
+
+        self
+    }
+
+    fn opt<V: fmt::Display>(&mut self, name: &'static str, value: Option<V>) -> &mut Self {
+        if let Some(value) = value {
+            self.add(name, value);
+        }
+
+        self
+    }
+
+    fn quoted(&mut self, name: &'static str, value: &str) -> &mut Self {
+        self.0.push((name, Value::Quoted(value.into())));
+
+        self
+    }
+
+    fn flag(&mut self, name: &'static str, set: bool) -> &mut Self {
+        if set {
+            self.0.push((name, Value::Flag));
+        }
+
+        self
+    }
+
+    fn identifiers<'ast, I>(&mut self, name: &'static str, identifiers: I) -> &mut Self
+    where
+        I: IntoIterator<Item = Option<Identifier<'ast>>>,
+    {
+        let names: Vec<_> = identifiers.into_iter().map(|id| id.unwrap_or("_").into()).collect();
+
+        if !names.is_empty() {
+            self.0.push((name, Value::List(names)));
+        }
+
+        self
+    }
+}
+
+fn name<'ast>(id: &Option<IdentifierNode<'ast>>) -> Option<Identifier<'ast>> {
+    id.map(|id| id.value)
+}
+
+/// Collects the entries of the nodes while visiting a `Program`.
+struct Collector<'ast> {
+    entries: Vec<Entry>,
+
+    /// Depth of the next entry
+    depth: usize,
+
+    /// Return parameters of the function being visited
+    returns: ParameterList<'ast>,
+}
+
+impl<'ast> Collector<'ast> {
+    /// Push the entry and the entries of the children pushed by `walk`.
+    fn push<F>(&mut self, kind: &'static str, start: u32, end: u32, fields: Fields, walk: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        self.entries.push(Entry {
+            kind,
+            start,
+            end,
+            depth: self.depth,
+            fields: fields.0,
+        });
+
+        self.depth += 1;
+        walk(self);
+        self.depth -= 1;
+    }
+}
+
+impl<'ast> Visit<'ast> for Collector<'ast> {
+    fn visit_source_unit(&mut self, unit: &SourceUnitNode<'ast>) {
+        let mut fields = Fields::default();
+
+        match unit.value {
+            SourceUnit::PragmaDirective(ref pragma) => {
+                fields.quoted("version", pragma.version);
+            },
+            SourceUnit::ImportDirective(ImportDirective::Global { ref source, ref alias }) => {
+                fields.add("source", source.value).opt("alias", name(alias));
+            },
+            SourceUnit::ImportDirective(ImportDirective::From { ref symbol, ref alias, ref source }) => {
+                fields.add("symbol", name(symbol).unwrap_or("*")).opt("alias", name(alias)).add("source", source.value);
+            },
+            SourceUnit::ImportDirective(ImportDirective::ManyFrom { ref source, .. }) => {
+                fields.add("source", source.value);
+            },
+            SourceUnit::ContractDefinition(ref contract) => {
+                fields.add("name", contract.name.value)
+                      .identifiers("inherits", contract.inherits.iter().map(|id| Some(id.value)));
+            },
+            SourceUnit::Error => {},
+        }
+
+        self.push(unit.value.kind(), unit.start, unit.end, fields, |d| walk_source_unit(d, unit));
+    }
+
+    fn visit_import(&mut self, import: &Import<'ast>, start: u32, end: u32) {
+        let mut fields = Fields::default();
+
+        fields.add("symbol", import.symbol.value).opt("alias", name(&import.alias));
+
+        self.push("Import", start, end, fields, |d| walk_import(d, import));
+    }
+
+    fn visit_contract_part(&mut self, part: &ContractPartNode<'ast>) {
+        let mut fields = Fields::default();
+
+        match part.value {
+            ContractPart::StateVariableDeclaration(ref var) => {
+                fields.add("name", var.name.value)
+                      .opt("visibility", var.visibility.map(|visibility| visibility.value.as_str()))
+                      .flag("constant", var.constant.is_some());
+            },
+            ContractPart::UsingForDeclaration(ref using) => {
+                fields.add("library", using.id.value);
+            },
+            ContractPart::StructDefinition(ref def) => {
+                fields.add("name", def.name.value);
+            },
+            ContractPart::ModifierDefinition(ref def) => {
+                fields.add("name", def.name.value);
+            },
+            ContractPart::FunctionDefinition(ref def) => {
+                fields.opt("name", name(&def.name))
+                      .opt("visibility", def.visibility.map(|visibility| visibility.value.as_str()))
+                      .opt("mutability", def.mutability.map(|mutability| mutability.value.as_str()));
+            },
+            ContractPart::EventDefinition(ref def) => {
+                fields.add("name", def.name.value).flag("anonymous", def.anonymous.is_some());
+            },
+            ContractPart::EnumDefinition(ref def) => {
+                fields.add("name", def.name.value)
+                      .identifiers("variants", def.variants.iter().map(|id| Some(id.value)));
+            },
+            ContractPart::Error => {},
+        }
+
+        self.push(part.value.kind(), part.start, part.end, fields, |d| walk_contract_part(d, part));
+    }
+
+    fn visit_function_definition(&mut self, def: &FunctionDefinition<'ast>, _start: u32, _end: u32) {
+        self.returns = def.returns;
+
+        walk_function_definition(self, def);
+    }
+
+    fn visit_indexed_parameter(&mut self, param: &IndexedParameter<'ast>, start: u32, end: u32) {
+        let mut fields = Fields::default();
+
+        fields.opt("name", name(&param.name)).flag("indexed", param.indexed.is_some());
+
+        self.push("IndexedParameter", start, end, fields, |d| walk_indexed_parameter(d, param));
+    }
+
+    fn visit_parameter(&mut self, param: &Parameter<'ast>, start: u32, end: u32) {
+        let mut fields = Fields::default();
+        let returns = self.returns.iter().any(|returns| ptr::eq(&returns.value, param));
+
+        fields.opt("name", name(&param.name)).flag("returns", returns);
+
+        self.push("Parameter", start, end, fields, |d| walk_parameter(d, param));
+    }
+
+    fn visit_modifier_invocation(&mut self, modifier: &ModifierInvocation<'ast>, start: u32, end: u32) {
+        let mut fields = Fields::default();
+
+        fields.add("name", modifier.id.value);
+
+        self.push("ModifierInvocation", start, end, fields, |d| walk_modifier_invocation(d, modifier));
+    }
+
+    fn visit_doc_comment(&mut self, doc: &DocComment<'ast>, start: u32, end: u32) {
+        self.push("DocComment", start, end, Fields::default(), |d| walk_doc_comment(d, doc));
+    }
+
+    fn visit_doc_tag(&mut self, tag: &DocTag<'ast>, start: u32, end: u32) {
+        let mut fields = Fields::default();
+
+        match tag.kind {
+            DocTagKind::Title            => fields.add("tag", "@title"),
+            DocTagKind::Author           => fields.add("tag", "@author"),
+            DocTagKind::Notice           => fields.add("tag", "@notice"),
+            DocTagKind::Dev              => fields.add("tag", "@dev"),
+            DocTagKind::Return           => fields.add("tag", "@return"),
+            DocTagKind::Param(name)      => fields.add("tag", "@param").add("name", name),
+            DocTagKind::Inheritdoc(name) => fields.add("tag", "@inheritdoc").add("name", name),
+            DocTagKind::Custom(name)     => fields.add("tag", format_args!("@custom:{}", name)),
+            DocTagKind::Unknown(name)    => fields.add("tag", format_args!("@{}", name)),
+        };
+
+        fields.quoted("text", tag.text);
+
+        self.push("DocTag", start, end, fields, |_| {});
+    }
+
+    fn visit_type_name(&mut self, type_name: &TypeNameNode<'ast>) {
+        let mut fields = Fields::default();
+
+        match type_name.value {
+            TypeName::ElementaryTypeName(elementary) => { fields.add("type", elementary); },
+            TypeName::UserDefinedTypeName(name)      => { fields.add("name", name); },
+            TypeName::Mapping(ref mapping)           => { fields.add("from", mapping.from.value); },
+            TypeName::ArrayTypeName                  |
+            TypeName::FunctionTypeName               => {},
+        }
+
+        self.push(type_name.value.kind(), type_name.start, type_name.end, fields, |d| walk_type_name(d, type_name));
+    }
+
+    fn visit_variable_declaration(&mut self, declaration: &VariableDeclaration<'ast>, start: u32, end: u32) {
+        let mut fields = Fields::default();
+
+        fields.add("name", declaration.id.value)
+              .opt("location", declaration.location.map(|location| location.value.as_str()));
+
+        self.push("VariableDeclaration", start, end, fields, |d| walk_variable_declaration(d, declaration));
+    }
+
+    fn visit_statement(&mut self, statement: &StatementNode<'ast>) {
+        let mut fields = Fields::default();
+
+        match statement.value {
+            Statement::InlineAssemblyStatement(ref stmt) => {
+                fields.opt("string", stmt.string.map(|string| string.value));
+            },
+            Statement::InferredDefinitionStatement(ref stmt) => {
+                fields.identifiers("ids", stmt.ids.iter().map(name));
+            },
+            _ => {},
+        }
+
+        self.push(statement.value.kind(), statement.start, statement.end, fields, |d| match statement.value {
+            // The statement node is the block itself
+            Statement::BlockStatement(ref block) => walk_block(d, block),
+            _                                    => walk_statement(d, statement),
+        });
+    }
+
+    fn visit_simple_statement(&mut self, statement: &SimpleStatementNode<'ast>) {
+        let mut fields = Fields::default();
+
+        if let SimpleStatement::InferredDefinitionStatement(ref stmt) = statement.value {
+            fields.identifiers("ids", stmt.ids.iter().map(name));
+        }
+
+        self.push(statement.value.kind(), statement.start, statement.end, fields, |d| walk_simple_statement(d, statement));
+    }
+
+    fn visit_block(&mut self, block: &Block<'ast>, start: u32, end: u32) {
+        self.push("Block", start, end, Fields::default(), |d| walk_block(d, block));
+    }
+
+    fn visit_expression(&mut self, expression: &ExpressionNode<'ast>) {
+        let mut fields = Fields::default();
+
+        match expression.value {
+            Expression::IdentifierExpression(name)       => { fields.add("name", name); },
+            Expression::PrimitiveExpression(primitive)   => { primitive_fields(&mut fields, primitive); },
+            Expression::ElementaryTypeExpression(ty)     => { fields.add("type", ty); },
+            Expression::PrefixExpression(ref expr)       => { fields.add("operator", expr.operator.value.as_str()); },
+            Expression::PostfixExpression(ref expr)      => { fields.add("operator", expr.operator.value.as_str()); },
+            Expression::BinaryExpression(ref expr)       => { fields.add("operator", expr.operator.value.as_str()); },
+            Expression::AssignmentExpression(ref expr)   => { fields.add("operator", expr.operator.value.as_str()); },
+            Expression::MemberAccessExpression(ref expr) => { fields.add("member", expr.member.value); },
+            _                                            => {},
+        }
+
+        self.push(expression.value.kind(), expression.start, expression.end, fields, |d| walk_expression(d, expression));
+    }
+
+    fn visit_inline_assembly_block(&mut self, block: &InlineAssemblyBlock<'ast>, start: u32, end: u32) {
+        self.push("InlineAssemblyBlock", start, end, Fields::default(), |d| walk_inline_assembly_block(d, block));
+    }
+
+    fn visit_assembly_item(&mut self, item: &AssemblyItemNode<'ast>) {
+        let mut fields = Fields::default();
+
+        match item.value {
+            AssemblyItem::Identifier(name)                       => { fields.add("name", name); },
+            AssemblyItem::NumberLiteral(primitive)               => { primitive_fields(&mut fields, primitive); },
+            AssemblyItem::FunctionalAssemblyExpression(ref expr) => { fields.add("name", expr.id.value); },
+            AssemblyItem::AssemblyLocalBinding(ref binding)      => { fields.add("name", binding.id.value); },
+            AssemblyItem::AssemblyAssignment(ref assignment)     => { fields.add("name", assignment.id.value); },
+            AssemblyItem::AssemblyLabel(ref label)               => { fields.add("name", label.id.value); },
+            _                                                    => {},
+        }
+
+        self.push(item.value.kind(), item.start, item.end, fields, |d| match item.value {
+            // Likewise, the item node is the expression or block itself
+            AssemblyItem::FunctionalAssemblyExpression(ref expr) => walk_functional_assembly_expression(d, expr),
+            AssemblyItem::InlineAssemblyBlock(ref block)         => walk_inline_assembly_block(d, block),
+            _                                                    => walk_assembly_item(d, item),
+        });
+    }
+
+    fn visit_functional_assembly_expression(&mut self, expr: &FunctionalAssemblyExpression<'ast>, start: u32, end: u32) {
+        let mut fields = Fields::default();
+
+        fields.add("name", expr.id.value);
+
+        self.push("FunctionalAssemblyExpression", start, end, fields, |d| walk_functional_assembly_expression(d, expr));
+    }
+}
+
+fn primitive_fields<'f>(fields: &'f mut Fields, primitive: Primitive) -> &'f mut Fields {
+    let (value, unit) = match primitive {
+        Primitive::Bool(true)                  => ("true", NumberUnit::None),
+        Primitive::Bool(false)                 => ("false", NumberUnit::None),
+        Primitive::HexNumber(value)            => (value, NumberUnit::None),
+        Primitive::IntegerNumber(value, unit)  => (value, unit),
+        Primitive::RationalNumber(value, unit) => (value, unit),
+        Primitive::String(value)               => (value, NumberUnit::None),
+    };
+
+    fields.add("value", value).opt("unit", unit.as_str())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn program<'ast>() -> Program<'ast> {
+        Program::build(|b| {
+            let sum = b.at(60, 65).binary(
+                b.at(60, 61).identifier_expression("a"),
+                BinaryOperator::Addition,
+                b.at(64, 65).number("1", NumberUnit::Ether(EtherUnit::Ether)),
+            );
+            let function = b.at(15, 70).function(
+                Some("bar"),
+                b.list(Some(b.at(28, 34).parameter(b.at(28, 32).elementary(ElementaryTypeName::Uint(32)), Some("a")))),
+                Some(FunctionVisibility::Public),
+                None,
+                b.list(None),
+                Some(b.at(50, 70).block(Some(b.at(52, 66).return_statement(Some(sum))))),
+            );
+
+            b.list(Some(b.at(0, 72).contract("Foo", &["Bar", "Baz"], Some(function))))
+        })
+    }
+
+    #[test]
+    fn dump() {
+        assert_eq!(program().dump(), "\
+ContractDefinition 0..72 name=Foo inherits=[Bar, Baz]
+  FunctionDefinition 15..70 name=bar visibility=public
+    Parameter 28..34 name=a
+      ElementaryTypeName 28..32 type=uint256
+    Block 50..70
+      ReturnStatement 52..66
+        BinaryExpression 60..65 operator=+
+          IdentifierExpression 60..61 name=a
+          PrimitiveExpression 64..65 value=1 unit=ether
+");
+    }
+
+    #[test]
+    fn filters() {
+        let program = program();
+
+        assert_eq!(program.dump_with_options(DumpOptions {
+            max_depth: 1,
+            ..DumpOptions::default()
+        }), "\
+ContractDefinition 0..72 name=Foo inherits=[Bar, Baz]
+  FunctionDefinition 15..70 name=bar visibility=public
+");
+
+        assert_eq!(program.dump_with_options(DumpOptions {
+            max_depth: 1,
+            subtree: Some("ReturnStatement"),
+            ..DumpOptions::default()
+        }), "\
+ReturnStatement 52..66
+  BinaryExpression 60..65 operator=+
+");
+
+        assert_eq!(program.dump_with_options(DumpOptions {
+            subtree: Some("EventDefinition"),
+            ..DumpOptions::default()
+        }), "");
+    }
+}
@@ -17,16 +17,36 @@ pub enum Expression<'ast> {
     ElementaryTypeExpression(ElementaryTypeName),
 }
 
+impl<'ast> Expression<'ast> {
+    /// Name of the variant.
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            Expression::ThisExpression              => "ThisExpression",
+            Expression::IdentifierExpression(_)     => "IdentifierExpression",
+            Expression::PrimitiveExpression(_)      => "PrimitiveExpression",
+            Expression::PrefixExpression(_)         => "PrefixExpression",
+            Expression::PostfixExpression(_)        => "PostfixExpression",
+            Expression::BinaryExpression(_)         => "BinaryExpression",
+            Expression::AssignmentExpression(_)     => "AssignmentExpression",
+            Expression::TupleExpression(_)          => "TupleExpression",
+            Expression::CallExpression(_)           => "CallExpression",
+            Expression::MemberAccessExpression(_)   => "MemberAccessExpression",
+            Expression::IndexAccessExpression(_)    => "IndexAccessExpression",
+            Expression::ConditionalExpression(_)    => "ConditionalExpression",
+            Expression::ElementaryTypeExpression(_) => "ElementaryTypeExpression",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Primitive<'ast> {
     Bool(bool),
     HexNumber(&'ast str),
     IntegerNumber(&'ast str, NumberUnit),
-    RationalNumber(&'ast str),
+    RationalNumber(&'ast str, NumberUnit),
     String(&'ast str),
 }
 
-// TODO: Exact units
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum NumberUnit {
     None,
@@ -34,6 +54,18 @@ pub enum NumberUnit {
     Time(TimeUnit),
 }
 
+impl NumberUnit {
+    /// Keyword of this unit in Solidity source, if any.
+    #[inline]
+    pub fn as_str(&self) -> Option<&'static str> {
+        match *self {
+            NumberUnit::None        => None,
+            NumberUnit::Ether(unit) => Some(unit.as_str()),
+            NumberUnit::Time(unit)  => Some(unit.as_str()),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum TimeUnit {
     Years,
@@ -45,14 +77,45 @@ pub enum TimeUnit {
     Seconds,
 }
 
+impl TimeUnit {
+    /// Keyword of this unit in Solidity source.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            TimeUnit::Years   => "years",
+            TimeUnit::Months  => "months",
+            TimeUnit::Weeks   => "weeks",
+            TimeUnit::Days    => "days",
+            TimeUnit::Hours   => "hours",
+            TimeUnit::Minutes => "minutes",
+            TimeUnit::Seconds => "seconds",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum EtherUnit {
     Ether,
     Finney,
     Szabo,
+    Gwei,
     Wei,
 }
 
+impl EtherUnit {
+    /// Keyword of this unit in Solidity source.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            EtherUnit::Ether  => "ether",
+            EtherUnit::Finney => "finney",
+            EtherUnit::Szabo  => "szabo",
+            EtherUnit::Gwei   => "gwei",
+            EtherUnit::Wei    => "wei",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum PrefixOperator {
     LogicalNot,
@@ -64,12 +127,39 @@ pub enum PrefixOperator {
     Minus,
 }
 
+impl PrefixOperator {
+    /// Operator as it appears in Solidity source.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            PrefixOperator::LogicalNot => "!",
+            PrefixOperator::BitNot     => "~",
+            PrefixOperator::Delete     => "delete",
+            PrefixOperator::Increment  => "++",
+            PrefixOperator::Decrement  => "--",
+            PrefixOperator::Plus       => "+",
+            PrefixOperator::Minus      => "-",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum PostfixOperator {
     Increment,
     Decrement,
 }
 
+impl PostfixOperator {
+    /// Operator as it appears in Solidity source.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            PostfixOperator::Increment => "++",
+            PostfixOperator::Decrement => "--",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum BinaryOperator {
     Multiplication,
@@ -93,6 +183,34 @@ pub enum BinaryOperator {
     LogicalOr,
 }
 
+impl BinaryOperator {
+    /// Operator as it appears in Solidity source.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            BinaryOperator::Multiplication => "*",
+            BinaryOperator::Division       => "/",
+            BinaryOperator::Remainder      => "%",
+            BinaryOperator::Exponent       => "**",
+            BinaryOperator::Addition       => "+",
+            BinaryOperator::Subtraction    => "-",
+            BinaryOperator::BitShiftLeft   => "<<",
+            BinaryOperator::BitShiftRight  => ">>",
+            BinaryOperator::Lesser         => "<",
+            BinaryOperator::LesserEquals   => "<=",
+            BinaryOperator::Greater        => ">",
+            BinaryOperator::GreaterEquals  => ">=",
+            BinaryOperator::Equality       => "==",
+            BinaryOperator::Inequality     => "!=",
+            BinaryOperator::BitAnd         => "&",
+            BinaryOperator::BitXor         => "^",
+            BinaryOperator::BitOr          => "|",
+            BinaryOperator::LogicalAnd     => "&&",
+            BinaryOperator::LogicalOr      => "||",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum AssignmentOperator {
     Plain,
@@ -108,6 +226,26 @@ pub enum AssignmentOperator {
     BitOr,
 }
 
+impl AssignmentOperator {
+    /// Operator as it appears in Solidity source.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            AssignmentOperator::Plain          => "=",
+            AssignmentOperator::Addition       => "+=",
+            AssignmentOperator::Subtraction    => "-=",
+            AssignmentOperator::Multiplication => "*=",
+            AssignmentOperator::Division       => "/=",
+            AssignmentOperator::Remainder      => "%=",
+            AssignmentOperator::BitShiftLeft   => "<<=",
+            AssignmentOperator::BitShiftRight  => ">>=",
+            AssignmentOperator::BitAnd         => "&=",
+            AssignmentOperator::BitXor         => "^=",
+            AssignmentOperator::BitOr          => "|=",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct PrefixExpression<'ast> {
     pub operator: Node<'ast, PrefixOperator>,
@@ -0,0 +1,48 @@
+//! Programs shared by the tests, built with the locations the parser
+//! would give them.
+
+use {*};
+
+/// `contract Foo { function bar(uint a) { if (a > 1) a += 1; } }`
+pub fn program<'ast>() -> Program<'ast> {
+    Program::build(|b| {
+        let one = |start, end| b.at(start, end).number("1", NumberUnit::None);
+
+        let test = b.at(42, 47).node(BinaryExpression {
+            left: b.at(42, 43).identifier_expression("a"),
+            operator: b.at(44, 45).node(BinaryOperator::Greater),
+            right: one(46, 47),
+        });
+
+        let assignment = b.at(49, 55).node(AssignmentExpression {
+            left: b.at(49, 50).identifier_expression("a"),
+            operator: b.at(51, 53).node(AssignmentOperator::Addition),
+            right: one(54, 55),
+        });
+
+        let statement = b.at(38, 56).if_statement(test, b.at(49, 56).expression_statement(assignment), None);
+
+        let param = b.at(28, 34).node(Parameter {
+            type_name: b.at(28, 32).elementary(ElementaryTypeName::Uint(32)),
+            name: Some(b.at(33, 34).identifier("a")),
+        });
+
+        let function = b.at(15, 58).node(FunctionDefinition {
+            doc: None,
+            name: Some(b.at(24, 27).identifier("bar")),
+            params: b.list(Some(param)),
+            visibility: None,
+            mutability: None,
+            modifiers: NodeList::empty(),
+            returns: NodeList::empty(),
+            block: Some(b.at(36, 58).block(Some(statement))),
+        });
+
+        b.list(Some(b.at(0, 60).node(ContractDefinition {
+            doc: None,
+            name: b.at(9, 12).identifier("Foo"),
+            inherits: NodeList::empty(),
+            body: b.list(Some(function)),
+        })))
+    })
+}
@@ -2,6 +2,7 @@ use {*};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct FunctionDefinition<'ast> {
+    pub doc: Option<DocCommentNode<'ast>>,
     pub name: Option<IdentifierNode<'ast>>,
     pub params: ParameterList<'ast>,
     pub visibility: Option<Node<'ast, FunctionVisibility>>,
@@ -19,6 +20,19 @@ pub enum FunctionVisibility {
     Private,
 }
 
+impl FunctionVisibility {
+    /// Keyword of this visibility in Solidity source.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            FunctionVisibility::External => "external",
+            FunctionVisibility::Public   => "public",
+            FunctionVisibility::Internal => "internal",
+            FunctionVisibility::Private  => "private",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum StateMutability {
     Pure,
@@ -27,6 +41,19 @@ pub enum StateMutability {
     Payable,
 }
 
+impl StateMutability {
+    /// Keyword of this state mutability in Solidity source.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            StateMutability::Pure     => "pure",
+            StateMutability::Constant => "constant",
+            StateMutability::View     => "view",
+            StateMutability::Payable  => "payable",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct ModifierInvocation<'ast> {
     pub id: IdentifierNode<'ast>,
@@ -0,0 +1,466 @@
+//! Identity and parents of the nodes of an AST.
+//!
+//! `AstIndex` walks a `Program` once with `Visit` and assigns each `Node` in
+//! it a dense `NodeId`, in the order of a pre-order walk, along with its kind,
+//! span and the id of its parent. Since the ids are dense, side tables keyed
+//! by node can be plain `Vec`s indexed with `NodeId::index`.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::mem;
+
+use {*};
+use visit::*;
+
+/// Identifier of a `Node` within an `AstIndex`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(u32);
+
+impl NodeId {
+    #[inline]
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A node of the AST as recorded by the `AstIndex`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NodeEntry {
+    /// Name of the AST type or enum variant of the node,
+    /// such as `"ContractDefinition"` or `"BinaryExpression"`,
+    /// see `Statement::kind` and the like.
+    pub kind: &'static str,
+    pub start: u32,
+    pub end: u32,
+    pub parent: Option<NodeId>,
+}
+
+/// Ids and parents of all nodes of a `Program`, see module docs.
+#[derive(Clone, Debug, Default)]
+pub struct AstIndex {
+    entries: Vec<NodeEntry>,
+
+    /// Ids keyed by the address of the node, see `key`.
+    ids: HashMap<usize, NodeId>,
+
+    /// All ids sorted by the start of their nodes.
+    by_start: Vec<NodeId>,
+}
+
+impl AstIndex {
+    pub fn new(program: &Program) -> Self {
+        let mut indexer = Indexer::default();
+
+        indexer.visit_program(program);
+
+        let mut index = indexer.index;
+        let mut by_start: Vec<_> = (0..index.entries.len() as u32).map(NodeId).collect();
+
+        // Stable, so nodes starting at the same offset stay in pre-order
+        by_start.sort_by_key(|&id| index.get(id).start);
+        index.by_start = by_start;
+        index
+    }
+
+    /// Get the id of a node of the indexed `Program`.
+    #[inline]
+    pub fn id<T>(&self, node: &Node<T>) -> Option<NodeId> {
+        self.ids.get(&key(node)).cloned()
+    }
+
+    #[inline]
+    pub fn get(&self, id: NodeId) -> &NodeEntry {
+        &self.entries[id.index()]
+    }
+
+    #[inline]
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.get(id).parent
+    }
+
+    /// Iterate over the parent of the node, the parent of the parent and so on.
+    pub fn ancestors<'a>(&'a self, id: NodeId) -> impl Iterator<Item = NodeId> + 'a {
+        let mut next = self.parent(id);
+
+        ::std::iter::from_fn(move || {
+            let id = next?;
+
+            next = self.parent(id);
+
+            Some(id)
+        })
+    }
+
+    /// Iterate over all nodes in the order of their ids.
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = (NodeId, &'a NodeEntry)> + 'a {
+        self.entries.iter().enumerate().map(|(index, entry)| (NodeId(index as u32), entry))
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the innermost node containing the byte offset.
+    pub fn node_at(&self, offset: u32) -> Option<NodeId> {
+        let position = self.by_start
+            .binary_search_by(|&id| match self.get(id).start <= offset {
+                true  => Ordering::Less,
+                false => Ordering::Greater,
+            })
+            .unwrap_or_else(|position| position);
+
+        let last = *self.by_start.get(position.checked_sub(1)?)?;
+
+        // Nodes are nested within their parents, so the nodes containing the
+        // offset are the last node starting before it and its ancestors.
+        Some(last).into_iter()
+            .chain(self.ancestors(last))
+            .find(|&id| offset < self.get(id).end)
+    }
+
+    /// Get the innermost node containing the byte offset
+    /// for which `filter` returns `true`.
+    pub fn enclosing<F>(&self, offset: u32, filter: F) -> Option<NodeId>
+    where
+        F: Fn(&NodeEntry) -> bool,
+    {
+        let id = self.node_at(offset)?;
+
+        Some(id).into_iter()
+            .chain(self.ancestors(id))
+            .find(|&id| filter(self.get(id)))
+    }
+
+    pub fn enclosing_contract(&self, offset: u32) -> Option<NodeId> {
+        self.enclosing(offset, |entry| entry.kind == "ContractDefinition")
+    }
+
+    pub fn enclosing_function(&self, offset: u32) -> Option<NodeId> {
+        self.enclosing(offset, |entry| entry.kind == "FunctionDefinition")
+    }
+
+    /// Get the innermost statement containing the byte offset,
+    /// including the initialization of `for` loops.
+    pub fn enclosing_statement(&self, offset: u32) -> Option<NodeId> {
+        self.enclosing(offset, |entry| is_statement(entry.kind))
+    }
+}
+
+/// Pushes the entries of an `AstIndex` while visiting a `Program`.
+#[derive(Default)]
+struct Indexer {
+    index: AstIndex,
+
+    /// Parent of the nodes being pushed.
+    parent: Option<NodeId>,
+}
+
+impl Indexer {
+    fn push(&mut self, key: usize, kind: &'static str, start: u32, end: u32) -> NodeId {
+        let index = &mut self.index;
+        let id = NodeId(index.entries.len() as u32);
+
+        index.entries.push(NodeEntry {
+            kind,
+            start,
+            end,
+            parent: self.parent,
+        });
+        index.ids.entry(key).or_insert(id);
+
+        id
+    }
+
+    /// Push the node and make it the parent of the nodes pushed by `walk`.
+    fn enter<F>(&mut self, key: usize, kind: &'static str, start: u32, end: u32, walk: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        let id = self.push(key, kind, start, end);
+        let parent = self.parent.replace(id);
+
+        walk(self);
+        self.parent = parent;
+    }
+
+    #[inline]
+    fn node<T, F>(&mut self, node: &Node<T>, kind: &'static str, walk: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        self.enter(key(node), kind, node.start, node.end, walk)
+    }
+
+    /// Same as `node`, for the value of a struct `Node` passed by `Visit`.
+    #[inline]
+    fn value<T, F>(&mut self, value: &T, kind: &'static str, start: u32, end: u32, walk: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        self.enter(address(value), kind, start, end, walk)
+    }
+
+    #[inline]
+    fn leaf<T>(&mut self, node: &Node<T>, kind: &'static str) {
+        self.push(key(node), kind, node.start, node.end);
+    }
+}
+
+impl<'ast> Visit<'ast> for Indexer {
+    fn visit_source_unit(&mut self, unit: &SourceUnitNode<'ast>) {
+        self.node(unit, unit.value.kind(), |i| walk_source_unit(i, unit));
+    }
+
+    fn visit_import(&mut self, import: &Import<'ast>, start: u32, end: u32) {
+        self.value(import, "Import", start, end, |i| walk_import(i, import));
+    }
+
+    fn visit_contract_part(&mut self, part: &ContractPartNode<'ast>) {
+        self.node(part, part.value.kind(), |i| walk_contract_part(i, part));
+    }
+
+    fn visit_indexed_parameter(&mut self, param: &IndexedParameter<'ast>, start: u32, end: u32) {
+        self.value(param, "IndexedParameter", start, end, |i| walk_indexed_parameter(i, param));
+    }
+
+    fn visit_parameter(&mut self, param: &Parameter<'ast>, start: u32, end: u32) {
+        self.value(param, "Parameter", start, end, |i| walk_parameter(i, param));
+    }
+
+    fn visit_modifier_invocation(&mut self, modifier: &ModifierInvocation<'ast>, start: u32, end: u32) {
+        self.value(modifier, "ModifierInvocation", start, end, |i| walk_modifier_invocation(i, modifier));
+    }
+
+    fn visit_doc_comment(&mut self, doc: &DocComment<'ast>, start: u32, end: u32) {
+        // Individual tags are within a single comment, so
+        // they don't get their own nodes.
+        self.value(doc, "DocComment", start, end, |_| {});
+    }
+
+    fn visit_type_name(&mut self, type_name: &TypeNameNode<'ast>) {
+        self.node(type_name, type_name.value.kind(), |i| walk_type_name(i, type_name));
+    }
+
+    fn visit_variable_declaration(&mut self, declaration: &VariableDeclaration<'ast>, start: u32, end: u32) {
+        self.value(declaration, "VariableDeclaration", start, end, |i| walk_variable_declaration(i, declaration));
+    }
+
+    fn visit_statement(&mut self, statement: &StatementNode<'ast>) {
+        self.node(statement, statement.value.kind(), |i| match statement.value {
+            // The statement node is the block itself
+            Statement::BlockStatement(ref block) => walk_block(i, block),
+            _                                    => walk_statement(i, statement),
+        });
+    }
+
+    fn visit_simple_statement(&mut self, statement: &SimpleStatementNode<'ast>) {
+        self.node(statement, statement.value.kind(), |i| walk_simple_statement(i, statement));
+    }
+
+    fn visit_block(&mut self, block: &Block<'ast>, start: u32, end: u32) {
+        self.value(block, "Block", start, end, |i| walk_block(i, block));
+    }
+
+    fn visit_expression(&mut self, expression: &ExpressionNode<'ast>) {
+        self.node(expression, expression.value.kind(), |i| walk_expression(i, expression));
+    }
+
+    fn visit_inline_assembly_block(&mut self, block: &InlineAssemblyBlock<'ast>, start: u32, end: u32) {
+        self.value(block, "InlineAssemblyBlock", start, end, |i| walk_inline_assembly_block(i, block));
+    }
+
+    fn visit_assembly_item(&mut self, item: &AssemblyItemNode<'ast>) {
+        self.node(item, item.value.kind(), |i| match item.value {
+            // Likewise, the item node is the expression or block itself
+            AssemblyItem::FunctionalAssemblyExpression(ref expr) => walk_functional_assembly_expression(i, expr),
+            AssemblyItem::InlineAssemblyBlock(ref block)         => walk_inline_assembly_block(i, block),
+            _                                                    => walk_assembly_item(i, item),
+        });
+    }
+
+    fn visit_functional_assembly_expression(&mut self, expr: &FunctionalAssemblyExpression<'ast>, start: u32, end: u32) {
+        self.value(expr, "FunctionalAssemblyExpression", start, end, |i| walk_functional_assembly_expression(i, expr));
+    }
+
+    fn visit_identifier(&mut self, identifier: &IdentifierNode<'ast>) {
+        self.leaf(identifier, "Identifier");
+    }
+
+    fn visit_string_literal(&mut self, string: &StringLiteralNode<'ast>) {
+        self.leaf(string, "StringLiteral");
+    }
+
+    fn visit_flag(&mut self, flag: &FlagNode<'ast>) {
+        self.leaf(flag, "Flag");
+    }
+
+    fn visit_elementary_type_name(&mut self, type_name: &ElementaryTypeNameNode<'ast>) {
+        self.leaf(type_name, "ElementaryTypeName");
+    }
+
+    fn visit_state_variable_visibility(&mut self, visibility: &Node<'ast, StateVariableVisibility>) {
+        self.leaf(visibility, "StateVariableVisibility");
+    }
+
+    fn visit_function_visibility(&mut self, visibility: &Node<'ast, FunctionVisibility>) {
+        self.leaf(visibility, "FunctionVisibility");
+    }
+
+    fn visit_state_mutability(&mut self, mutability: &Node<'ast, StateMutability>) {
+        self.leaf(mutability, "StateMutability");
+    }
+
+    fn visit_storage_location(&mut self, location: &Node<'ast, StorageLocation>) {
+        self.leaf(location, "StorageLocation");
+    }
+
+    fn visit_prefix_operator(&mut self, operator: &Node<'ast, PrefixOperator>) {
+        self.leaf(operator, "PrefixOperator");
+    }
+
+    fn visit_postfix_operator(&mut self, operator: &Node<'ast, PostfixOperator>) {
+        self.leaf(operator, "PostfixOperator");
+    }
+
+    fn visit_binary_operator(&mut self, operator: &Node<'ast, BinaryOperator>) {
+        self.leaf(operator, "BinaryOperator");
+    }
+
+    fn visit_assignment_operator(&mut self, operator: &Node<'ast, AssignmentOperator>) {
+        self.leaf(operator, "AssignmentOperator");
+    }
+}
+
+/// Key of a node in the ids of an `AstIndex`. `Visit` passes along only the
+/// values of struct nodes, so it's the address of the value, except for
+/// zero-sized values, which can share it with the next allocation.
+#[inline]
+fn key<T>(node: &Node<T>) -> usize {
+    match mem::size_of::<T>() {
+        0 => address::<NodeInner<T>>(node),
+        _ => address(&node.value),
+    }
+}
+
+#[inline]
+fn address<T>(value: &T) -> usize {
+    value as *const T as usize
+}
+
+fn is_statement(kind: &str) -> bool {
+    matches!(
+        kind,
+        "Placeholder"                 |
+        "ContinueStatement"           |
+        "BreakStatement"              |
+        "ThrowStatement"              |
+        "IfStatement"                 |
+        "WhileStatement"              |
+        "ForStatement"                |
+        "BlockStatement"              |
+        "InlineAssemblyStatement"     |
+        "DoWhileStatement"            |
+        "ReturnStatement"             |
+        "VariableDefinitionStatement" |
+        "InferredDefinitionStatement" |
+        "ExpressionStatement"
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use toolshed::Arena;
+    use fixture::program;
+
+    #[test]
+    fn ids_and_parents() {
+        let program = program();
+        let index = AstIndex::new(&program);
+
+        let nodes: Vec<_> = index.iter()
+            .map(|(id, entry)| (id.index(), entry.kind, entry.parent.map(NodeId::index)))
+            .collect();
+
+        assert_eq!(nodes, vec![
+            (0, "ContractDefinition", None),
+            (1, "Identifier", Some(0)),
+            (2, "FunctionDefinition", Some(0)),
+            (3, "Identifier", Some(2)),
+            (4, "Parameter", Some(2)),
+            (5, "ElementaryTypeName", Some(4)),
+            (6, "Identifier", Some(4)),
+            (7, "Block", Some(2)),
+            (8, "IfStatement", Some(7)),
+            (9, "BinaryExpression", Some(8)),
+            (10, "IdentifierExpression", Some(9)),
+            (11, "BinaryOperator", Some(9)),
+            (12, "PrimitiveExpression", Some(9)),
+            (13, "ExpressionStatement", Some(8)),
+            (14, "AssignmentExpression", Some(13)),
+            (15, "IdentifierExpression", Some(14)),
+            (16, "AssignmentOperator", Some(14)),
+            (17, "PrimitiveExpression", Some(14)),
+        ]);
+
+        let ancestors: Vec<_> = index.ancestors(NodeId(15)).map(NodeId::index).collect();
+
+        assert_eq!(ancestors, vec![14, 13, 8, 7, 2, 0]);
+    }
+
+    #[test]
+    fn node_ids() {
+        let program = program();
+        let index = AstIndex::new(&program);
+        let contract = program.body().only_element().cloned().unwrap();
+
+        let function = match contract.value {
+            SourceUnit::ContractDefinition(ref contract) => contract.body.only_element().cloned().unwrap(),
+            _ => panic!("Expected a contract"),
+        };
+
+        assert_eq!(index.id(&contract), Some(NodeId(0)));
+        assert_eq!(index.id(&function), Some(NodeId(2)));
+        assert_eq!(index.get(NodeId(2)), &NodeEntry {
+            kind: "FunctionDefinition",
+            start: 15,
+            end: 58,
+            parent: Some(NodeId(0)),
+        });
+
+        let arena = Arena::new();
+        let other = AstBuilder::new(&arena).at(15, 58).flag();
+
+        assert_eq!(index.id(&other), None);
+    }
+
+    #[test]
+    fn enclosing_nodes() {
+        let program = program();
+        let index = AstIndex::new(&program);
+
+        assert_eq!(index.node_at(52), Some(NodeId(16)));
+        assert_eq!(index.enclosing_statement(52), Some(NodeId(13)));
+        assert_eq!(index.enclosing_statement(45), Some(NodeId(8)));
+        assert_eq!(index.enclosing_function(45), Some(NodeId(2)));
+        assert_eq!(index.enclosing_contract(45), Some(NodeId(0)));
+
+        assert_eq!(index.node_at(33), Some(NodeId(6)));
+        assert_eq!(index.enclosing_statement(33), None);
+        assert_eq!(index.enclosing_function(33), Some(NodeId(2)));
+
+        assert_eq!(index.node_at(10), Some(NodeId(1)));
+        assert_eq!(index.enclosing_statement(10), None);
+        assert_eq!(index.enclosing_function(10), None);
+        assert_eq!(index.enclosing_contract(10), Some(NodeId(0)));
+
+        assert_eq!(index.node_at(60), None);
+        assert_eq!(index.enclosing_contract(60), None);
+    }
+}
@@ -1,5 +1,11 @@
 extern crate toolshed;
 
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde_json;
+
 #[cfg(test)]
 #[macro_use]
 extern crate pretty_assertions;
@@ -14,6 +20,26 @@ mod type_name;
 mod expression;
 mod statement;
 mod assembly;
+mod literal;
+mod doc;
+mod number;
+mod line_index;
+mod index;
+mod source_files;
+mod builder;
+mod structural;
+mod dump;
+mod query;
+pub mod visit;
+pub mod visit_mut;
+
+#[cfg(feature = "serde")]
+mod ser;
+#[cfg(feature = "serde")]
+pub mod solc;
+
+#[cfg(test)]
+mod fixture;
 
 use toolshed::list::{List, UnsafeList};
 use toolshed::Arena;
@@ -27,6 +53,18 @@ pub use self::type_name::*;
 pub use self::expression::*;
 pub use self::statement::*;
 pub use self::assembly::*;
+pub use self::literal::*;
+pub use self::doc::*;
+pub use self::number::*;
+pub use self::line_index::{LineIndex, LineCol};
+pub use self::index::{AstIndex, NodeId, NodeEntry};
+pub use self::source_files::{SourceFiles, SourceFile, FileId, Location, DisplayLocation};
+pub use self::builder::AstBuilder;
+pub use self::structural::{structurally_eq, StructuralHash, StructuralOptions, Structure};
+pub use self::dump::DumpOptions;
+pub use self::query::{Query, QueryMatch, Capture, QueryError};
+pub use self::visit::Visit;
+pub use self::visit_mut::VisitMut;
 
 /// Useful for boolean flags that need location information via FlagNode,
 /// for example: `indexed` or `anonymous`.
@@ -55,6 +93,16 @@ pub struct Program<'ast> {
     /// `Arena` on which the entire AST is allocated.
     arena: Arena,
 
+    /// Source the AST was parsed from, allocated on the `arena`.
+    source: &'ast str,
+
+    /// Offset at which the `source` begins, nonzero for files parsed
+    /// from `SourceFiles`.
+    offset: u32,
+
+    /// Set if the parser recovered from errors while producing the AST.
+    partial: bool,
+
     /// For lifetime safety :).
     _phantom: PhantomData<SourceUnitList<'ast>>
 }
@@ -62,9 +110,19 @@ pub struct Program<'ast> {
 impl<'ast> Program<'ast> {
     #[inline]
     pub fn new(body: UnsafeList, arena: Arena) -> Self {
+        Program::with_source(body, arena, "", 0)
+    }
+
+    /// Create a `Program` that keeps the source it was parsed from, the
+    /// `source` has to be allocated on the `arena` (or be `'static`).
+    #[inline]
+    pub fn with_source(body: UnsafeList, arena: Arena, source: &'ast str, offset: u32) -> Self {
         Program {
             body,
             arena,
+            source,
+            offset,
+            partial: false,
             _phantom: PhantomData,
         }
     }
@@ -75,9 +133,117 @@ impl<'ast> Program<'ast> {
         unsafe { self.body.into_list() }
     }
 
+    /// Get the source the AST was parsed from, empty if the `Program`
+    /// wasn't produced by the parser.
+    #[inline]
+    pub fn source(&self) -> &'ast str {
+        self.source
+    }
+
+    /// Get the offset at which the source begins, nonzero for files
+    /// parsed from `SourceFiles`.
+    #[inline]
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Check if the parser recovered from errors while producing the AST,
+    /// in which case it might be missing parts of the source.
+    #[inline]
+    pub fn is_partial(&self) -> bool {
+        self.partial
+    }
+
+    /// Mark the `Program` as produced with errors, see `is_partial`.
+    #[inline]
+    pub fn set_partial(&mut self, partial: bool) {
+        self.partial = partial;
+    }
+
+    /// Take the body and the `Arena` out of the `Program`, so that the AST
+    /// can be modified and put back together with `with_source`.
+    #[inline]
+    pub fn into_parts(self) -> (UnsafeList, Arena) {
+        (self.body, self.arena)
+    }
+
+    /// Get the exact source of the node, comments and whitespace within it
+    /// included. Panics if the node is not located within the source, as is
+    /// the case for nodes created with `node`.
+    #[inline]
+    pub fn text<T>(&self, node: &Node<'ast, T>) -> &'ast str
+    where
+        T: 'ast,
+    {
+        let start = (node.start - self.offset) as usize;
+        let end = (node.end - self.offset) as usize;
+
+        &self.source[start..end]
+    }
+
     /// Get a reference to the `Arena` on which the AST is allocated.
     #[inline]
     pub fn arena(&'ast self) -> &'ast Arena {
         &self.arena
     }
+
+    /// Allocate a new `Node` on the `Arena` of this `Program`, so that it can
+    /// be put into the AST, e.g. with `Node::replace`.
+    #[inline]
+    pub fn node<T, I>(&'ast self, start: u32, end: u32, value: I) -> Node<'ast, T>
+    where
+        T: 'ast + Copy,
+        I: Into<T>,
+    {
+        Node::new(self.arena.alloc(NodeInner::new(start, end, value.into())))
+    }
+
+    /// Allocate a new `NodeList` on the `Arena` of this `Program`.
+    #[inline]
+    pub fn list<T, I>(&'ast self, nodes: I) -> NodeList<'ast, T>
+    where
+        T: 'ast + Copy,
+        I: IntoIterator<Item = Node<'ast, T>>,
+    {
+        List::from_iter(&self.arena, nodes)
+    }
+
+    /// Copy a string onto the `Arena` of this `Program`, for use as
+    /// an `Identifier` or a `StringLiteral` in new nodes.
+    #[inline]
+    pub fn alloc_str(&'ast self, value: &str) -> &'ast str {
+        self.arena.alloc_str(value)
+    }
+
+    /// Serialize the AST to JSON, requires the `serde` feature. The output
+    /// only changes with the AST itself, so it's safe for snapshot tests.
+    ///
+    /// + Every node is an object with `type` as its first entry, followed
+    ///   by its fields in the order they are declared in the AST types, and
+    ///   `start` and `end` byte offsets as the last two entries.
+    /// + Variants of enums like `Statement` or `Expression` are tagged with
+    ///   the variant name, fields of their payload are inlined, so an `if`
+    ///   is `{"type":"IfStatement","test":...,"consequent":...}`.
+    /// + Identifiers are `{"type":"Identifier","name":...}`, string literals
+    ///   `{"type":"StringLiteral","value":...}` with the quotes included.
+    /// + Operators, visibility, state mutability and storage location are
+    ///   `{"type":"BinaryOperator","value":"+"}` and the like.
+    /// + Elementary types are named canonically, `uint` becomes `uint256`.
+    /// + Literals in expressions have `kind` (`bool`, `hex`, `integer`,
+    ///   `rational` or `string`), `value` as written in the source, and
+    ///   `unit`, which is `null` unless a number has a unit.
+    /// + Missing optional fields are `null`, empty lists are `[]`.
+    /// + The root is `{"type":"Program","body":[...]}`, without a location.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("AST can always be serialized to JSON")
+    }
+
+    /// Export the AST in the format of `solc --ast-compact-json`, as the only
+    /// source file at `path`. Requires the `serde` feature, see `solc::Exporter`
+    /// for exporting multiple files with unique node ids.
+    #[cfg(feature = "serde")]
+    pub fn to_solc_json(&self, path: &str) -> String {
+        solc::Exporter::new().export(self, path, 0).to_string()
+    }
 }
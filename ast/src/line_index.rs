@@ -0,0 +1,268 @@
+use std::ops::Range;
+
+/// Zero-based line and column position in the source. Depending on the
+/// method that produced it, `column` is counted in UTF-8 bytes, `char`s
+/// or UTF-16 code units (the latter being the unit used by LSP).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineCol {
+    pub line: u32,
+    pub column: u32,
+}
+
+/// Maps byte offsets, such as `start` and `end` of a `Node`, to lines
+/// and columns and back. Build it once per source and reuse it.
+#[derive(Clone, Debug)]
+pub struct LineIndex<'src> {
+    source: &'src str,
+
+    /// Byte offsets at which every line begins, the first one is always 0.
+    lines: Vec<u32>,
+}
+
+impl<'src> LineIndex<'src> {
+    pub fn new(source: &'src str) -> Self {
+        let mut lines = vec![0];
+
+        lines.extend(
+            source.bytes()
+                  .enumerate()
+                  .filter(|&(_, byte)| byte == b'\n')
+                  .map(|(index, _)| index as u32 + 1)
+        );
+
+        LineIndex {
+            source,
+            lines,
+        }
+    }
+
+    #[inline]
+    pub fn source(&self) -> &'src str {
+        self.source
+    }
+
+    #[inline]
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Line and column of a byte offset, with the column in bytes. Offsets past
+    /// the end of the source are clamped to it, offsets in the middle of a
+    /// multi-byte character are moved back to its start.
+    pub fn line_col(&self, offset: u32) -> LineCol {
+        let offset = self.clamp(offset);
+        let line   = self.line_of(offset);
+
+        LineCol {
+            line: line as u32,
+            column: offset - self.lines[line],
+        }
+    }
+
+    /// Same as `line_col`, with the column counted in `char`s.
+    pub fn line_col_chars(&self, offset: u32) -> LineCol {
+        let LineCol { line, column } = self.line_col(offset);
+        let text = self.prefix(line, column);
+
+        LineCol {
+            line,
+            column: text.chars().count() as u32,
+        }
+    }
+
+    /// Same as `line_col`, with the column counted in UTF-16 code units.
+    pub fn line_col_utf16(&self, offset: u32) -> LineCol {
+        let LineCol { line, column } = self.line_col(offset);
+        let text = self.prefix(line, column);
+
+        LineCol {
+            line,
+            column: text.chars().map(|ch| ch.len_utf16() as u32).sum(),
+        }
+    }
+
+    /// Byte offset of a line and a column in bytes. Returns `None` if the line
+    /// doesn't exist, the column is past the end of the line (the line
+    /// terminator itself is a valid position) or not at a character boundary.
+    pub fn offset(&self, position: LineCol) -> Option<u32> {
+        let start  = *self.lines.get(position.line as usize)?;
+        let offset = start + position.column;
+
+        if offset > self.line_end(position.line as usize) || !self.source.is_char_boundary(offset as usize) {
+            return None;
+        }
+
+        Some(offset)
+    }
+
+    /// Same as `offset`, with the column counted in `char`s.
+    pub fn offset_chars(&self, position: LineCol) -> Option<u32> {
+        self.offset_by(position, |_| 1)
+    }
+
+    /// Same as `offset`, with the column counted in UTF-16 code units.
+    pub fn offset_utf16(&self, position: LineCol) -> Option<u32> {
+        self.offset_by(position, |ch| ch.len_utf16() as u32)
+    }
+
+    /// Byte range of a line, excluding the line terminator.
+    pub fn line_range(&self, line: u32) -> Option<Range<u32>> {
+        let start   = *self.lines.get(line as usize)?;
+        let mut end = self.line_end(line as usize);
+
+        if end > start && self.source.as_bytes()[end as usize - 1] == b'\r' {
+            end -= 1;
+        }
+
+        Some(start..end)
+    }
+
+    /// Text of a line, excluding the line terminator.
+    pub fn line_text(&self, line: u32) -> Option<&'src str> {
+        let range = self.line_range(line)?;
+
+        Some(&self.source[range.start as usize..range.end as usize])
+    }
+
+    fn offset_by<F>(&self, position: LineCol, width: F) -> Option<u32>
+    where
+        F: Fn(char) -> u32,
+    {
+        let start = *self.lines.get(position.line as usize)?;
+        let end   = self.line_end(position.line as usize);
+        let text  = &self.source[start as usize..end as usize];
+
+        let mut column = 0;
+
+        for (index, ch) in text.char_indices() {
+            if column >= position.column {
+                break;
+            }
+
+            column += width(ch);
+
+            if column == position.column {
+                return Some(start + (index + ch.len_utf8()) as u32);
+            }
+        }
+
+        if column == position.column && position.column == 0 {
+            return Some(start);
+        }
+
+        None
+    }
+
+    /// Offset of the `\n` terminating the line, or the end of the source.
+    fn line_end(&self, line: usize) -> u32 {
+        match self.lines.get(line + 1) {
+            Some(&next) => next - 1,
+            None        => self.source.len() as u32,
+        }
+    }
+
+    fn prefix(&self, line: u32, column: u32) -> &'src str {
+        let start = self.lines[line as usize] as usize;
+
+        &self.source[start..start + column as usize]
+    }
+
+    fn clamp(&self, offset: u32) -> u32 {
+        let mut offset = (offset as usize).min(self.source.len());
+
+        while !self.source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+
+        offset as u32
+    }
+
+    fn line_of(&self, offset: u32) -> usize {
+        match self.lines.binary_search(&offset) {
+            Ok(line)  => line,
+            Err(next) => next - 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SOURCE: &str = "contract Foo {\r\n    string s = unicode\"żółw 🐢\";\n}\n";
+
+    fn at(line: u32, column: u32) -> LineCol {
+        LineCol { line, column }
+    }
+
+    #[test]
+    fn lines() {
+        let index = LineIndex::new(SOURCE);
+
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.line_text(0), Some("contract Foo {"));
+        assert_eq!(index.line_text(1), Some("    string s = unicode\"żółw 🐢\";"));
+        assert_eq!(index.line_text(2), Some("}"));
+        assert_eq!(index.line_text(3), Some(""));
+        assert_eq!(index.line_text(4), None);
+        assert_eq!(index.line_range(0), Some(0..14));
+    }
+
+    #[test]
+    fn offset_to_line_col() {
+        let index = LineIndex::new(SOURCE);
+        let turtle = SOURCE.find('🐢').unwrap() as u32;
+        let brace = SOURCE.rfind('}').unwrap() as u32;
+
+        assert_eq!(index.line_col(0), at(0, 0));
+        assert_eq!(index.line_col(9), at(0, 9));
+        assert_eq!(index.line_col(16), at(1, 0));
+        assert_eq!(index.line_col(brace), at(2, 0));
+        assert_eq!(index.line_col(SOURCE.len() as u32), at(3, 0));
+        assert_eq!(index.line_col(1000), at(3, 0));
+
+        assert_eq!(index.line_col(turtle), at(1, 31));
+        assert_eq!(index.line_col_chars(turtle), at(1, 28));
+        assert_eq!(index.line_col_utf16(turtle), at(1, 28));
+        assert_eq!(index.line_col_chars(turtle + 4), at(1, 29));
+        assert_eq!(index.line_col_utf16(turtle + 4), at(1, 30));
+
+        // Middle of the turtle
+        assert_eq!(index.line_col(turtle + 2), at(1, 31));
+    }
+
+    #[test]
+    fn line_col_to_offset() {
+        let index = LineIndex::new(SOURCE);
+        let turtle = SOURCE.find('🐢').unwrap() as u32;
+
+        assert_eq!(index.offset(at(0, 9)), Some(9));
+        assert_eq!(index.offset(at(0, 14)), Some(14));
+        assert_eq!(index.offset(at(0, 15)), Some(15));
+        assert_eq!(index.offset(at(0, 16)), None);
+        assert_eq!(index.offset(at(1, 31)), Some(turtle));
+        assert_eq!(index.offset(at(1, 32)), None);
+        assert_eq!(index.offset(at(5, 0)), None);
+
+        assert_eq!(index.offset_chars(at(1, 28)), Some(turtle));
+        assert_eq!(index.offset_chars(at(1, 29)), Some(turtle + 4));
+        assert_eq!(index.offset_utf16(at(1, 28)), Some(turtle));
+        assert_eq!(index.offset_utf16(at(1, 29)), None);
+        assert_eq!(index.offset_utf16(at(1, 30)), Some(turtle + 4));
+        assert_eq!(index.offset_utf16(at(1, 32)), Some(turtle + 6));
+        assert_eq!(index.offset_utf16(at(1, 33)), None);
+    }
+
+    #[test]
+    fn round_trip() {
+        let index = LineIndex::new(SOURCE);
+
+        for (offset, _) in SOURCE.char_indices() {
+            let offset = offset as u32;
+
+            assert_eq!(index.offset(index.line_col(offset)), Some(offset));
+            assert_eq!(index.offset_chars(index.line_col_chars(offset)), Some(offset));
+            assert_eq!(index.offset_utf16(index.line_col_utf16(offset)), Some(offset));
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use std::borrow::Cow;
+
+use {*};
+
+impl<'ast> Primitive<'ast> {
+    /// For `Primitive::String` get the bytes the literal represents, with
+    /// the quotes stripped and all escape sequences decoded.
+    #[inline]
+    pub fn decoded_bytes(&self) -> Option<Cow<'ast, [u8]>> {
+        match *self {
+            Primitive::String(raw) => Some(decode_string_literal(raw)),
+            _                      => None,
+        }
+    }
+
+    /// For number literals get their exact value, units included.
+    #[inline]
+    pub fn number_value(&self) -> Option<Result<NumberValue, NumberError>> {
+        match *self {
+            Primitive::HexNumber(raw)            => Some(evaluate_number(raw, NumberUnit::None)),
+            Primitive::IntegerNumber(raw, unit)  |
+            Primitive::RationalNumber(raw, unit) => Some(evaluate_number(raw, unit)),
+            _                                    => None,
+        }
+    }
+}
+
+/// Decode the raw source of a string literal, quotes and the optional
+/// `unicode` prefix included, into the bytes it represents. Literals
+/// without escape sequences are returned without allocating.
+///
+/// The lexer rejects string literals with invalid escape sequences, should
+/// one be found here regardless it's kept verbatim.
+pub fn decode_string_literal<'src>(raw: &'src str) -> Cow<'src, [u8]> {
+    let raw = raw.as_bytes();
+    let raw = if raw.starts_with(b"unicode") { &raw[7..] } else { raw };
+
+    if raw.len() < 2 {
+        return Cow::Borrowed(&[]);
+    }
+
+    let body = &raw[1..raw.len() - 1];
+
+    if !body.contains(&b'\\') {
+        return Cow::Borrowed(body);
+    }
+
+    let mut bytes = Vec::with_capacity(body.len());
+    let mut iter = body.iter().cloned().peekable();
+
+    while let Some(byte) = iter.next() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+
+        match iter.next() {
+            Some(b'n')  => bytes.push(b'\n'),
+            Some(b'r')  => bytes.push(b'\r'),
+            Some(b't')  => bytes.push(b'\t'),
+            Some(b'\n') => {},
+            Some(b'\r') => {
+                if iter.peek() == Some(&b'\n') {
+                    iter.next();
+                }
+            },
+            Some(b'x') => match hex_escape(&mut iter, 2) {
+                Some(value) => bytes.push(value as u8),
+                None        => bytes.extend_from_slice(b"\\x"),
+            },
+            Some(b'u') => match hex_escape(&mut iter, 4) {
+                Some(value) => push_utf8(&mut bytes, value),
+                None        => bytes.extend_from_slice(b"\\u"),
+            },
+            Some(b'\\') => bytes.push(b'\\'),
+            Some(b'\'') => bytes.push(b'\''),
+            Some(b'"')  => bytes.push(b'"'),
+            Some(other) => bytes.extend_from_slice(&[b'\\', other]),
+            None        => bytes.push(b'\\'),
+        }
+    }
+
+    Cow::Owned(bytes)
+}
+
+fn hex_escape<I>(iter: &mut I, digits: usize) -> Option<u32>
+where
+    I: Iterator<Item = u8> + Clone,
+{
+    let mut lookahead = iter.clone();
+    let mut value = 0;
+
+    for _ in 0..digits {
+        let digit = match lookahead.next()? {
+            byte @ b'0'..=b'9' => byte - b'0',
+            byte @ b'a'..=b'f' => byte - b'a' + 10,
+            byte @ b'A'..=b'F' => byte - b'A' + 10,
+            _                  => return None,
+        };
+
+        value = value * 16 + digit as u32;
+    }
+
+    *iter = lookahead;
+
+    Some(value)
+}
+
+/// `\uNNNN` is always encoded as UTF-8, same as solc does it. This can't
+/// use `char` since surrogates are representable with the escape.
+fn push_utf8(bytes: &mut Vec<u8>, value: u32) {
+    match value {
+        0..=0x7F => bytes.push(value as u8),
+        0x80..=0x7FF => {
+            bytes.push(0xC0 | (value >> 6) as u8);
+            bytes.push(0x80 | (value & 0x3F) as u8);
+        },
+        _ => {
+            bytes.push(0xE0 | (value >> 12) as u8);
+            bytes.push(0x80 | ((value >> 6) & 0x3F) as u8);
+            bytes.push(0x80 | (value & 0x3F) as u8);
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decode_plain_string() {
+        assert_eq!(&*decode_string_literal("\"foo\""), b"foo");
+        assert_eq!(&*decode_string_literal("'foo bar'"), b"foo bar");
+        assert_eq!(&*decode_string_literal("\"\""), b"");
+    }
+
+    #[test]
+    fn decode_escapes() {
+        assert_eq!(&*decode_string_literal(r#""\n\r\t\\\'\"""#), b"\n\r\t\\'\"");
+        assert_eq!(&*decode_string_literal(r#""\x19Ethereum""#), b"\x19Ethereum");
+        assert_eq!(&*decode_string_literal(r#"'\xff\x00'"#), b"\xff\x00");
+        assert_eq!(&*decode_string_literal(r#""\u20ACA\u00e9""#), "€Aé".as_bytes());
+    }
+
+    #[test]
+    fn decode_line_continuations() {
+        assert_eq!(&*decode_string_literal("\"foo\\\nbar\""), b"foobar");
+        assert_eq!(&*decode_string_literal("\"foo\\\r\nbar\""), b"foobar");
+    }
+
+    #[test]
+    fn decode_unicode_string() {
+        assert_eq!(&*decode_string_literal("unicode\"Hello 😃\""), "Hello 😃".as_bytes());
+        assert_eq!(&*decode_string_literal("unicode'\\u00e9'"), "é".as_bytes());
+    }
+
+    #[test]
+    fn primitive_decoded_bytes() {
+        let string = Primitive::String("\"doge\\n\"");
+
+        assert_eq!(string.decoded_bytes().unwrap(), &b"doge\n"[..]);
+        assert_eq!(Primitive::Bool(true).decoded_bytes(), None);
+    }
+
+    #[test]
+    fn primitive_number_value() {
+        let ether = Primitive::RationalNumber("0.5", NumberUnit::Ether(EtherUnit::Ether));
+        let hex = Primitive::HexNumber("0x10");
+
+        assert_eq!(ether.number_value().unwrap().unwrap().to_string(), "500000000000000000");
+        assert_eq!(hex.number_value().unwrap().unwrap().to_string(), "16");
+        assert_eq!(Primitive::String("\"10\"").number_value(), None);
+    }
+}
@@ -63,6 +63,13 @@ impl<'ast, T: 'ast> Node<'ast, T> {
         self.inner.set(ptr)
     }
 
+    /// Point this node at the value of `other`. Since the AST only holds
+    /// `Node`s by value, this replaces the node wherever it is in the tree.
+    #[inline]
+    pub fn replace(&self, other: Node<'ast, T>) {
+        self.inner.set(other.inner.get())
+    }
+
     #[inline]
     pub fn get_mut(&mut self) -> &mut &'ast NodeInner<T> {
         self.inner.get_mut()
@@ -0,0 +1,586 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use {NumberUnit, EtherUnit, TimeUnit};
+
+/// Largest absolute decimal exponent a literal is evaluated with. Anything
+/// beyond that is either way out of the 256 bit range, or a fraction with
+/// a denominator far larger than any fixed point type can handle.
+const MAX_EXPONENT: i64 = 4096;
+
+/// Arbitrary precision unsigned integer, used for exact evaluation
+/// of number literals.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// Little endian base 2^32 digits, without trailing zeroes.
+    limbs: Vec<u32>,
+}
+
+/// Exact value of a number literal.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NumberValue {
+    Integer(BigUint),
+
+    /// Fraction in its lowest terms, numerator first. The denominator
+    /// is always greater than one.
+    Rational(BigUint, BigUint),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NumberError {
+    /// The literal isn't a well formed number.
+    Invalid,
+
+    /// The value is 2^256 or larger, or the exponent is too large
+    /// for the literal to be evaluated.
+    Overflow,
+
+    /// The value is a fraction with a negative exponent too large
+    /// for it to be evaluated.
+    Fraction,
+}
+
+impl BigUint {
+    #[inline]
+    pub fn zero() -> Self {
+        BigUint::default()
+    }
+
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Number of bits needed to represent this value.
+    pub fn bits(&self) -> usize {
+        match self.limbs.last() {
+            Some(last) => self.limbs.len() * 32 - last.leading_zeros() as usize,
+            None       => 0,
+        }
+    }
+
+    /// Get the value as `u64`, if it fits.
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs.len() {
+            0 => Some(0),
+            1 => Some(self.limbs[0] as u64),
+            2 => Some(self.limbs[0] as u64 | (self.limbs[1] as u64) << 32),
+            _ => None,
+        }
+    }
+
+    /// Big endian bytes of the value, without leading zeroes.
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.limbs.len() * 4);
+
+        for limb in self.limbs.iter().rev() {
+            bytes.extend_from_slice(&[
+                (limb >> 24) as u8,
+                (limb >> 16) as u8,
+                (limb >> 8) as u8,
+                *limb as u8,
+            ]);
+        }
+
+        let zeroes = bytes.iter().take_while(|&&byte| byte == 0).count();
+
+        bytes.split_off(zeroes)
+    }
+
+    /// Compute `10^exp`.
+    pub fn pow10(exp: u32) -> Self {
+        let mut value = BigUint::from(1);
+
+        for _ in 0..exp / 9 {
+            value.mul_small(1_000_000_000);
+        }
+
+        value.mul_small(10u32.pow(exp % 9));
+        value
+    }
+
+    pub fn mul(&self, other: &BigUint) -> BigUint {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::zero();
+        }
+
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let product = a as u64 * b as u64 + limbs[i + j] as u64 + carry;
+
+                limbs[i + j] = product as u32;
+                carry = product >> 32;
+            }
+
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+
+        BigUint::from_limbs(limbs)
+    }
+
+    /// Compute `self * 2^bits`.
+    pub fn shl(&self, bits: usize) -> BigUint {
+        if self.is_zero() {
+            return BigUint::zero();
+        }
+
+        let shift = (bits % 32) as u32;
+        let mut limbs = vec![0u32; bits / 32];
+
+        if shift == 0 {
+            limbs.extend_from_slice(&self.limbs);
+        } else {
+            let mut carry = 0;
+
+            for &limb in &self.limbs {
+                limbs.push(limb << shift | carry);
+                carry = limb >> (32 - shift);
+            }
+
+            limbs.push(carry);
+        }
+
+        BigUint::from_limbs(limbs)
+    }
+
+    fn from_limbs(limbs: Vec<u32>) -> Self {
+        let mut value = BigUint { limbs };
+
+        value.normalize();
+        value
+    }
+
+    fn normalize(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+
+    fn mul_small(&mut self, factor: u32) {
+        let mut carry = 0u64;
+
+        for limb in self.limbs.iter_mut() {
+            let product = *limb as u64 * factor as u64 + carry;
+
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+
+        if carry != 0 {
+            self.limbs.push(carry as u32);
+        }
+
+        self.normalize();
+    }
+
+    fn add_small(&mut self, value: u32) {
+        let mut carry = value as u64;
+
+        for limb in self.limbs.iter_mut() {
+            if carry == 0 {
+                return;
+            }
+
+            let sum = *limb as u64 + carry;
+
+            *limb = sum as u32;
+            carry = sum >> 32;
+        }
+
+        if carry != 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+
+    /// Divide in place, returning the remainder.
+    fn div_rem_small(&mut self, divisor: u32) -> u32 {
+        let mut rem = 0u64;
+
+        for limb in self.limbs.iter_mut().rev() {
+            let current = rem << 32 | *limb as u64;
+
+            *limb = (current / divisor as u64) as u32;
+            rem = current % divisor as u64;
+        }
+
+        self.normalize();
+
+        rem as u32
+    }
+
+    fn rem_small(&self, divisor: u32) -> u32 {
+        self.limbs.iter().rev().fold(0u64, |rem, &limb| {
+            (rem << 32 | limb as u64) % divisor as u64
+        }) as u32
+    }
+}
+
+impl From<u64> for BigUint {
+    #[inline]
+    fn from(value: u64) -> Self {
+        BigUint::from_limbs(vec![value as u32, (value >> 32) as u32])
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &BigUint) -> Ordering {
+        self.limbs.len().cmp(&other.limbs.len()).then_with(|| {
+            self.limbs.iter().rev().cmp(other.limbs.iter().rev())
+        })
+    }
+}
+
+impl PartialOrd for BigUint {
+    #[inline]
+    fn partial_cmp(&self, other: &BigUint) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut value = self.clone();
+        let mut chunks = Vec::new();
+
+        loop {
+            chunks.push(value.div_rem_small(1_000_000_000));
+
+            if value.is_zero() {
+                break;
+            }
+        }
+
+        let mut chunks = chunks.iter().rev();
+
+        if let Some(first) = chunks.next() {
+            write!(f, "{}", first)?;
+        }
+
+        for chunk in chunks {
+            write!(f, "{:09}", chunk)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for BigUint {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl NumberValue {
+    /// Whether the literal has an integer value, e.g. `1.5e3` or `0.5 ether`.
+    #[inline]
+    pub fn is_integer(&self) -> bool {
+        matches!(*self, NumberValue::Integer(_))
+    }
+
+    #[inline]
+    pub fn as_integer(&self) -> Option<&BigUint> {
+        match *self {
+            NumberValue::Integer(ref value) => Some(value),
+            _                               => None,
+        }
+    }
+}
+
+impl fmt::Display for NumberValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NumberValue::Integer(ref value) => write!(f, "{}", value),
+            NumberValue::Rational(ref numerator, ref denominator) => {
+                write!(f, "{}/{}", numerator, denominator)
+            },
+        }
+    }
+}
+
+impl NumberUnit {
+    /// Value of one unit, in wei or seconds.
+    pub fn multiplier(&self) -> u64 {
+        match *self {
+            NumberUnit::None                       => 1,
+            NumberUnit::Ether(EtherUnit::Ether)    => 1_000_000_000_000_000_000,
+            NumberUnit::Ether(EtherUnit::Finney)   => 1_000_000_000_000_000,
+            NumberUnit::Ether(EtherUnit::Szabo)    => 1_000_000_000_000,
+            NumberUnit::Ether(EtherUnit::Gwei)     => 1_000_000_000,
+            NumberUnit::Ether(EtherUnit::Wei)      => 1,
+            NumberUnit::Time(TimeUnit::Years)      => 365 * 24 * 3600,
+            NumberUnit::Time(TimeUnit::Months)     => 30 * 24 * 3600,
+            NumberUnit::Time(TimeUnit::Weeks)      => 7 * 24 * 3600,
+            NumberUnit::Time(TimeUnit::Days)       => 24 * 3600,
+            NumberUnit::Time(TimeUnit::Hours)      => 3600,
+            NumberUnit::Time(TimeUnit::Minutes)    => 60,
+            NumberUnit::Time(TimeUnit::Seconds)    => 1,
+        }
+    }
+}
+
+/// Evaluate the raw source of a number literal (decimal, hexadecimal,
+/// rational or in scientific notation, `_` separators allowed) multiplied
+/// by its unit to an exact value.
+pub fn evaluate_number(raw: &str, unit: NumberUnit) -> Result<NumberValue, NumberError> {
+    let raw = raw.as_bytes();
+
+    let (mantissa, exponent) = if raw.starts_with(b"0x") || raw.starts_with(b"0X") {
+        (parse_hex(&raw[2..])?, 0)
+    } else {
+        parse_decimal(raw)?
+    };
+
+    let mut numerator = mantissa.mul(&BigUint::from(unit.multiplier()));
+
+    if numerator.is_zero() {
+        return Ok(NumberValue::Integer(numerator));
+    }
+
+    if exponent > MAX_EXPONENT {
+        return Err(NumberError::Overflow);
+    }
+
+    if exponent < -MAX_EXPONENT {
+        // Below `10^MAX_EXPONENT`, so it can't be a multiple of `10^-exponent`
+        return match numerator.bits() <= 3 * MAX_EXPONENT as usize {
+            true  => Err(NumberError::Fraction),
+            false => Err(NumberError::Overflow),
+        };
+    }
+
+    if exponent >= 0 {
+        return integer(numerator.mul(&BigUint::pow10(exponent as u32)));
+    }
+
+    // Denominator is `10^-exponent`, reduce the fraction by dividing
+    // out the twos and fives it is made of.
+    let mut twos = -exponent as usize;
+    let mut fives = twos;
+
+    while twos > 0 && numerator.rem_small(2) == 0 {
+        numerator.div_rem_small(2);
+        twos -= 1;
+    }
+
+    while fives > 0 && numerator.rem_small(5) == 0 {
+        numerator.div_rem_small(5);
+        fives -= 1;
+    }
+
+    let mut denominator = BigUint::from(1);
+
+    for _ in 0..fives {
+        denominator.mul_small(5);
+    }
+
+    let denominator = denominator.shl(twos);
+
+    if denominator == BigUint::from(1) {
+        return integer(numerator);
+    }
+
+    if numerator >= denominator.shl(256) {
+        return Err(NumberError::Overflow);
+    }
+
+    Ok(NumberValue::Rational(numerator, denominator))
+}
+
+fn integer(value: BigUint) -> Result<NumberValue, NumberError> {
+    if value.bits() > 256 {
+        return Err(NumberError::Overflow);
+    }
+
+    Ok(NumberValue::Integer(value))
+}
+
+fn parse_hex(digits: &[u8]) -> Result<BigUint, NumberError> {
+    let mut value = BigUint::zero();
+    let mut count = 0;
+
+    for &byte in digits {
+        let digit = match byte {
+            b'0'..=b'9' => byte - b'0',
+            b'a'..=b'f' => byte - b'a' + 10,
+            b'A'..=b'F' => byte - b'A' + 10,
+            b'_'        => continue,
+            _           => return Err(NumberError::Invalid),
+        };
+
+        value.mul_small(16);
+        value.add_small(digit as u32);
+        count += 1;
+    }
+
+    if count == 0 {
+        return Err(NumberError::Invalid);
+    }
+
+    Ok(value)
+}
+
+/// Parse a decimal literal into a mantissa and a decimal exponent.
+fn parse_decimal(raw: &[u8]) -> Result<(BigUint, i64), NumberError> {
+    let mut mantissa = BigUint::zero();
+    let mut exponent = 0i64;
+    let mut count = 0;
+    let mut fraction = false;
+    let mut bytes = raw.iter();
+
+    while let Some(&byte) = bytes.next() {
+        match byte {
+            b'0'..=b'9' => {
+                mantissa.mul_small(10);
+                mantissa.add_small((byte - b'0') as u32);
+                count += 1;
+
+                if fraction {
+                    exponent -= 1;
+                }
+            },
+            b'_'              => {},
+            b'.' if !fraction => fraction = true,
+            b'e' | b'E'       => {
+                let exponent = exponent.saturating_add(parse_exponent(bytes.as_slice())?);
+
+                return match count {
+                    0 => Err(NumberError::Invalid),
+                    _ => Ok((mantissa, exponent)),
+                };
+            },
+            _ => return Err(NumberError::Invalid),
+        }
+    }
+
+    if count == 0 {
+        return Err(NumberError::Invalid);
+    }
+
+    Ok((mantissa, exponent))
+}
+
+fn parse_exponent(raw: &[u8]) -> Result<i64, NumberError> {
+    let (negative, digits) = match raw.first() {
+        Some(&b'-') => (true, &raw[1..]),
+        Some(&b'+') => (false, &raw[1..]),
+        _           => (false, raw),
+    };
+
+    let mut value = 0i64;
+    let mut count = 0;
+
+    for &byte in digits {
+        match byte {
+            b'0'..=b'9' => {
+                value = value.saturating_mul(10).saturating_add((byte - b'0') as i64);
+                count += 1;
+            },
+            b'_' => {},
+            _    => return Err(NumberError::Invalid),
+        }
+    }
+
+    if count == 0 {
+        return Err(NumberError::Invalid);
+    }
+
+    Ok(if negative { -value } else { value })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn eval(raw: &str) -> String {
+        evaluate_number(raw, NumberUnit::None).unwrap().to_string()
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(eval("0"), "0");
+        assert_eq!(eval("42"), "42");
+        assert_eq!(eval("1_000_000"), "1000000");
+        assert_eq!(eval("0xff"), "255");
+        assert_eq!(eval("0xDEAD_beef"), "3735928559");
+        assert_eq!(eval("12345678901234567890123456789"), "12345678901234567890123456789");
+    }
+
+    #[test]
+    fn scientific_notation() {
+        assert_eq!(eval("1e18"), "1000000000000000000");
+        assert_eq!(eval("2.5e1"), "25");
+        assert_eq!(eval("1_0e-1"), "1");
+        assert_eq!(eval(".5e1"), "5");
+        assert_eq!(eval("0e99999999999"), "0");
+    }
+
+    #[test]
+    fn rationals() {
+        assert_eq!(eval("0.5"), "1/2");
+        assert_eq!(eval(".25"), "1/4");
+        assert_eq!(eval("1.75"), "7/4");
+        assert_eq!(eval("3e-1"), "3/10");
+        assert_eq!(eval("101e-2"), "101/100");
+        assert_eq!(evaluate_number("2.5", NumberUnit::None).unwrap().is_integer(), false);
+    }
+
+    #[test]
+    fn units() {
+        let ether = NumberUnit::Ether(EtherUnit::Ether);
+        let gwei  = NumberUnit::Ether(EtherUnit::Gwei);
+        let weeks = NumberUnit::Time(TimeUnit::Weeks);
+
+        assert_eq!(evaluate_number("1", ether).unwrap().to_string(), "1000000000000000000");
+        assert_eq!(evaluate_number("0.5", ether).unwrap().to_string(), "500000000000000000");
+        assert_eq!(evaluate_number("1.5", gwei).unwrap().to_string(), "1500000000");
+        assert_eq!(evaluate_number("2", weeks).unwrap().to_string(), "1209600");
+        assert_eq!(evaluate_number("1e-19", ether).unwrap().to_string(), "1/10");
+    }
+
+    #[test]
+    fn overflow() {
+        let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        let ether = NumberUnit::Ether(EtherUnit::Ether);
+
+        assert_eq!(eval(max), max);
+        assert_eq!(eval("0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"), max);
+        assert_eq!(evaluate_number("0x1_0000000000000000000000000000000000000000000000000000000000000000", NumberUnit::None), Err(NumberError::Overflow));
+        assert_eq!(evaluate_number("115792089237316195423570985008687907853269984665640564039457584007913129639936", NumberUnit::None), Err(NumberError::Overflow));
+        assert_eq!(evaluate_number("1e78", NumberUnit::None), Err(NumberError::Overflow));
+        assert_eq!(evaluate_number("1e60", ether), Err(NumberError::Overflow));
+        assert_eq!(evaluate_number("1e99999999999", NumberUnit::None), Err(NumberError::Overflow));
+    }
+
+    #[test]
+    fn tiny_fractions() {
+        assert_eq!(evaluate_number("1e-99999999999", NumberUnit::None), Err(NumberError::Fraction));
+        assert_eq!(evaluate_number(".5e-9223372036854775807", NumberUnit::None), Err(NumberError::Fraction));
+        assert_eq!(evaluate_number("0e-99999999999", NumberUnit::None).unwrap().to_string(), "0");
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(evaluate_number("", NumberUnit::None), Err(NumberError::Invalid));
+        assert_eq!(evaluate_number("0x", NumberUnit::None), Err(NumberError::Invalid));
+        assert_eq!(evaluate_number("1e", NumberUnit::None), Err(NumberError::Invalid));
+        assert_eq!(evaluate_number("1.2.3", NumberUnit::None), Err(NumberError::Invalid));
+        assert_eq!(evaluate_number("foo", NumberUnit::None), Err(NumberError::Invalid));
+    }
+
+    #[test]
+    fn big_uint() {
+        let value = BigUint::pow10(20);
+
+        assert_eq!(value.to_string(), "100000000000000000000");
+        assert_eq!(value.to_u64(), None);
+        assert_eq!(BigUint::from(300).to_u64(), Some(300));
+        assert_eq!(BigUint::from(0x1234).to_be_bytes(), vec![0x12, 0x34]);
+        assert_eq!(BigUint::from(1).shl(256).bits(), 257);
+        assert!(BigUint::from(1).shl(64) > BigUint::from(u64::MAX));
+    }
+}
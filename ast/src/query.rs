@@ -0,0 +1,671 @@
+//! Queries over the AST in the style of CSS selectors.
+//!
+//! Nodes are selected by their kind, the same as in `AstIndex` and `dump`,
+//! and by their fields as shown by `dump`:
+//!
+//! + `FunctionDefinition` matches any function, `*` matches any node.
+//! + `A B` matches `B` within `A`, `A > B` matches `B` that is a child of `A`.
+//!   A query starting with `>` matches the top level nodes.
+//! + `[name]` matches nodes with the field, such as `[constant]`, while
+//!   `[name=value]`, `[name!=value]`, `[name^=prefix]`, `[name$=suffix]`
+//!   and `[name*=text]` compare its value. `[name~=value]` matches lists,
+//!   such as `[inherits~=Owned]`. Values can be quoted with `"`.
+//! + `:is(A, B)` matches either `A` or `B`, `:not(A)` matches when `A`
+//!   doesn't, and `:has(A)` or `:has(> A)` matches nodes containing `A`.
+//! + `A, B` matches either `A` or `B`.
+//! + `@name` after a node, such as `FunctionDefinition@function`, captures
+//!   the node matched there.
+//!
+//! For example, public functions without modifiers:
+//!
+//! ```text
+//! FunctionDefinition[visibility=public]:not(:has(> ModifierInvocation))
+//! ```
+//!
+//! Or calls to `.call.value(...)` within loops:
+//!
+//! ```text
+//! :is(ForStatement, WhileStatement, DoWhileStatement)@loop
+//!     CallExpression:has(> MemberAccessExpression[member=value] > [member=call])
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use dump::{self, Entry, Value};
+use Program;
+
+/// Error produced by parsing a `Query`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum QueryError {
+    /// The query is malformed at the byte offset.
+    Unexpected {
+        offset: usize,
+        expected: &'static str,
+    },
+
+    /// Unknown pseudo class, such as `:first`.
+    UnknownPseudoClass {
+        offset: usize,
+        name: String,
+    },
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            QueryError::Unexpected { offset, expected } => {
+                write!(f, "expected {} at {}", expected, offset)
+            },
+            QueryError::UnknownPseudoClass { offset, ref name } => {
+                write!(f, "unknown pseudo class `:{}` at {}", name, offset)
+            },
+        }
+    }
+}
+
+impl ::std::error::Error for QueryError {}
+
+type Result<T> = ::std::result::Result<T, QueryError>;
+
+/// Parsed query, see module docs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query {
+    selectors: Vec<Selector>,
+}
+
+/// A node matched by a `Query`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryMatch {
+    pub kind: &'static str,
+    pub start: u32,
+    pub end: u32,
+
+    /// Nodes captured with `@name`, in the order of the source.
+    pub captures: Vec<Capture>,
+}
+
+impl QueryMatch {
+    /// Get the first capture with the name.
+    pub fn capture(&self, name: &str) -> Option<&Capture> {
+        self.captures.iter().find(|capture| capture.name == name)
+    }
+}
+
+/// A node captured with `@name`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Capture {
+    pub name: String,
+    pub kind: &'static str,
+    pub start: u32,
+    pub end: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Combinator {
+    Descendant,
+    Child,
+}
+
+/// Compounds along with their relation to the compound before them,
+/// the relation of the first being to the node of `:has`, or the root.
+#[derive(Clone, Debug, PartialEq)]
+struct Selector {
+    parts: Vec<(Combinator, Compound)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Compound {
+    kind: Option<String>,
+    filters: Vec<Filter>,
+    capture: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Filter {
+    Field(String, Option<(Operator, String)>),
+    Is(Vec<Selector>),
+    Not(Vec<Selector>),
+    Has(Vec<Selector>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Operator {
+    Equal,
+    NotEqual,
+    Prefix,
+    Suffix,
+    Contains,
+    Includes,
+}
+
+impl Query {
+    pub fn parse(query: &str) -> Result<Query> {
+        let mut parser = QueryParser {
+            query,
+            offset: 0,
+        };
+
+        let selectors = parser.selectors()?;
+
+        match parser.peek() {
+            None => Ok(Query { selectors }),
+            Some(_) => Err(parser.expected("`,` or the end of the query")),
+        }
+    }
+
+    /// Get the nodes of the `Program` matching the query, in pre-order.
+    pub fn matches(&self, program: &Program) -> Vec<QueryMatch> {
+        let tree = Tree::new(dump::entries(program));
+        let mut matches = Vec::new();
+        let mut captures = Vec::new();
+
+        for node in 0..tree.entries.len() {
+            let matched = self.selectors.iter().any(|selector| {
+                tree.matches(node, &selector.parts, None, &mut captures)
+            });
+
+            if matched {
+                let entry = &tree.entries[node];
+
+                captures.sort_by_key(|&(node, _)| node);
+
+                matches.push(QueryMatch {
+                    kind: entry.kind,
+                    start: entry.start,
+                    end: entry.end,
+                    captures: captures.drain(..).map(|(node, name)| {
+                        let entry = &tree.entries[node];
+
+                        Capture {
+                            name,
+                            kind: entry.kind,
+                            start: entry.start,
+                            end: entry.end,
+                        }
+                    }).collect(),
+                });
+            }
+        }
+
+        matches
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(query: &str) -> Result<Query> {
+        Query::parse(query)
+    }
+}
+
+impl<'ast> Program<'ast> {
+    /// Parse the query and get the nodes matching it, see `query` module.
+    pub fn query(&self, query: &str) -> Result<Vec<QueryMatch>> {
+        Ok(Query::parse(query)?.matches(self))
+    }
+}
+
+struct QueryParser<'a> {
+    query: &'a str,
+    offset: usize,
+}
+
+impl<'a> QueryParser<'a> {
+    #[inline]
+    fn peek(&self) -> Option<u8> {
+        self.query.as_bytes().get(self.offset).cloned()
+    }
+
+    #[inline]
+    fn eat(&mut self, byte: u8) -> bool {
+        if self.peek() == Some(byte) {
+            self.offset += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, byte: u8, expected: &'static str) -> Result<()> {
+        match self.eat(byte) {
+            true  => Ok(()),
+            false => Err(self.expected(expected)),
+        }
+    }
+
+    fn expected(&self, expected: &'static str) -> QueryError {
+        QueryError::Unexpected {
+            offset: self.offset,
+            expected,
+        }
+    }
+
+    /// Skip whitespace, returns `true` if there was any.
+    fn whitespace(&mut self) -> bool {
+        let start = self.offset;
+
+        while matches!(self.peek(), Some(byte) if byte.is_ascii_whitespace()) {
+            self.offset += 1;
+        }
+
+        self.offset != start
+    }
+
+    fn name(&mut self) -> Option<&'a str> {
+        let start = self.offset;
+
+        while matches!(self.peek(), Some(byte) if byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'-') {
+            self.offset += 1;
+        }
+
+        match self.offset != start {
+            true  => Some(&self.query[start..self.offset]),
+            false => None,
+        }
+    }
+
+    fn selectors(&mut self) -> Result<Vec<Selector>> {
+        let mut selectors = vec![self.selector()?];
+
+        while self.eat(b',') {
+            selectors.push(self.selector()?);
+        }
+
+        Ok(selectors)
+    }
+
+    fn selector(&mut self) -> Result<Selector> {
+        self.whitespace();
+
+        let mut combinator = match self.eat(b'>') {
+            true  => Combinator::Child,
+            false => Combinator::Descendant,
+        };
+        let mut parts = Vec::new();
+
+        loop {
+            self.whitespace();
+            parts.push((combinator, self.compound()?));
+
+            let spaced = self.whitespace();
+
+            combinator = match self.peek() {
+                Some(b'>')                   => { self.offset += 1; Combinator::Child },
+                None | Some(b',') | Some(b')') => return Ok(Selector { parts }),
+                Some(_) if spaced            => Combinator::Descendant,
+                Some(_)                      => return Err(self.expected("a combinator")),
+            };
+        }
+    }
+
+    fn compound(&mut self) -> Result<Compound> {
+        let kind = match self.peek() {
+            Some(b'*') => { self.offset += 1; None },
+            Some(b'[') | Some(b':') | Some(b'@') => None,
+            _ => match self.name() {
+                Some(kind) => Some(kind.into()),
+                None       => return Err(self.expected("a node kind")),
+            },
+        };
+        let mut filters = Vec::new();
+
+        loop {
+            if self.eat(b'[') {
+                filters.push(self.field()?);
+            } else if self.eat(b':') {
+                filters.push(self.pseudo_class()?);
+            } else {
+                break;
+            }
+        }
+
+        let capture = match self.eat(b'@') {
+            true => match self.name() {
+                Some(name) => Some(name.into()),
+                None       => return Err(self.expected("a capture name")),
+            },
+            false => None,
+        };
+
+        Ok(Compound { kind, filters, capture })
+    }
+
+    fn field(&mut self) -> Result<Filter> {
+        self.whitespace();
+
+        let name = match self.name() {
+            Some(name) => name.into(),
+            None       => return Err(self.expected("a field name")),
+        };
+
+        self.whitespace();
+
+        let operator = match self.peek() {
+            Some(b']') => {
+                self.offset += 1;
+
+                return Ok(Filter::Field(name, None));
+            },
+            Some(b'=') => Operator::Equal,
+            Some(b'!') => Operator::NotEqual,
+            Some(b'^') => Operator::Prefix,
+            Some(b'$') => Operator::Suffix,
+            Some(b'*') => Operator::Contains,
+            Some(b'~') => Operator::Includes,
+            _          => return Err(self.expected("`]` or an operator")),
+        };
+
+        if operator != Operator::Equal {
+            self.offset += 1;
+        }
+
+        self.expect(b'=', "`=`")?;
+        self.whitespace();
+
+        let value = self.value()?;
+
+        self.whitespace();
+        self.expect(b']', "`]`")?;
+
+        Ok(Filter::Field(name, Some((operator, value))))
+    }
+
+    fn value(&mut self) -> Result<String> {
+        if !self.eat(b'"') {
+            let start = self.offset;
+
+            while matches!(self.peek(), Some(byte) if byte != b']' && !byte.is_ascii_whitespace()) {
+                self.offset += 1;
+            }
+
+            return match self.offset != start {
+                true  => Ok(self.query[start..self.offset].into()),
+                false => Err(self.expected("a value")),
+            };
+        }
+
+        let mut value = String::new();
+        let mut chars = self.query[self.offset..].char_indices();
+
+        while let Some((index, ch)) = chars.next() {
+            match ch {
+                '"' => {
+                    self.offset += index + 1;
+
+                    return Ok(value);
+                },
+                '\\' => match chars.next() {
+                    Some((_, ch)) => value.push(ch),
+                    None          => break,
+                },
+                ch => value.push(ch),
+            }
+        }
+
+        self.offset = self.query.len();
+
+        Err(self.expected("`\"`"))
+    }
+
+    fn pseudo_class(&mut self) -> Result<Filter> {
+        let start = self.offset;
+        let name = self.name().unwrap_or("");
+
+        let filter: fn(Vec<Selector>) -> Filter = match name {
+            "is"  => Filter::Is,
+            "not" => Filter::Not,
+            "has" => Filter::Has,
+            _     => {
+                return Err(QueryError::UnknownPseudoClass {
+                    offset: start,
+                    name: name.into(),
+                });
+            },
+        };
+
+        self.expect(b'(', "`(`")?;
+
+        let selectors = self.selectors()?;
+
+        self.expect(b')', "`)`")?;
+
+        Ok(filter(selectors))
+    }
+}
+
+/// Entries of a `Program` along with their relations.
+struct Tree {
+    entries: Vec<Entry>,
+    parents: Vec<Option<usize>>,
+
+    /// Index past the last descendant of each entry.
+    ends: Vec<usize>,
+}
+
+impl Tree {
+    fn new(entries: Vec<Entry>) -> Self {
+        let mut parents = Vec::with_capacity(entries.len());
+        let mut ends = vec![entries.len(); entries.len()];
+        let mut stack: Vec<usize> = Vec::new();
+
+        for (index, entry) in entries.iter().enumerate() {
+            while let Some(&last) = stack.last() {
+                if entries[last].depth < entry.depth {
+                    break;
+                }
+
+                ends[last] = index;
+                stack.pop();
+            }
+
+            parents.push(stack.last().cloned());
+            stack.push(index);
+        }
+
+        Tree { entries, parents, ends }
+    }
+
+    /// Check if the node matches the last of `parts`, and the nodes related
+    /// to it match the rest, pushing the captures if they all do.
+    fn matches(
+        &self,
+        node: usize,
+        parts: &[(Combinator, Compound)],
+        anchor: Option<usize>,
+        captures: &mut Vec<(usize, String)>,
+    ) -> bool {
+        let (&(combinator, ref compound), rest) = match parts.split_last() {
+            Some(last) => last,
+            None       => return false,
+        };
+        let mark = captures.len();
+
+        let matched = self.compound(node, compound, captures) && match (rest.is_empty(), combinator) {
+            (true, Combinator::Child) => self.parents[node] == anchor,
+            (true, Combinator::Descendant) => match anchor {
+                Some(anchor) => anchor < node && node < self.ends[anchor],
+                None         => true,
+            },
+            (false, Combinator::Child) => match self.parents[node] {
+                Some(parent) => self.matches(parent, rest, anchor, captures),
+                None         => false,
+            },
+            (false, Combinator::Descendant) => {
+                let mut parent = self.parents[node];
+
+                loop {
+                    match parent {
+                        Some(ancestor) if self.matches(ancestor, rest, anchor, captures) => break true,
+                        Some(ancestor) => parent = self.parents[ancestor],
+                        None           => break false,
+                    }
+                }
+            },
+        };
+
+        if !matched {
+            captures.truncate(mark);
+        }
+
+        matched
+    }
+
+    fn compound(&self, node: usize, compound: &Compound, captures: &mut Vec<(usize, String)>) -> bool {
+        let entry = &self.entries[node];
+
+        if matches!(compound.kind, Some(ref kind) if kind != entry.kind) {
+            return false;
+        }
+
+        for filter in &compound.filters {
+            let matched = match *filter {
+                Filter::Field(ref name, ref operator) => field(entry, name, operator),
+                Filter::Is(ref selectors) => {
+                    selectors.iter().any(|selector| self.matches(node, &selector.parts, None, captures))
+                },
+                Filter::Not(ref selectors) => {
+                    let mut ignored = Vec::new();
+
+                    !selectors.iter().any(|selector| self.matches(node, &selector.parts, None, &mut ignored))
+                },
+                Filter::Has(ref selectors) => {
+                    (node + 1..self.ends[node]).any(|descendant| {
+                        selectors.iter().any(|selector| {
+                            self.matches(descendant, &selector.parts, Some(node), captures)
+                        })
+                    })
+                },
+            };
+
+            if !matched {
+                return false;
+            }
+        }
+
+        if let Some(ref name) = compound.capture {
+            captures.push((node, name.clone()));
+        }
+
+        true
+    }
+}
+
+fn field(entry: &Entry, name: &str, operator: &Option<(Operator, String)>) -> bool {
+    let (value, operator, expected) = match (entry.field(name), operator) {
+        (None, &Some((Operator::NotEqual, _)))        => return true,
+        (None, _)                                     => return false,
+        (Some(_), &None)                              => return true,
+        (Some(value), &Some((operator, ref expected))) => (value, operator, expected.as_str()),
+    };
+    let text = match *value {
+        Value::Flag                                     => String::new(),
+        Value::Text(ref text) | Value::Quoted(ref text) => text.clone(),
+        Value::List(ref items)                          => items.join(", "),
+    };
+
+    match operator {
+        Operator::Equal    => text == expected,
+        Operator::NotEqual => text != expected,
+        Operator::Prefix   => text.starts_with(expected),
+        Operator::Suffix   => text.ends_with(expected),
+        Operator::Contains => text.contains(expected),
+        Operator::Includes => match *value {
+            Value::List(ref items) => items.iter().any(|item| item == expected),
+            _                      => text == expected,
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use {ElementaryTypeName, FunctionVisibility, NumberUnit};
+
+    fn program<'ast>() -> Program<'ast> {
+        Program::build(|b| {
+            let uint = ElementaryTypeName::Uint(32);
+            let a = b.at(30, 60).function(
+                Some("a"),
+                b.list(None),
+                Some(FunctionVisibility::Public),
+                None,
+                b.list(None),
+                Some(b.at(50, 60).block(Some(b.at(52, 58).return_statement(Some(b.at(59, 60).number("1", NumberUnit::None)))))),
+            );
+            let c = b.at(61, 90).function(
+                Some("c"),
+                b.list(Some(b.at(72, 78).parameter(b.at(72, 76).elementary(uint), Some("x")))),
+                Some(FunctionVisibility::Private),
+                None,
+                b.list(None),
+                Some(b.at(85, 90).block(None)),
+            );
+
+            b.list(Some(b.at(0, 100).contract("Foo", &["Owned", "Mortal"], vec![a, c])))
+        })
+    }
+
+    fn spans(matches: Vec<QueryMatch>) -> Vec<(&'static str, u32, u32)> {
+        matches.into_iter().map(|m| (m.kind, m.start, m.end)).collect()
+    }
+
+    #[test]
+    fn kinds_and_fields() {
+        let program = program();
+
+        assert_eq!(spans(program.query("FunctionDefinition").unwrap()), vec![
+            ("FunctionDefinition", 30, 60),
+            ("FunctionDefinition", 61, 90),
+        ]);
+        assert_eq!(spans(program.query("FunctionDefinition[visibility=private]").unwrap()), vec![
+            ("FunctionDefinition", 61, 90),
+        ]);
+        assert_eq!(spans(program.query("[inherits~=Owned], Parameter[name^=\"x\"]").unwrap()), vec![
+            ("ContractDefinition", 0, 100),
+            ("Parameter", 72, 78),
+        ]);
+        assert_eq!(program.query("[inherits=Owned]").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn combinators() {
+        let program = program();
+
+        assert_eq!(spans(program.query("> *").unwrap()), vec![("ContractDefinition", 0, 100)]);
+        assert_eq!(spans(program.query("ContractDefinition > ElementaryTypeName").unwrap()), vec![]);
+        assert_eq!(spans(program.query("ContractDefinition ElementaryTypeName").unwrap()), vec![
+            ("ElementaryTypeName", 72, 76),
+        ]);
+        assert_eq!(spans(program.query("FunctionDefinition:not(:has(> Parameter))").unwrap()), vec![
+            ("FunctionDefinition", 30, 60),
+        ]);
+        assert_eq!(spans(program.query(":is(ReturnStatement, Parameter)").unwrap()), vec![
+            ("ReturnStatement", 52, 58),
+            ("Parameter", 72, 78),
+        ]);
+    }
+
+    #[test]
+    fn captures() {
+        let matches = program().query("FunctionDefinition@function > Block ReturnStatement > *@value").unwrap();
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!((matches[0].kind, matches[0].start), ("PrimitiveExpression", 59));
+        assert_eq!(matches[0].captures, vec![
+            Capture { name: "function".into(), kind: "FunctionDefinition", start: 30, end: 60 },
+            Capture { name: "value".into(), kind: "PrimitiveExpression", start: 59, end: 60 },
+        ]);
+        assert_eq!(matches[0].capture("function").map(|capture| capture.start), Some(30));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Query::parse("Foo["), Err(QueryError::Unexpected { offset: 4, expected: "a field name" }));
+        assert_eq!(Query::parse("Foo:bar(Baz)"), Err(QueryError::UnknownPseudoClass { offset: 4, name: "bar".into() }));
+        assert_eq!(Query::parse("Foo)"), Err(QueryError::Unexpected { offset: 3, expected: "`,` or the end of the query" }));
+        assert_eq!(Query::parse("[name=\"x]").unwrap_err().to_string(), "expected `\"` at 9");
+        assert!("A > B, :is(C D)@c".parse::<Query>().is_ok());
+    }
+}
@@ -0,0 +1,446 @@
+//! `Serialize` implementations for the AST, enabled with the `serde` feature.
+//!
+//! Every `Node` is serialized as a map with a `type` entry first, followed
+//! by the fields of the node in declaration order, and `start` and `end`
+//! last. Enum nodes such as `Statement` are tagged with the name of the
+//! variant. See `Program::to_json` for the details.
+
+use serde::ser::{Serialize, Serializer, SerializeMap};
+
+use {*};
+
+/// Values stored in `Node`s, write the `type` and their own fields into
+/// the map of the `Node`.
+pub trait SerializeNode {
+    fn serialize_fields<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error>;
+}
+
+impl<'ast, T: SerializeNode> Serialize for Node<'ast, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+
+        self.value.serialize_fields(&mut map)?;
+        map.serialize_entry("start", &self.start)?;
+        map.serialize_entry("end", &self.end)?;
+        map.end()
+    }
+}
+
+impl<'ast> Serialize for Program<'ast> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(2))?;
+
+        map.serialize_entry("type", "Program")?;
+        map.serialize_entry("body", &self.body())?;
+        map.end()
+    }
+}
+
+/// `StringLiteral` and `Identifier` are the same type, this wrapper
+/// serializes the former.
+struct Literal<'ast>(StringLiteralNode<'ast>);
+
+impl<'ast> Serialize for Literal<'ast> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(4))?;
+
+        map.serialize_entry("type", "StringLiteral")?;
+        map.serialize_entry("value", self.0.value)?;
+        map.serialize_entry("start", &self.0.start)?;
+        map.serialize_entry("end", &self.0.end)?;
+        map.end()
+    }
+}
+
+/// Implement `SerializeNode` for structs, writing all listed fields as is.
+macro_rules! impl_struct {
+    ($( $name:ident { $( $field:ident ),* } )*) => {
+        $(
+            impl<'ast> SerializeNode for $name<'ast> {
+                fn serialize_fields<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+                    map.serialize_entry("type", stringify!($name))?;
+                    $(
+                        map.serialize_entry(stringify!($field), &self.$field)?;
+                    )*
+                    Ok(())
+                }
+            }
+        )*
+    }
+}
+
+/// Implement `SerializeNode` for fieldless enums, writing their source
+/// representation as `value`.
+macro_rules! impl_keyword {
+    ($( $name:ident )*) => {
+        $(
+            impl SerializeNode for $name {
+                fn serialize_fields<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+                    map.serialize_entry("type", stringify!($name))?;
+                    map.serialize_entry("value", self.as_str())
+                }
+            }
+        )*
+    }
+}
+
+impl_struct! {
+    ContractDefinition { doc, name, inherits, body }
+    StateVariableDeclaration { doc, type_name, visibility, constant, name, init }
+    UsingForDeclaration { id, type_name }
+    StructDefinition { name, body }
+    ModifierDefinition { doc, name, params, block }
+    FunctionDefinition { doc, name, params, visibility, mutability, modifiers, returns, block }
+    EventDefinition { doc, anonymous, name, params }
+    IndexedParameter { type_name, indexed, name }
+    EnumDefinition { name, variants }
+    Parameter { type_name, name }
+    ModifierInvocation { id, arguments }
+    Import { symbol, alias }
+    DocComment { tags }
+    Mapping { from, to }
+    VariableDeclaration { type_name, location, id }
+    Block { body }
+    IfStatement { test, consequent, alternate }
+    WhileStatement { test, body }
+    ForStatement { init, test, update, body }
+    DoWhileStatement { body, test }
+    ReturnStatement { value }
+    VariableDefinitionStatement { declaration, init }
+    InferredDefinitionStatement { ids, init }
+    PrefixExpression { operator, operand }
+    PostfixExpression { operand, operator }
+    BinaryExpression { left, operator, right }
+    AssignmentExpression { left, operator, right }
+    TupleExpression { expressions }
+    CallExpression { callee, arguments }
+    MemberAccessExpression { object, member }
+    IndexAccessExpression { array, index }
+    ConditionalExpression { test, consequent, alternate }
+    InlineAssemblyBlock { items }
+    FunctionalAssemblyExpression { id, arguments }
+    AssemblyLocalBinding { id, init }
+    AssemblyAssignment { id, init }
+    AssemblyLabel { id }
+}
+
+impl_keyword! {
+    StateVariableVisibility
+    FunctionVisibility
+    StateMutability
+    StorageLocation
+    PrefixOperator
+    PostfixOperator
+    BinaryOperator
+    AssignmentOperator
+}
+
+impl<'ast> SerializeNode for Identifier<'ast> {
+    fn serialize_fields<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+        map.serialize_entry("type", "Identifier")?;
+        map.serialize_entry("name", self)
+    }
+}
+
+impl SerializeNode for Flag {
+    fn serialize_fields<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+        map.serialize_entry("type", "Flag")
+    }
+}
+
+impl SerializeNode for ElementaryTypeName {
+    fn serialize_fields<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+        map.serialize_entry("type", "ElementaryTypeName")?;
+        map.serialize_entry("name", &self.to_string())
+    }
+}
+
+impl<'ast> SerializeNode for SourceUnit<'ast> {
+    fn serialize_fields<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+        match *self {
+            SourceUnit::PragmaDirective(ref pragma) => {
+                map.serialize_entry("type", "PragmaDirective")?;
+                map.serialize_entry("version", pragma.version)
+            },
+            SourceUnit::ImportDirective(ref import) => import.serialize_fields(map),
+            SourceUnit::ContractDefinition(ref def) => def.serialize_fields(map),
+            SourceUnit::Error                       => map.serialize_entry("type", "Error"),
+        }
+    }
+}
+
+impl<'ast> SerializeNode for ImportDirective<'ast> {
+    fn serialize_fields<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+        map.serialize_entry("type", "ImportDirective")?;
+
+        match *self {
+            ImportDirective::Global { source, alias } => {
+                map.serialize_entry("kind", "global")?;
+                map.serialize_entry("source", &Literal(source))?;
+                map.serialize_entry("alias", &alias)
+            },
+            ImportDirective::From { symbol, alias, source } => {
+                map.serialize_entry("kind", "from")?;
+                map.serialize_entry("symbol", &symbol)?;
+                map.serialize_entry("alias", &alias)?;
+                map.serialize_entry("source", &Literal(source))
+            },
+            ImportDirective::ManyFrom { imports, source } => {
+                map.serialize_entry("kind", "many")?;
+                map.serialize_entry("imports", &imports)?;
+                map.serialize_entry("source", &Literal(source))
+            },
+        }
+    }
+}
+
+impl<'ast> SerializeNode for ContractPart<'ast> {
+    fn serialize_fields<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+        match *self {
+            ContractPart::StateVariableDeclaration(ref var) => var.serialize_fields(map),
+            ContractPart::UsingForDeclaration(ref using)    => using.serialize_fields(map),
+            ContractPart::StructDefinition(ref def)         => def.serialize_fields(map),
+            ContractPart::ModifierDefinition(ref def)       => def.serialize_fields(map),
+            ContractPart::FunctionDefinition(ref def)       => def.serialize_fields(map),
+            ContractPart::EventDefinition(ref def)          => def.serialize_fields(map),
+            ContractPart::EnumDefinition(ref def)           => def.serialize_fields(map),
+            ContractPart::Error                             => map.serialize_entry("type", "Error"),
+        }
+    }
+}
+
+impl<'ast> SerializeNode for DocTag<'ast> {
+    fn serialize_fields<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+        let (kind, name) = match self.kind {
+            DocTagKind::Title            => ("title", None),
+            DocTagKind::Author           => ("author", None),
+            DocTagKind::Notice           => ("notice", None),
+            DocTagKind::Dev              => ("dev", None),
+            DocTagKind::Param(name)      => ("param", Some(name)),
+            DocTagKind::Return           => ("return", None),
+            DocTagKind::Inheritdoc(name) => ("inheritdoc", Some(name)),
+            DocTagKind::Custom(name)     => ("custom", Some(name)),
+            DocTagKind::Unknown(name)    => ("unknown", Some(name)),
+        };
+
+        map.serialize_entry("type", "DocTag")?;
+        map.serialize_entry("kind", kind)?;
+        map.serialize_entry("name", &name)?;
+        map.serialize_entry("text", self.text)
+    }
+}
+
+impl<'ast> SerializeNode for TypeName<'ast> {
+    fn serialize_fields<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+        match *self {
+            TypeName::ElementaryTypeName(ref elementary) => elementary.serialize_fields(map),
+            TypeName::UserDefinedTypeName(name) => {
+                map.serialize_entry("type", "UserDefinedTypeName")?;
+                map.serialize_entry("name", name)
+            },
+            TypeName::Mapping(ref mapping) => mapping.serialize_fields(map),
+            TypeName::ArrayTypeName        => map.serialize_entry("type", "ArrayTypeName"),
+            TypeName::FunctionTypeName     => map.serialize_entry("type", "FunctionTypeName"),
+        }
+    }
+}
+
+impl<'ast> SerializeNode for Statement<'ast> {
+    fn serialize_fields<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+        match *self {
+            Statement::IfStatement(ref stmt)                 => stmt.serialize_fields(map),
+            Statement::WhileStatement(ref stmt)              => stmt.serialize_fields(map),
+            Statement::ForStatement(ref stmt)                => stmt.serialize_fields(map),
+            Statement::DoWhileStatement(ref stmt)            => stmt.serialize_fields(map),
+            Statement::ReturnStatement(ref stmt)             => stmt.serialize_fields(map),
+            Statement::VariableDefinitionStatement(ref stmt) => stmt.serialize_fields(map),
+            Statement::InferredDefinitionStatement(ref stmt) => stmt.serialize_fields(map),
+            Statement::BlockStatement(ref block) => {
+                map.serialize_entry("type", "BlockStatement")?;
+                map.serialize_entry("body", &block.body)
+            },
+            Statement::InlineAssemblyStatement(ref stmt) => {
+                map.serialize_entry("type", "InlineAssemblyStatement")?;
+                map.serialize_entry("string", &stmt.string.map(Literal))?;
+                map.serialize_entry("block", &stmt.block)
+            },
+            Statement::ExpressionStatement(ref expression) => {
+                map.serialize_entry("type", "ExpressionStatement")?;
+                map.serialize_entry("expression", expression)
+            },
+            Statement::Placeholder       => map.serialize_entry("type", "Placeholder"),
+            Statement::ContinueStatement => map.serialize_entry("type", "ContinueStatement"),
+            Statement::BreakStatement    => map.serialize_entry("type", "BreakStatement"),
+            Statement::ThrowStatement    => map.serialize_entry("type", "ThrowStatement"),
+            Statement::Error             => map.serialize_entry("type", "Error"),
+        }
+    }
+}
+
+impl<'ast> SerializeNode for SimpleStatement<'ast> {
+    fn serialize_fields<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+        match *self {
+            SimpleStatement::VariableDefinitionStatement(ref stmt) => stmt.serialize_fields(map),
+            SimpleStatement::InferredDefinitionStatement(ref stmt) => stmt.serialize_fields(map),
+            SimpleStatement::ExpressionStatement(ref expression) => {
+                map.serialize_entry("type", "ExpressionStatement")?;
+                map.serialize_entry("expression", expression)
+            },
+        }
+    }
+}
+
+impl<'ast> SerializeNode for Expression<'ast> {
+    fn serialize_fields<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+        match *self {
+            Expression::ThisExpression => map.serialize_entry("type", "ThisExpression"),
+            Expression::IdentifierExpression(name) => {
+                map.serialize_entry("type", "IdentifierExpression")?;
+                map.serialize_entry("name", name)
+            },
+            Expression::PrimitiveExpression(ref primitive) => {
+                map.serialize_entry("type", "PrimitiveExpression")?;
+                serialize_primitive(primitive, map)
+            },
+            Expression::PrefixExpression(ref expr)       => expr.serialize_fields(map),
+            Expression::PostfixExpression(ref expr)      => expr.serialize_fields(map),
+            Expression::BinaryExpression(ref expr)       => expr.serialize_fields(map),
+            Expression::AssignmentExpression(ref expr)   => expr.serialize_fields(map),
+            Expression::TupleExpression(ref expr)        => expr.serialize_fields(map),
+            Expression::CallExpression(ref expr)         => expr.serialize_fields(map),
+            Expression::MemberAccessExpression(ref expr) => expr.serialize_fields(map),
+            Expression::IndexAccessExpression(ref expr)  => expr.serialize_fields(map),
+            Expression::ConditionalExpression(ref expr)  => expr.serialize_fields(map),
+            Expression::ElementaryTypeExpression(elementary) => {
+                map.serialize_entry("type", "ElementaryTypeExpression")?;
+                map.serialize_entry("name", &elementary.to_string())
+            },
+        }
+    }
+}
+
+impl<'ast> SerializeNode for AssemblyItem<'ast> {
+    fn serialize_fields<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+        match *self {
+            AssemblyItem::Identifier(name) => {
+                map.serialize_entry("type", "Identifier")?;
+                map.serialize_entry("name", name)
+            },
+            AssemblyItem::FunctionalAssemblyExpression(ref expr) => expr.serialize_fields(map),
+            AssemblyItem::InlineAssemblyBlock(ref block)         => block.serialize_fields(map),
+            AssemblyItem::AssemblyLocalBinding(ref binding)      => binding.serialize_fields(map),
+            AssemblyItem::AssemblyAssignment(ref assignment)     => assignment.serialize_fields(map),
+            AssemblyItem::AssemblyLabel(ref label)               => label.serialize_fields(map),
+            AssemblyItem::NumberLiteral(ref primitive) => {
+                map.serialize_entry("type", "NumberLiteral")?;
+                serialize_primitive(primitive, map)
+            },
+            AssemblyItem::StringLiteral => map.serialize_entry("type", "StringLiteral"),
+            AssemblyItem::HexLiteral    => map.serialize_entry("type", "HexLiteral"),
+        }
+    }
+}
+
+/// Write `kind`, `value` and `unit` of a `Primitive`. Numbers and strings
+/// are written verbatim as they appear in source, `unit` is `null` for
+/// anything but integer and rational numbers with a unit.
+fn serialize_primitive<M: SerializeMap>(primitive: &Primitive, map: &mut M) -> Result<(), M::Error> {
+    let (kind, raw, unit) = match *primitive {
+        Primitive::Bool(value) => {
+            map.serialize_entry("kind", "bool")?;
+            map.serialize_entry("value", &value)?;
+
+            return map.serialize_entry("unit", &None::<&str>);
+        },
+        Primitive::HexNumber(raw)            => ("hex", raw, NumberUnit::None),
+        Primitive::IntegerNumber(raw, unit)  => ("integer", raw, unit),
+        Primitive::RationalNumber(raw, unit) => ("rational", raw, unit),
+        Primitive::String(raw)               => ("string", raw, NumberUnit::None),
+    };
+
+    map.serialize_entry("kind", kind)?;
+    map.serialize_entry("value", raw)?;
+    map.serialize_entry("unit", &unit.as_str())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use toolshed::Arena;
+
+    /// contract Foo { function bar(uint a) public { return a * 1 ether; } }
+    fn program<'ast>() -> Program<'ast> {
+        Program::build(|b| {
+            let value = b.at(52, 63).node(BinaryExpression {
+                left: b.at(52, 53).identifier_expression("a"),
+                operator: b.at(54, 55).node(BinaryOperator::Multiplication),
+                right: b.at(56, 63).number("1", NumberUnit::Ether(EtherUnit::Ether)),
+            });
+
+            let param = b.at(28, 34).node(Parameter {
+                type_name: b.at(28, 32).elementary(ElementaryTypeName::Uint(32)),
+                name: Some(b.at(33, 34).identifier("a")),
+            });
+
+            let function = b.at(15, 66).node(FunctionDefinition {
+                doc: None,
+                name: Some(b.at(24, 27).identifier("bar")),
+                params: b.list(Some(param)),
+                visibility: Some(b.at(36, 42).node(FunctionVisibility::Public)),
+                mutability: None,
+                modifiers: NodeList::empty(),
+                returns: NodeList::empty(),
+                block: Some(b.at(43, 66).block(Some(b.at(45, 64).return_statement(Some(value))))),
+            });
+
+            b.list(Some(b.at(0, 68).node(ContractDefinition {
+                doc: None,
+                name: b.at(9, 12).identifier("Foo"),
+                inherits: NodeList::empty(),
+                body: b.list(Some(function)),
+            })))
+        })
+    }
+
+    #[test]
+    fn program_to_json() {
+        let expected = concat!(
+            r#"{"type":"Program","body":[{"type":"ContractDefinition","doc":null,"#,
+            r#""name":{"type":"Identifier","name":"Foo","start":9,"end":12},"inherits":[],"#,
+            r#""body":[{"type":"FunctionDefinition","doc":null,"#,
+            r#""name":{"type":"Identifier","name":"bar","start":24,"end":27},"#,
+            r#""params":[{"type":"Parameter","#,
+            r#""type_name":{"type":"ElementaryTypeName","name":"uint256","start":28,"end":32},"#,
+            r#""name":{"type":"Identifier","name":"a","start":33,"end":34},"start":28,"end":34}],"#,
+            r#""visibility":{"type":"FunctionVisibility","value":"public","start":36,"end":42},"#,
+            r#""mutability":null,"modifiers":[],"returns":[],"#,
+            r#""block":{"type":"Block","body":[{"type":"ReturnStatement","#,
+            r#""value":{"type":"BinaryExpression","#,
+            r#""left":{"type":"IdentifierExpression","name":"a","start":52,"end":53},"#,
+            r#""operator":{"type":"BinaryOperator","value":"*","start":54,"end":55},"#,
+            r#""right":{"type":"PrimitiveExpression","kind":"integer","value":"1","unit":"ether","start":56,"end":63},"#,
+            r#""start":52,"end":63},"start":45,"end":64}],"start":43,"end":66},"#,
+            r#""start":15,"end":66}],"start":0,"end":68}]}"#,
+        );
+
+        assert_eq!(program().to_json(), expected);
+    }
+
+    #[test]
+    fn literals() {
+        let arena = Arena::new();
+        let b = AstBuilder::new(&arena);
+        let source = b.at(7, 16).node("\"foo.sol\"");
+        let import: Node<ImportDirective> = b.at(0, 17).node(ImportDirective::Global { source, alias: None });
+        let tag: Node<DocTag> = b.at(4, 20).node(DocTag { kind: DocTagKind::Param("a"), text: "the a" });
+
+        assert_eq!(
+            ::serde_json::to_string(&import).unwrap(),
+            r#"{"type":"ImportDirective","kind":"global","source":{"type":"StringLiteral","value":"\"foo.sol\"","start":7,"end":16},"alias":null,"start":0,"end":17}"#
+        );
+        assert_eq!(
+            ::serde_json::to_string(&tag).unwrap(),
+            r#"{"type":"DocTag","kind":"param","name":"a","text":"the a","start":4,"end":20}"#
+        );
+    }
+}
@@ -0,0 +1,1144 @@
+//! Export of the AST to `solc` JSON.
+//!
+//! All syntactic node kinds lunarity parses are covered, with `id`s and `src`
+//! strings assigned the same way `solc` does it: children get lower `id`s
+//! than their parents, and `src` is `start:length:file` in bytes. Fields that
+//! require semantic analysis, such as `typeDescriptions`, `scope` or
+//! `referencedDeclaration`, are omitted. A few things can't be recovered
+//! from the AST and are approximated:
+//!
+//! + Elementary type names are canonical, `uint` is exported as `uint256`.
+//! + `text` of `StructuredDocumentation` is rebuilt from the doc tags.
+//! + `src` of an empty `ParameterList` is an empty range.
+//! + `Error` nodes of a partial AST and labels in inline assembly, neither
+//!   of which `solc` has, are exported as `Error` and `YulLabel` nodes.
+
+use serde_json::{Map, Value};
+
+use {*};
+
+/// Exports `Program`s to `solc` JSON. The same `Exporter` can be used for
+/// multiple source files, keeping the node `id`s unique across all of them.
+pub struct Exporter {
+    next_id: u32,
+    file: u32,
+
+    /// Offset of the exported `Program`, subtracted from all locations.
+    offset: u32,
+}
+
+impl Exporter {
+    /// Create a new `Exporter`, the first exported node gets `id` 0.
+    pub fn new() -> Self {
+        Exporter {
+            next_id: 0,
+            file: 0,
+            offset: 0,
+        }
+    }
+
+    /// Export the `Program` as the `SourceUnit` of the file at `path`, which
+    /// has `file` as its index in the `src` strings. Locations are relative
+    /// to the start of the file, also for files parsed from `SourceFiles`.
+    pub fn export(&mut self, program: &Program, path: &str, file: u32) -> Value {
+        self.file = file;
+        self.offset = program.offset();
+
+        let mut exported = Map::new();
+
+        let nodes: Vec<Value> = program.body().iter().map(|unit| {
+            let node = self.source_unit(unit);
+
+            if let SourceUnit::ContractDefinition(ref contract) = unit.value {
+                exported.insert(contract.name.value.to_string(), json!([node["id"]]));
+            }
+
+            node
+        }).collect();
+
+        let end = program.body().iter().last().map_or(self.offset, |unit| unit.end);
+        let start = self.offset;
+
+        self.node("SourceUnit", start, end, json!({
+            "absolutePath": path,
+            "exportedSymbols": exported,
+            "nodes": nodes,
+        }))
+    }
+
+    fn id(&mut self) -> u32 {
+        let id = self.next_id;
+
+        self.next_id += 1;
+
+        id
+    }
+
+    fn src(&self, start: u32, end: u32) -> String {
+        format!("{}:{}:{}", start.saturating_sub(self.offset), end.saturating_sub(start), self.file)
+    }
+
+    /// Finish a node, its children must already be exported for the `id`s
+    /// to be ordered the way `solc` orders them.
+    fn node(&mut self, node_type: &str, start: u32, end: u32, mut value: Value) -> Value {
+        let id  = self.id();
+        let src = self.src(start, end);
+
+        if let Value::Object(ref mut map) = value {
+            map.insert("id".into(), id.into());
+            map.insert("nodeType".into(), node_type.into());
+            map.insert("src".into(), src.into());
+        }
+
+        value
+    }
+
+    /// Finish a Yul node, those don't have `id`s.
+    fn yul(&self, node_type: &str, start: u32, end: u32, mut value: Value) -> Value {
+        if let Value::Object(ref mut map) = value {
+            map.insert("nodeType".into(), node_type.into());
+            map.insert("src".into(), self.src(start, end).into());
+        }
+
+        value
+    }
+
+    fn opt<T, F>(&mut self, value: Option<T>, mut f: F) -> Value
+    where
+        F: FnMut(&mut Self, T) -> Value,
+    {
+        match value {
+            Some(value) => f(self, value),
+            None        => Value::Null,
+        }
+    }
+
+    fn source_unit(&mut self, unit: &SourceUnitNode) -> Value {
+        let (start, end) = (unit.start, unit.end);
+
+        match unit.value {
+            SourceUnit::PragmaDirective(ref pragma) => {
+                self.node("PragmaDirective", start, end, json!({
+                    "literals": pragma_literals(pragma.version),
+                }))
+            },
+            SourceUnit::ImportDirective(ref import) => self.import_directive(import, start, end),
+            SourceUnit::ContractDefinition(ref contract) => self.contract_definition(contract, start, end),
+            SourceUnit::Error => self.node("Error", start, end, json!({})),
+        }
+    }
+
+    fn import_directive(&mut self, import: &ImportDirective, start: u32, end: u32) -> Value {
+        let (source, unit_alias, aliases) = match *import {
+            ImportDirective::Global { source, alias } => (source, alias, Vec::new()),
+            ImportDirective::From { symbol, alias, source } => {
+                // `import foo as bar from "foo"` is the same as `import "foo" as bar`
+                let alias = alias.or(symbol);
+
+                (source, alias, Vec::new())
+            },
+            ImportDirective::ManyFrom { imports, source } => {
+                let aliases = imports.iter().map(|import| {
+                    let foreign = self.identifier(import.value.symbol);
+
+                    json!({
+                        "foreign": foreign,
+                        "local": import.value.alias.map(|alias| alias.value),
+                        "nameLocation": self.src(import.start, import.end),
+                    })
+                }).collect();
+
+                (source, None, aliases)
+            },
+        };
+
+        let path = String::from_utf8_lossy(&decode_string_literal(source.value)).into_owned();
+
+        self.node("ImportDirective", start, end, json!({
+            "absolutePath": path,
+            "file": path,
+            "symbolAliases": aliases,
+            "unitAlias": unit_alias.map_or("", |alias| alias.value),
+        }))
+    }
+
+    fn contract_definition(&mut self, contract: &ContractDefinition, start: u32, end: u32) -> Value {
+        let documentation = self.opt(contract.doc, Self::documentation);
+
+        let base_contracts: Vec<Value> = contract.inherits.iter().map(|base| {
+            let name = self.identifier_path(*base);
+
+            self.node("InheritanceSpecifier", base.start, base.end, json!({
+                "baseName": name,
+            }))
+        }).collect();
+
+        let nodes: Vec<Value> = contract.body.iter().map(|part| self.contract_part(contract, part)).collect();
+
+        let implemented = contract.body.iter().all(|part| match part.value {
+            ContractPart::FunctionDefinition(ref def) => def.block.is_some(),
+            _                                         => true,
+        });
+
+        self.node("ContractDefinition", start, end, json!({
+            "abstract": false,
+            "baseContracts": base_contracts,
+            "contractKind": "contract",
+            "documentation": documentation,
+            "fullyImplemented": implemented,
+            "name": contract.name.value,
+            "nameLocation": self.src(contract.name.start, contract.name.end),
+            "nodes": nodes,
+        }))
+    }
+
+    fn contract_part(&mut self, contract: &ContractDefinition, part: &ContractPartNode) -> Value {
+        let (start, end) = (part.start, part.end);
+
+        match part.value {
+            ContractPart::StateVariableDeclaration(ref var) => {
+                let documentation = self.opt(var.doc, Self::documentation);
+                let type_name     = self.type_name(var.type_name);
+                let value         = self.opt(var.init, Self::expression);
+
+                self.node("VariableDeclaration", start, end, json!({
+                    "constant": var.constant.is_some(),
+                    "documentation": documentation,
+                    "mutability": if var.constant.is_some() { "constant" } else { "mutable" },
+                    "name": var.name.value,
+                    "nameLocation": self.src(var.name.start, var.name.end),
+                    "stateVariable": true,
+                    "storageLocation": "default",
+                    "typeName": type_name,
+                    "value": value,
+                    "visibility": var.visibility.map_or("internal", |visibility| visibility.value.as_str()),
+                }))
+            },
+            ContractPart::UsingForDeclaration(ref using) => {
+                let library   = self.identifier_path(using.id);
+                let type_name = self.opt(using.type_name, Self::type_name);
+
+                self.node("UsingForDirective", start, end, json!({
+                    "libraryName": library,
+                    "typeName": type_name,
+                }))
+            },
+            ContractPart::StructDefinition(ref def) => {
+                let members: Vec<Value> = def.body.iter().map(|declaration| self.variable_declaration(*declaration)).collect();
+
+                self.node("StructDefinition", start, end, json!({
+                    "canonicalName": format!("{}.{}", contract.name.value, def.name.value),
+                    "members": members,
+                    "name": def.name.value,
+                    "nameLocation": self.src(def.name.start, def.name.end),
+                    "visibility": "public",
+                }))
+            },
+            ContractPart::ModifierDefinition(ref def) => {
+                let documentation = self.opt(def.doc, Self::documentation);
+                let parameters    = self.parameter_list(def.params, def.name.end);
+                let body          = self.block(def.block);
+
+                self.node("ModifierDefinition", start, end, json!({
+                    "body": body,
+                    "documentation": documentation,
+                    "name": def.name.value,
+                    "nameLocation": self.src(def.name.start, def.name.end),
+                    "parameters": parameters,
+                    "virtual": false,
+                    "visibility": "internal",
+                }))
+            },
+            ContractPart::FunctionDefinition(ref def) => self.function_definition(contract, def, start, end),
+            ContractPart::EventDefinition(ref def) => {
+                let documentation = self.opt(def.doc, Self::documentation);
+                let parameters: Vec<Value> = def.params.iter().map(|param| {
+                    let type_name = self.type_name(param.value.type_name);
+
+                    self.parameter(param.start, param.end, type_name, param.value.name, Some(param.value.indexed.is_some()))
+                }).collect();
+                let parameters = self.list("ParameterList", "parameters", def.params.iter().map(|param| (param.start, param.end)), def.name.end, parameters);
+
+                self.node("EventDefinition", start, end, json!({
+                    "anonymous": def.anonymous.is_some(),
+                    "documentation": documentation,
+                    "name": def.name.value,
+                    "nameLocation": self.src(def.name.start, def.name.end),
+                    "parameters": parameters,
+                }))
+            },
+            ContractPart::EnumDefinition(ref def) => {
+                let members: Vec<Value> = def.variants.iter().map(|variant| {
+                    self.node("EnumValue", variant.start, variant.end, json!({
+                        "name": variant.value,
+                        "nameLocation": self.src(variant.start, variant.end),
+                    }))
+                }).collect();
+
+                self.node("EnumDefinition", start, end, json!({
+                    "canonicalName": format!("{}.{}", contract.name.value, def.name.value),
+                    "members": members,
+                    "name": def.name.value,
+                    "nameLocation": self.src(def.name.start, def.name.end),
+                }))
+            },
+            ContractPart::Error => self.node("Error", start, end, json!({})),
+        }
+    }
+
+    fn function_definition(&mut self, contract: &ContractDefinition, def: &FunctionDefinition, start: u32, end: u32) -> Value {
+        let documentation = self.opt(def.doc, Self::documentation);
+        let params_start  = def.name.map_or(start, |name| name.end);
+        let parameters    = self.parameter_list(def.params, params_start);
+
+        let modifiers: Vec<Value> = def.modifiers.iter().map(|modifier| {
+            let name      = self.identifier_path(modifier.value.id);
+            let arguments = self.expressions(modifier.value.arguments);
+
+            self.node("ModifierInvocation", modifier.start, modifier.end, json!({
+                "arguments": arguments,
+                "kind": "modifierInvocation",
+                "modifierName": name,
+            }))
+        }).collect();
+
+        let returns_start = def.modifiers.iter().last().map_or(params_start, |modifier| modifier.end);
+        let returns       = self.parameter_list(def.returns, returns_start);
+        let body          = self.opt(def.block, Self::block);
+
+        let kind = match def.name {
+            None                                             => "fallback",
+            Some(name) if name.value == contract.name.value => "constructor",
+            Some(_)                                          => "function",
+        };
+
+        let mutability = match def.mutability.map(|mutability| mutability.value) {
+            None                            => "nonpayable",
+            Some(StateMutability::Constant) => "view",
+            Some(mutability)                => mutability.as_str(),
+        };
+
+        // Legacy field of solc 0.4, set for functions declared `constant`
+        let constant = def.mutability.map(|mutability| mutability.value) == Some(StateMutability::Constant);
+
+        let mut value = json!({
+            "constant": constant,
+            "documentation": documentation,
+            "implemented": def.block.is_some(),
+            "kind": kind,
+            "modifiers": modifiers,
+            "name": def.name.map_or("", |name| name.value),
+            "parameters": parameters,
+            "returnParameters": returns,
+            "stateMutability": mutability,
+            "virtual": false,
+            "visibility": def.visibility.map_or("public", |visibility| visibility.value.as_str()),
+        });
+
+        if let Some(name) = def.name {
+            value["nameLocation"] = self.src(name.start, name.end).into();
+        }
+
+        // `solc` omits the body of unimplemented functions
+        if !body.is_null() {
+            value["body"] = body;
+        }
+
+        self.node("FunctionDefinition", start, end, value)
+    }
+
+    fn documentation(&mut self, doc: DocCommentNode) -> Value {
+        let text: Vec<String> = doc.value.tags.iter().map(|tag| {
+            let tag = tag.value;
+
+            match tag.kind {
+                DocTagKind::Title            => format!("@title {}", tag.text),
+                DocTagKind::Author           => format!("@author {}", tag.text),
+                DocTagKind::Notice           => format!("@notice {}", tag.text),
+                DocTagKind::Dev              => format!("@dev {}", tag.text),
+                DocTagKind::Param(name)      => format!("@param {} {}", name, tag.text),
+                DocTagKind::Return           => format!("@return {}", tag.text),
+                DocTagKind::Inheritdoc(name) => format!("@inheritdoc {} {}", name, tag.text),
+                DocTagKind::Custom(name)     => format!("@custom:{} {}", name, tag.text),
+                DocTagKind::Unknown(name)    => format!("@{} {}", name, tag.text),
+            }
+        }).collect();
+
+        self.node("StructuredDocumentation", doc.start, doc.end, json!({
+            "text": text.join("\n"),
+        }))
+    }
+
+    fn parameter_list(&mut self, params: ParameterList, fallback: u32) -> Value {
+        let parameters: Vec<Value> = params.iter().map(|param| {
+            let type_name = self.type_name(param.value.type_name);
+
+            self.parameter(param.start, param.end, type_name, param.value.name, None)
+        }).collect();
+
+        self.list("ParameterList", "parameters", params.iter().map(|param| (param.start, param.end)), fallback, parameters)
+    }
+
+    /// Finish a list node spanning all of its items, or empty at `fallback`
+    /// if there are none.
+    fn list<I>(&mut self, node_type: &str, field: &str, spans: I, fallback: u32, items: Vec<Value>) -> Value
+    where
+        I: Iterator<Item = (u32, u32)>,
+    {
+        let (start, end) = spans.fold(None, |span, (start, end)| match span {
+            Some((first, _)) => Some((first, end)),
+            None             => Some((start, end)),
+        }).unwrap_or((fallback, fallback));
+
+        let mut value = Map::new();
+
+        value.insert(field.into(), items.into());
+
+        self.node(node_type, start, end, value.into())
+    }
+
+    fn parameter(&mut self, start: u32, end: u32, type_name: Value, name: Option<IdentifierNode>, indexed: Option<bool>) -> Value {
+        let mut value = json!({
+            "constant": false,
+            "mutability": "mutable",
+            "name": name.map_or("", |name| name.value),
+            "stateVariable": false,
+            "storageLocation": "default",
+            "typeName": type_name,
+            "visibility": "internal",
+        });
+
+        if let Some(name) = name {
+            value["nameLocation"] = self.src(name.start, name.end).into();
+        }
+
+        if let Some(indexed) = indexed {
+            value["indexed"] = indexed.into();
+        }
+
+        self.node("VariableDeclaration", start, end, value)
+    }
+
+    fn variable_declaration(&mut self, declaration: VariableDeclarationNode) -> Value {
+        let type_name = self.type_name(declaration.value.type_name);
+        let id        = declaration.value.id;
+
+        self.node("VariableDeclaration", declaration.start, declaration.end, json!({
+            "constant": false,
+            "mutability": "mutable",
+            "name": id.value,
+            "nameLocation": self.src(id.start, id.end),
+            "stateVariable": false,
+            "storageLocation": declaration.value.location.map_or("default", |location| location.value.as_str()),
+            "typeName": type_name,
+            "visibility": "internal",
+        }))
+    }
+
+    fn type_name(&mut self, type_name: TypeNameNode) -> Value {
+        let (start, end) = (type_name.start, type_name.end);
+
+        match type_name.value {
+            TypeName::ElementaryTypeName(elementary) => self.elementary_type_name(elementary, start, end),
+            TypeName::UserDefinedTypeName(name) => {
+                let path = self.node("IdentifierPath", start, end, json!({
+                    "name": name,
+                }));
+
+                self.node("UserDefinedTypeName", start, end, json!({
+                    "pathNode": path,
+                }))
+            },
+            TypeName::Mapping(ref mapping) => {
+                let key   = self.elementary_type_name(mapping.from.value, mapping.from.start, mapping.from.end);
+                let value = self.type_name(mapping.to);
+
+                self.node("Mapping", start, end, json!({
+                    "keyType": key,
+                    "valueType": value,
+                }))
+            },
+            TypeName::ArrayTypeName    => self.node("ArrayTypeName", start, end, json!({})),
+            TypeName::FunctionTypeName => self.node("FunctionTypeName", start, end, json!({})),
+        }
+    }
+
+    fn elementary_type_name(&mut self, elementary: ElementaryTypeName, start: u32, end: u32) -> Value {
+        self.node("ElementaryTypeName", start, end, json!({
+            "name": elementary.to_string(),
+        }))
+    }
+
+    fn identifier(&mut self, id: IdentifierNode) -> Value {
+        self.node("Identifier", id.start, id.end, json!({
+            "name": id.value,
+        }))
+    }
+
+    fn identifier_path(&mut self, id: IdentifierNode) -> Value {
+        self.node("IdentifierPath", id.start, id.end, json!({
+            "name": id.value,
+        }))
+    }
+
+    fn block(&mut self, block: BlockNode) -> Value {
+        let statements = self.statements(block.value.body);
+
+        self.node("Block", block.start, block.end, json!({
+            "statements": statements,
+        }))
+    }
+
+    fn statements(&mut self, statements: StatementList) -> Vec<Value> {
+        statements.iter().map(|statement| self.statement(*statement)).collect()
+    }
+
+    fn statement(&mut self, statement: StatementNode) -> Value {
+        let (start, end) = (statement.start, statement.end);
+
+        match statement.value {
+            Statement::IfStatement(ref stmt) => {
+                let condition  = self.expression(stmt.test);
+                let true_body  = self.statement(stmt.consequent);
+                let false_body = self.opt(stmt.alternate, Self::statement);
+
+                self.node("IfStatement", start, end, json!({
+                    "condition": condition,
+                    "falseBody": false_body,
+                    "trueBody": true_body,
+                }))
+            },
+            Statement::WhileStatement(ref stmt) => {
+                let condition = self.expression(stmt.test);
+                let body      = self.statement(stmt.body);
+
+                self.node("WhileStatement", start, end, json!({
+                    "body": body,
+                    "condition": condition,
+                }))
+            },
+            Statement::ForStatement(ref stmt) => {
+                let init      = self.opt(stmt.init, Self::simple_statement);
+                let condition = self.opt(stmt.test, Self::expression);
+                let update    = self.opt(stmt.update, |exporter, update| {
+                    let expression = exporter.expression(update);
+
+                    exporter.node("ExpressionStatement", update.start, update.end, json!({
+                        "expression": expression,
+                    }))
+                });
+                let body = self.statement(stmt.body);
+
+                self.node("ForStatement", start, end, json!({
+                    "body": body,
+                    "condition": condition,
+                    "initializationExpression": init,
+                    "loopExpression": update,
+                }))
+            },
+            Statement::BlockStatement(ref block) => {
+                let statements = self.statements(block.body);
+
+                self.node("Block", start, end, json!({
+                    "statements": statements,
+                }))
+            },
+            Statement::InlineAssemblyStatement(ref stmt) => {
+                let block = self.assembly_block(stmt.block.value, stmt.block.start, stmt.block.end);
+
+                self.node("InlineAssembly", start, end, json!({
+                    "AST": block,
+                }))
+            },
+            Statement::DoWhileStatement(ref stmt) => {
+                let body      = self.statement(stmt.body);
+                let condition = self.expression(stmt.test);
+
+                self.node("DoWhileStatement", start, end, json!({
+                    "body": body,
+                    "condition": condition,
+                }))
+            },
+            Statement::ReturnStatement(ref stmt) => {
+                let expression = self.opt(stmt.value, Self::expression);
+
+                self.node("Return", start, end, json!({
+                    "expression": expression,
+                }))
+            },
+            Statement::VariableDefinitionStatement(ref stmt) => self.variable_definition(stmt, start, end),
+            Statement::InferredDefinitionStatement(ref stmt) => self.inferred_definition(stmt, start, end),
+            Statement::ExpressionStatement(expression) => {
+                let expression = self.expression(expression);
+
+                self.node("ExpressionStatement", start, end, json!({
+                    "expression": expression,
+                }))
+            },
+            Statement::Placeholder       => self.node("PlaceholderStatement", start, end, json!({})),
+            Statement::ContinueStatement => self.node("Continue", start, end, json!({})),
+            Statement::BreakStatement    => self.node("Break", start, end, json!({})),
+            Statement::ThrowStatement    => self.node("Throw", start, end, json!({})),
+            Statement::Error             => self.node("Error", start, end, json!({})),
+        }
+    }
+
+    fn simple_statement(&mut self, statement: SimpleStatementNode) -> Value {
+        let (start, end) = (statement.start, statement.end);
+
+        match statement.value {
+            SimpleStatement::VariableDefinitionStatement(ref stmt) => self.variable_definition(stmt, start, end),
+            SimpleStatement::InferredDefinitionStatement(ref stmt) => self.inferred_definition(stmt, start, end),
+            SimpleStatement::ExpressionStatement(expression) => {
+                let expression = self.expression(expression);
+
+                self.node("ExpressionStatement", start, end, json!({
+                    "expression": expression,
+                }))
+            },
+        }
+    }
+
+    fn variable_definition(&mut self, stmt: &VariableDefinitionStatement, start: u32, end: u32) -> Value {
+        let declaration = self.variable_declaration(stmt.declaration);
+        let value       = self.opt(stmt.init, Self::expression);
+
+        self.node("VariableDeclarationStatement", start, end, json!({
+            "assignments": [declaration["id"].clone()],
+            "declarations": [declaration],
+            "initialValue": value,
+        }))
+    }
+
+    fn inferred_definition(&mut self, stmt: &InferredDefinitionStatement, start: u32, end: u32) -> Value {
+        let declarations: Vec<Value> = stmt.ids.iter().map(|id| {
+            self.opt(*id, |exporter, id| {
+                exporter.node("VariableDeclaration", id.start, id.end, json!({
+                    "constant": false,
+                    "mutability": "mutable",
+                    "name": id.value,
+                    "nameLocation": exporter.src(id.start, id.end),
+                    "stateVariable": false,
+                    "storageLocation": "default",
+                    "typeName": null,
+                    "visibility": "internal",
+                }))
+            })
+        }).collect();
+
+        let assignments: Vec<Value> = declarations.iter().map(|declaration| declaration["id"].clone()).collect();
+        let value = self.expression(stmt.init);
+
+        self.node("VariableDeclarationStatement", start, end, json!({
+            "assignments": assignments,
+            "declarations": declarations,
+            "initialValue": value,
+        }))
+    }
+
+    fn expressions(&mut self, expressions: ExpressionList) -> Vec<Value> {
+        expressions.iter().map(|expression| self.expression(*expression)).collect()
+    }
+
+    fn expression(&mut self, expression: ExpressionNode) -> Value {
+        let (start, end) = (expression.start, expression.end);
+
+        match expression.value {
+            Expression::ThisExpression => self.node("Identifier", start, end, json!({
+                "name": "this",
+            })),
+            Expression::IdentifierExpression(name) => self.node("Identifier", start, end, json!({
+                "name": name,
+            })),
+            Expression::PrimitiveExpression(ref primitive) => {
+                let literal = literal(primitive);
+
+                self.node("Literal", start, end, literal)
+            },
+            Expression::PrefixExpression(ref expr) => {
+                let operand = self.expression(expr.operand);
+
+                self.node("UnaryOperation", start, end, json!({
+                    "operator": expr.operator.value.as_str(),
+                    "prefix": true,
+                    "subExpression": operand,
+                }))
+            },
+            Expression::PostfixExpression(ref expr) => {
+                let operand = self.expression(expr.operand);
+
+                self.node("UnaryOperation", start, end, json!({
+                    "operator": expr.operator.value.as_str(),
+                    "prefix": false,
+                    "subExpression": operand,
+                }))
+            },
+            Expression::BinaryExpression(ref expr) => {
+                let left  = self.expression(expr.left);
+                let right = self.expression(expr.right);
+
+                self.node("BinaryOperation", start, end, json!({
+                    "leftExpression": left,
+                    "operator": expr.operator.value.as_str(),
+                    "rightExpression": right,
+                }))
+            },
+            Expression::AssignmentExpression(ref expr) => {
+                let left  = self.expression(expr.left);
+                let right = self.expression(expr.right);
+
+                self.node("Assignment", start, end, json!({
+                    "leftHandSide": left,
+                    "operator": expr.operator.value.as_str(),
+                    "rightHandSide": right,
+                }))
+            },
+            Expression::TupleExpression(ref expr) => {
+                let components = self.expressions(expr.expressions);
+
+                self.node("TupleExpression", start, end, json!({
+                    "components": components,
+                    "isInlineArray": false,
+                }))
+            },
+            Expression::CallExpression(ref expr) => {
+                let callee    = self.expression(expr.callee);
+                let arguments = self.expressions(expr.arguments);
+
+                self.node("FunctionCall", start, end, json!({
+                    "arguments": arguments,
+                    "expression": callee,
+                    "kind": "functionCall",
+                    "names": [],
+                    "tryCall": false,
+                }))
+            },
+            Expression::MemberAccessExpression(ref expr) => {
+                let object = self.expression(expr.object);
+
+                self.node("MemberAccess", start, end, json!({
+                    "expression": object,
+                    "memberLocation": self.src(expr.member.start, expr.member.end),
+                    "memberName": expr.member.value,
+                }))
+            },
+            Expression::IndexAccessExpression(ref expr) => {
+                let array = self.expression(expr.array);
+                let index = self.opt(expr.index, Self::expression);
+
+                self.node("IndexAccess", start, end, json!({
+                    "baseExpression": array,
+                    "indexExpression": index,
+                }))
+            },
+            Expression::ConditionalExpression(ref expr) => {
+                let test       = self.expression(expr.test);
+                let consequent = self.expression(expr.consequent);
+                let alternate  = self.expression(expr.alternate);
+
+                self.node("Conditional", start, end, json!({
+                    "condition": test,
+                    "falseExpression": alternate,
+                    "trueExpression": consequent,
+                }))
+            },
+            Expression::ElementaryTypeExpression(elementary) => {
+                let type_name = self.elementary_type_name(elementary, start, end);
+
+                self.node("ElementaryTypeNameExpression", start, end, json!({
+                    "typeName": type_name,
+                }))
+            },
+        }
+    }
+
+    fn assembly_block(&self, block: InlineAssemblyBlock, start: u32, end: u32) -> Value {
+        let statements: Vec<Value> = block.items.iter().map(|item| self.assembly_statement(*item)).collect();
+
+        self.yul("YulBlock", start, end, json!({
+            "statements": statements,
+        }))
+    }
+
+    fn assembly_statement(&self, item: AssemblyItemNode) -> Value {
+        let (start, end) = (item.start, item.end);
+
+        match item.value {
+            AssemblyItem::InlineAssemblyBlock(block) => self.assembly_block(block, start, end),
+            AssemblyItem::AssemblyLocalBinding(ref binding) => {
+                let variable = self.yul("YulTypedName", binding.id.start, binding.id.end, json!({
+                    "name": binding.id.value,
+                    "type": "",
+                }));
+
+                self.yul("YulVariableDeclaration", start, end, json!({
+                    "value": self.assembly_call(&binding.init.value, binding.init.start, binding.init.end),
+                    "variables": [variable],
+                }))
+            },
+            AssemblyItem::AssemblyAssignment(ref assignment) => {
+                let variable = self.yul_identifier(assignment.id);
+
+                self.yul("YulAssignment", start, end, json!({
+                    "value": self.assembly_call(&assignment.init.value, assignment.init.start, assignment.init.end),
+                    "variableNames": [variable],
+                }))
+            },
+            AssemblyItem::AssemblyLabel(ref label) => self.yul("YulLabel", start, end, json!({
+                "name": label.id.value,
+            })),
+            _ => self.yul("YulExpressionStatement", start, end, json!({
+                "expression": self.assembly_expression(item),
+            })),
+        }
+    }
+
+    fn assembly_expression(&self, item: AssemblyItemNode) -> Value {
+        let (start, end) = (item.start, item.end);
+
+        match item.value {
+            AssemblyItem::Identifier(name) => self.yul("YulIdentifier", start, end, json!({
+                "name": name,
+            })),
+            AssemblyItem::FunctionalAssemblyExpression(ref expr) => self.assembly_call(expr, start, end),
+            AssemblyItem::NumberLiteral(ref primitive) => {
+                let value = match *primitive {
+                    Primitive::HexNumber(raw)         |
+                    Primitive::IntegerNumber(raw, _)  |
+                    Primitive::RationalNumber(raw, _) => raw,
+                    _                                 => "",
+                };
+
+                self.yul("YulLiteral", start, end, json!({
+                    "kind": "number",
+                    "type": "",
+                    "value": value,
+                }))
+            },
+            AssemblyItem::StringLiteral | AssemblyItem::HexLiteral => self.yul("YulLiteral", start, end, json!({
+                "kind": "string",
+                "type": "",
+            })),
+            _ => self.assembly_statement(item),
+        }
+    }
+
+    fn assembly_call(&self, expr: &FunctionalAssemblyExpression, start: u32, end: u32) -> Value {
+        let arguments: Vec<Value> = expr.arguments.iter().map(|item| self.assembly_expression(*item)).collect();
+
+        self.yul("YulFunctionCall", start, end, json!({
+            "arguments": arguments,
+            "functionName": self.yul_identifier(expr.id),
+        }))
+    }
+
+    fn yul_identifier(&self, id: IdentifierNode) -> Value {
+        self.yul("YulIdentifier", id.start, id.end, json!({
+            "name": id.value,
+        }))
+    }
+}
+
+impl Default for Exporter {
+    fn default() -> Self {
+        Exporter::new()
+    }
+}
+
+/// Fields of a `Literal` node.
+fn literal(primitive: &Primitive) -> Value {
+    let (kind, value, unit) = match *primitive {
+        Primitive::Bool(true)                => ("bool", "true".into(), NumberUnit::None),
+        Primitive::Bool(false)               => ("bool", "false".into(), NumberUnit::None),
+        Primitive::HexNumber(raw)            => ("number", raw.to_string(), NumberUnit::None),
+        Primitive::IntegerNumber(raw, unit)  |
+        Primitive::RationalNumber(raw, unit) => ("number", raw.to_string(), unit),
+        Primitive::String(raw) => {
+            let kind  = if raw.starts_with("unicode") { "unicodeString" } else { "string" };
+            let bytes = decode_string_literal(raw);
+
+            return json!({
+                "hexValue": hex(&bytes),
+                "kind": kind,
+                "value": ::std::str::from_utf8(&bytes).ok(),
+            });
+        },
+    };
+
+    let mut literal = json!({
+        "hexValue": hex(value.as_bytes()),
+        "kind": kind,
+        "value": value,
+    });
+
+    if let Some(unit) = unit.as_str() {
+        literal["subdenomination"] = unit.into();
+    }
+
+    literal
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Split the pragma into tokens the way the `solc` scanner does,
+/// so `solidity ^0.4.17` becomes `solidity`, `^`, `0.4` and `.17`.
+fn pragma_literals(pragma: &str) -> Vec<&str> {
+    let mut literals = Vec::new();
+    let bytes = pragma.as_bytes();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let start = index;
+
+        match bytes[index] {
+            b' ' | b'\t' | b'\r' | b'\n' => {
+                index += 1;
+
+                continue;
+            },
+            b'0'..=b'9' | b'.' => {
+                // Number literals contain at most one period
+                let mut period = false;
+
+                while index < bytes.len() {
+                    match bytes[index] {
+                        b'0'..=b'9'     => {},
+                        b'.' if !period => period = true,
+                        _               => break,
+                    }
+
+                    index += 1;
+                }
+            },
+            b'^' | b'~' | b'<' | b'>' | b'=' | b'|' | b'-' => {
+                while index < bytes.len() && b"^~<>=|-".contains(&bytes[index]) {
+                    index += 1;
+                }
+            },
+            _ => {
+                while index < bytes.len() && !b" \t\r\n^~<>=|-.".contains(&bytes[index]) {
+                    index += 1;
+                }
+            },
+        }
+
+        literals.push(&pragma[start..index]);
+    }
+
+    literals
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// pragma solidity ^0.4.17;
+    /// contract Foo {
+    ///     uint constant X = 2;
+    ///     function bar(uint a) public returns (uint) { return a * 1 ether; }
+    /// }
+    ///
+    /// all on a single line.
+    fn program<'ast>() -> Program<'ast> {
+        Program::build(|b| {
+            let uint = |start, end| b.at(start, end).elementary(ElementaryTypeName::Uint(32));
+
+            let constant = b.at(40, 60).node(StateVariableDeclaration {
+                doc: None,
+                type_name: uint(40, 44),
+                visibility: None,
+                constant: Some(b.at(45, 53).flag()),
+                name: b.at(54, 55).identifier("X"),
+                init: Some(b.at(58, 59).number("2", NumberUnit::None)),
+            });
+
+            let value = b.at(113, 124).node(BinaryExpression {
+                left: b.at(113, 114).identifier_expression("a"),
+                operator: b.at(115, 116).node(BinaryOperator::Multiplication),
+                right: b.at(117, 124).number("1", NumberUnit::Ether(EtherUnit::Ether)),
+            });
+
+            let param = b.at(74, 80).node(Parameter {
+                type_name: uint(74, 78),
+                name: Some(b.at(79, 80).identifier("a")),
+            });
+
+            let function = b.at(61, 127).node(FunctionDefinition {
+                doc: None,
+                name: Some(b.at(70, 73).identifier("bar")),
+                params: b.list(Some(param)),
+                visibility: Some(b.at(82, 88).node(FunctionVisibility::Public)),
+                mutability: None,
+                modifiers: NodeList::empty(),
+                returns: b.list(Some(b.at(98, 102).parameter(uint(98, 102), None))),
+                block: Some(b.at(104, 127).block(Some(b.at(106, 125).return_statement(Some(value))))),
+            });
+
+            b.list(vec![
+                b.at(0, 24).pragma("solidity ^0.4.17"),
+                b.at(25, 129).node(ContractDefinition {
+                    doc: None,
+                    name: b.at(34, 37).identifier("Foo"),
+                    inherits: NodeList::empty(),
+                    body: b.list(vec![constant, function]),
+                }),
+            ])
+        })
+    }
+
+    #[test]
+    fn source_unit() {
+        let json = Exporter::new().export(&program(), "foo.sol", 0);
+
+        assert_eq!(json["nodeType"], "SourceUnit");
+        assert_eq!(json["absolutePath"], "foo.sol");
+        assert_eq!(json["src"], "0:129:0");
+        assert_eq!(json["id"], 17);
+        assert_eq!(json["exportedSymbols"], json!({ "Foo": [16] }));
+        assert_eq!(json["nodes"][0], json!({
+            "id": 0,
+            "literals": ["solidity", "^", "0.4", ".17"],
+            "nodeType": "PragmaDirective",
+            "src": "0:24:0",
+        }));
+    }
+
+    #[test]
+    fn second_file() {
+        const SOURCE: &str = "pragma solidity ^0.4.17;";
+
+        let mut files = SourceFiles::new();
+
+        files.add("a.sol", "contract A {}");
+
+        let id = files.add("b.sol", SOURCE);
+        let start = files.get(id).start();
+        let (body, arena) = Program::build(|b| {
+            b.list(Some(b.at(start, start + 24).pragma("solidity ^0.4.17")))
+        }).into_parts();
+        let program = Program::with_source(body, arena, SOURCE, start);
+
+        let json = Exporter::new().export(&program, "b.sol", 1);
+
+        assert_eq!(json["src"], "0:24:1");
+        assert_eq!(json["nodes"][0]["src"], "0:24:1");
+    }
+
+    #[test]
+    fn contract() {
+        let json = Exporter::new().export(&program(), "foo.sol", 3);
+        let contract = &json["nodes"][1];
+
+        assert_eq!(contract["nodeType"], "ContractDefinition");
+        assert_eq!(contract["name"], "Foo");
+        assert_eq!(contract["nameLocation"], "34:3:3");
+        assert_eq!(contract["fullyImplemented"], true);
+
+        assert_eq!(contract["nodes"][0], json!({
+            "constant": true,
+            "documentation": null,
+            "id": 3,
+            "mutability": "constant",
+            "name": "X",
+            "nameLocation": "54:1:3",
+            "nodeType": "VariableDeclaration",
+            "src": "40:20:3",
+            "stateVariable": true,
+            "storageLocation": "default",
+            "typeName": {
+                "id": 1,
+                "name": "uint256",
+                "nodeType": "ElementaryTypeName",
+                "src": "40:4:3",
+            },
+            "value": {
+                "hexValue": "32",
+                "id": 2,
+                "kind": "number",
+                "nodeType": "Literal",
+                "src": "58:1:3",
+                "value": "2",
+            },
+            "visibility": "internal",
+        }));
+
+        let function = &contract["nodes"][1];
+
+        assert_eq!(function["id"], 15);
+        assert_eq!(function["kind"], "function");
+        assert_eq!(function["stateMutability"], "nonpayable");
+        assert_eq!(function["constant"], false);
+        assert_eq!(function["visibility"], "public");
+        assert_eq!(function["parameters"]["src"], "74:6:3");
+        assert_eq!(function["parameters"]["parameters"][0]["name"], "a");
+        assert_eq!(function["returnParameters"]["src"], "98:4:3");
+        assert_eq!(function["returnParameters"]["parameters"][0]["name"], "");
+        assert_eq!(function["body"]["statements"][0], json!({
+            "expression": {
+                "id": 12,
+                "leftExpression": {
+                    "id": 10,
+                    "name": "a",
+                    "nodeType": "Identifier",
+                    "src": "113:1:3",
+                },
+                "nodeType": "BinaryOperation",
+                "operator": "*",
+                "rightExpression": {
+                    "hexValue": "31",
+                    "id": 11,
+                    "kind": "number",
+                    "nodeType": "Literal",
+                    "src": "117:7:3",
+                    "subdenomination": "ether",
+                    "value": "1",
+                },
+                "src": "113:11:3",
+            },
+            "id": 13,
+            "nodeType": "Return",
+            "src": "106:19:3",
+        }));
+    }
+
+    #[test]
+    fn ids_are_unique_across_files() {
+        let program = program();
+        let mut exporter = Exporter::new();
+
+        let first  = exporter.export(&program, "foo.sol", 0);
+        let second = exporter.export(&program, "bar.sol", 1);
+
+        assert_eq!(first["id"], 17);
+        assert_eq!(second["id"], 35);
+        assert_eq!(second["nodes"][0]["src"], "0:24:1");
+    }
+
+    #[test]
+    fn literals() {
+        assert_eq!(literal(&Primitive::Bool(true)), json!({
+            "hexValue": "74727565",
+            "kind": "bool",
+            "value": "true",
+        }));
+        assert_eq!(literal(&Primitive::String(r#""a\n""#)), json!({
+            "hexValue": "610a",
+            "kind": "string",
+            "value": "a\n",
+        }));
+        assert_eq!(literal(&Primitive::String(r#"unicode"ż""#)), json!({
+            "hexValue": "c5bc",
+            "kind": "unicodeString",
+            "value": "ż",
+        }));
+    }
+
+    #[test]
+    fn pragma() {
+        assert_eq!(pragma_literals("solidity >=0.4.0 <0.6.0"), vec!["solidity", ">=", "0.4", ".0", "<", "0.6", ".0"]);
+        assert_eq!(pragma_literals("experimental ABIEncoderV2"), vec!["experimental", "ABIEncoderV2"]);
+        assert_eq!(pragma_literals("solidity 0.4.17 || ^0.5"), vec!["solidity", "0.4", ".17", "||", "^", "0.5"]);
+    }
+}
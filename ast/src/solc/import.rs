@@ -0,0 +1,1353 @@
+//! Import of `solc` JSON into a `Program`.
+//!
+//! The input is the `SourceUnit` node of a single file, as found under
+//! `sources.<path>.ast` of the `solc` standard JSON output. Only the
+//! syntactic fields are read, all node locations come from `src`. Node
+//! kinds, and variants of them, that the AST can't represent (such as
+//! libraries, array types, `try` statements or named call arguments) are
+//! reported as `ImportError::Unsupported`. A few things are approximated:
+//!
+//! + Nodes that `solc` doesn't locate, such as visibility of a function or
+//!   the path of an import, are given the location of their parent, and
+//!   the implicit default visibility is always set.
+//! + Constructors get the name of the contract, as in Solidity before 0.5.
+//! + `emit` statements are imported as calls to the event.
+//! + Doc comment tags are all located at the whole doc comment.
+
+use std::fmt;
+
+use serde_json::Value;
+use toolshed::list::List;
+use toolshed::Arena;
+
+use {*};
+
+/// Error produced by `import`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImportError {
+    /// The node, or a variant of it, can't be represented by the AST.
+    Unsupported {
+        node_type: String,
+        src: String,
+    },
+
+    /// A field of the node is missing, or its value is invalid.
+    InvalidField {
+        node_type: String,
+        field: &'static str,
+    },
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ImportError::Unsupported { ref node_type, ref src } => {
+                write!(f, "unsupported `{}` at {}", node_type, src)
+            },
+            ImportError::InvalidField { ref node_type, field } => {
+                write!(f, "missing or invalid field `{}` of `{}`", field, node_type)
+            },
+        }
+    }
+}
+
+impl ::std::error::Error for ImportError {}
+
+type Result<T> = ::std::result::Result<T, ImportError>;
+
+/// Build a `Program` from the `SourceUnit` node of `solc` JSON.
+pub fn import<'ast>(json: &Value) -> Result<Program<'ast>> {
+    let arena = Arena::new();
+
+    let body = {
+        let importer = Importer { arena: &arena };
+
+        importer.source_unit(json)?.into_unsafe()
+    };
+
+    Ok(Program::new(body, arena))
+}
+
+fn node_type(json: &Value) -> &str {
+    json["nodeType"].as_str().unwrap_or("")
+}
+
+fn unsupported(json: &Value) -> ImportError {
+    unsupported_as(json, node_type(json))
+}
+
+/// Unsupported variant of a node, such as a library `ContractDefinition`.
+fn unsupported_as(json: &Value, node_type: &str) -> ImportError {
+    ImportError::Unsupported {
+        node_type: node_type.into(),
+        src: json["src"].as_str().unwrap_or("").into(),
+    }
+}
+
+fn invalid(json: &Value, field: &'static str) -> ImportError {
+    ImportError::InvalidField {
+        node_type: node_type(json).into(),
+        field,
+    }
+}
+
+/// Field of a node that must be present and not `null`.
+fn field<'j>(json: &'j Value, field: &'static str) -> Result<&'j Value> {
+    match json.get(field) {
+        Some(&Value::Null) | None => Err(invalid(json, field)),
+        Some(value)               => Ok(value),
+    }
+}
+
+/// Field of a node that may be missing or `null`.
+fn opt_field<'j>(json: &'j Value, field: &'static str) -> Option<&'j Value> {
+    match json.get(field) {
+        Some(&Value::Null) | None => None,
+        Some(value)               => Some(value),
+    }
+}
+
+fn str_field<'j>(json: &'j Value, name: &'static str) -> Result<&'j str> {
+    field(json, name)?.as_str().ok_or_else(|| invalid(json, name))
+}
+
+/// Array field of a node, missing or `null` arrays are empty.
+fn array_field<'j>(json: &'j Value, field: &'static str) -> Result<&'j [Value]> {
+    match opt_field(json, field) {
+        Some(Value::Array(array)) => Ok(array),
+        Some(_)                   => Err(invalid(json, field)),
+        None                      => Ok(&[]),
+    }
+}
+
+/// Parse a `start:length:file` location.
+fn location(json: &Value, field: &'static str) -> Result<(u32, u32)> {
+    let src = str_field(json, field)?;
+    let mut parts = src.split(':').map(|part| part.parse::<u32>().ok());
+
+    match (parts.next(), parts.next()) {
+        (Some(Some(start)), Some(Some(length))) => {
+            start.checked_add(length).map(|end| (start, end)).ok_or_else(|| invalid(json, field))
+        },
+        _ => Err(invalid(json, field)),
+    }
+}
+
+fn src(json: &Value) -> Result<(u32, u32)> {
+    location(json, "src")
+}
+
+struct Importer<'ast> {
+    arena: &'ast Arena,
+}
+
+impl<'ast> Importer<'ast> {
+    fn node<T: Copy>(&self, (start, end): (u32, u32), value: T) -> Node<'ast, T> {
+        Node::new(self.arena.alloc(NodeInner::new(start, end, value)))
+    }
+
+    fn str(&self, value: &str) -> &'ast str {
+        self.arena.alloc_str(value)
+    }
+
+    fn list<T, F>(&self, items: &[Value], mut f: F) -> Result<NodeList<'ast, T>>
+    where
+        T: 'ast + Copy,
+        F: FnMut(&Self, &Value) -> Result<Node<'ast, T>>,
+    {
+        let nodes = items.iter().map(|item| f(self, item)).collect::<Result<Vec<_>>>()?;
+
+        Ok(List::from_iter(self.arena, nodes))
+    }
+
+    fn opt<T, F>(&self, json: Option<&Value>, f: F) -> Result<Option<T>>
+    where
+        F: FnOnce(&Self, &Value) -> Result<T>,
+    {
+        match json {
+            Some(json) => f(self, json).map(Some),
+            None       => Ok(None),
+        }
+    }
+
+    fn expect(&self, json: &Value, expected: &str) -> Result<()> {
+        if node_type(json) == expected {
+            Ok(())
+        } else {
+            Err(unsupported(json))
+        }
+    }
+
+    /// Identifier from the `name` field, located at `nameLocation` if
+    /// present, or the whole node otherwise.
+    fn name(&self, json: &Value) -> Result<IdentifierNode<'ast>> {
+        let name = str_field(json, "name")?;
+        let span = match opt_field(json, "nameLocation") {
+            Some(_) => location(json, "nameLocation")?,
+            None    => src(json)?,
+        };
+
+        Ok(self.node(span, self.str(name)))
+    }
+
+    /// Name of a `UserDefinedTypeName` or an `IdentifierPath`.
+    fn path(&self, json: &Value) -> Result<IdentifierNode<'ast>> {
+        match node_type(json) {
+            "IdentifierPath" => self.name(json),
+            "UserDefinedTypeName" => match opt_field(json, "pathNode") {
+                Some(path) => self.name(path),
+                None       => self.name(json),
+            },
+            _ => Err(unsupported(json)),
+        }
+    }
+
+    fn source_unit(&self, json: &Value) -> Result<SourceUnitList<'ast>> {
+        self.expect(json, "SourceUnit")?;
+
+        self.list(array_field(json, "nodes")?, |importer, unit| {
+            let span = src(unit)?;
+
+            let value = match node_type(unit) {
+                "PragmaDirective" => {
+                    let literals = array_field(unit, "literals")?;
+                    let mut version = String::new();
+
+                    for literal in literals {
+                        let literal = literal.as_str().ok_or_else(|| invalid(unit, "literals"))?;
+
+                        // Operators stick to the following version, `solc` splits versions at dots
+                        if !version.is_empty() && !version.ends_with(|ch| "^~<>=|-".contains(ch)) && !literal.starts_with('.') {
+                            version.push(' ');
+                        }
+
+                        version.push_str(literal);
+                    }
+
+                    SourceUnit::PragmaDirective(PragmaDirective {
+                        version: importer.str(&version),
+                    })
+                },
+                "ImportDirective"    => SourceUnit::ImportDirective(importer.import_directive(unit, span)?),
+                "ContractDefinition" => SourceUnit::ContractDefinition(importer.contract_definition(unit)?),
+                _                    => return Err(unsupported(unit)),
+            };
+
+            Ok(importer.node(span, value))
+        })
+    }
+
+    fn import_directive(&self, json: &Value, span: (u32, u32)) -> Result<ImportDirective<'ast>> {
+        let path   = str_field(json, "file")?;
+        let source = self.node(span, self.str(&quote(path.as_bytes())));
+        let aliases = array_field(json, "symbolAliases")?;
+
+        if !aliases.is_empty() {
+            let imports = self.list(aliases, |importer, alias| {
+                let foreign = field(alias, "foreign")?;
+                let symbol  = importer.name(foreign)?;
+                let alias   = match opt_field(alias, "local") {
+                    Some(local) => Some(importer.node(span, importer.str(local.as_str().ok_or_else(|| invalid(json, "symbolAliases"))?))),
+                    None        => None,
+                };
+
+                Ok(importer.node((symbol.start, symbol.end), Import { symbol, alias }))
+            })?;
+
+            return Ok(ImportDirective::ManyFrom { imports, source });
+        }
+
+        let alias = match opt_field(json, "unitAlias").and_then(Value::as_str) {
+            Some("") | None => None,
+            Some(alias)     => Some(self.node(span, self.str(alias))),
+        };
+
+        Ok(ImportDirective::Global { source, alias })
+    }
+
+    fn contract_definition(&self, json: &Value) -> Result<ContractDefinition<'ast>> {
+        match opt_field(json, "contractKind").and_then(Value::as_str) {
+            Some("contract") | None => {},
+            Some(kind)              => return Err(unsupported_as(json, &format!("ContractDefinition ({})", kind))),
+        }
+
+        if json["abstract"] == true {
+            return Err(unsupported_as(json, "ContractDefinition (abstract)"));
+        }
+
+        let span     = src(json)?;
+        let doc      = self.documentation(opt_field(json, "documentation"), span)?;
+        let name     = self.name(json)?;
+        let inherits = self.list(array_field(json, "baseContracts")?, |importer, base| {
+            importer.expect(base, "InheritanceSpecifier")?;
+
+            if !array_field(base, "arguments")?.is_empty() {
+                return Err(unsupported_as(base, "InheritanceSpecifier (arguments)"));
+            }
+
+            importer.path(field(base, "baseName")?)
+        })?;
+
+        let body = self.list(array_field(json, "nodes")?, |importer, part| {
+            let value = match node_type(part) {
+                "VariableDeclaration" => importer.state_variable(part)?.into(),
+                "UsingForDirective" => UsingForDeclaration {
+                    id: importer.path(field(part, "libraryName")?)?,
+                    type_name: importer.opt(opt_field(part, "typeName"), Self::type_name)?,
+                }.into(),
+                "StructDefinition" => StructDefinition {
+                    name: importer.name(part)?,
+                    body: importer.list(array_field(part, "members")?, Self::variable_declaration)?,
+                }.into(),
+                "ModifierDefinition" => ModifierDefinition {
+                    doc: importer.documentation(opt_field(part, "documentation"), src(part)?)?,
+                    name: importer.name(part)?,
+                    params: importer.parameter_list(field(part, "parameters")?)?,
+                    block: importer.block(field(part, "body")?)?,
+                }.into(),
+                "FunctionDefinition" => importer.function_definition(part, name)?.into(),
+                "EventDefinition" => {
+                    let parameters = field(part, "parameters")?;
+
+                    EventDefinition {
+                        doc: importer.documentation(opt_field(part, "documentation"), src(part)?)?,
+                        anonymous: if part["anonymous"] == true { Some(importer.node(src(part)?, Flag)) } else { None },
+                        name: importer.name(part)?,
+                        params: importer.list(array_field(parameters, "parameters")?, |importer, param| {
+                            let span = src(param)?;
+
+                            Ok(importer.node(span, IndexedParameter {
+                                type_name: importer.type_name(field(param, "typeName")?)?,
+                                indexed: if param["indexed"] == true { Some(importer.node(span, Flag)) } else { None },
+                                name: importer.opt_name(param)?,
+                            }))
+                        })?,
+                    }.into()
+                },
+                "EnumDefinition" => EnumDefinition {
+                    name: importer.name(part)?,
+                    variants: importer.list(array_field(part, "members")?, Self::name)?,
+                }.into(),
+                _ => return Err(unsupported(part)),
+            };
+
+            Ok(importer.node(src(part)?, value))
+        })?;
+
+        Ok(ContractDefinition {
+            doc,
+            name,
+            inherits,
+            body,
+        })
+    }
+
+    fn state_variable(&self, json: &Value) -> Result<StateVariableDeclaration<'ast>> {
+        let span = src(json)?;
+
+        let visibility = match str_field(json, "visibility")? {
+            "public"   => StateVariableVisibility::Public,
+            "internal" => StateVariableVisibility::Internal,
+            "private"  => StateVariableVisibility::Private,
+            _          => return Err(invalid(json, "visibility")),
+        };
+
+        let constant = json["constant"] == true || json["mutability"] == "constant";
+
+        Ok(StateVariableDeclaration {
+            doc: self.documentation(opt_field(json, "documentation"), span)?,
+            type_name: self.type_name(field(json, "typeName")?)?,
+            visibility: Some(self.node(span, visibility)),
+            constant: if constant { Some(self.node(span, Flag)) } else { None },
+            name: self.name(json)?,
+            init: self.opt(opt_field(json, "value"), Self::expression)?,
+        })
+    }
+
+    fn function_definition(&self, json: &Value, contract: IdentifierNode<'ast>) -> Result<FunctionDefinition<'ast>> {
+        let span = src(json)?;
+
+        let name = match str_field(json, "kind").unwrap_or("function") {
+            "function"    => Some(self.name(json)?),
+            "fallback"    => None,
+            "constructor" => Some(self.node((span.0, span.0), contract.value)),
+            kind          => return Err(unsupported_as(json, &format!("FunctionDefinition ({})", kind))),
+        };
+
+        let visibility = match str_field(json, "visibility")? {
+            "external" => FunctionVisibility::External,
+            "public"   => FunctionVisibility::Public,
+            "internal" => FunctionVisibility::Internal,
+            "private"  => FunctionVisibility::Private,
+            _          => return Err(invalid(json, "visibility")),
+        };
+
+        let mutability = match opt_field(json, "stateMutability").and_then(Value::as_str) {
+            Some("nonpayable") | None => None,
+            Some("pure")              => Some(StateMutability::Pure),
+            Some("view") if json["constant"] == true => Some(StateMutability::Constant),
+            Some("view")              => Some(StateMutability::View),
+            Some("payable")           => Some(StateMutability::Payable),
+            Some("constant")          => Some(StateMutability::Constant),
+            Some(_)                   => return Err(invalid(json, "stateMutability")),
+        };
+
+        let modifiers = self.list(array_field(json, "modifiers")?, |importer, modifier| {
+            importer.expect(modifier, "ModifierInvocation")?;
+
+            Ok(importer.node(src(modifier)?, ModifierInvocation {
+                id: importer.path_or_identifier(field(modifier, "modifierName")?)?,
+                arguments: importer.expressions(array_field(modifier, "arguments")?)?,
+            }))
+        })?;
+
+        Ok(FunctionDefinition {
+            doc: self.documentation(opt_field(json, "documentation"), span)?,
+            name,
+            params: self.parameter_list(field(json, "parameters")?)?,
+            visibility: Some(self.node(span, visibility)),
+            mutability: mutability.map(|mutability| self.node(span, mutability)),
+            modifiers,
+            returns: self.parameter_list(field(json, "returnParameters")?)?,
+            block: self.opt(opt_field(json, "body"), Self::block)?,
+        })
+    }
+
+    /// Older `solc` versions use `Identifier` for names of modifiers.
+    fn path_or_identifier(&self, json: &Value) -> Result<IdentifierNode<'ast>> {
+        match node_type(json) {
+            "Identifier" => self.name(json),
+            _            => self.path(json),
+        }
+    }
+
+    fn opt_name(&self, json: &Value) -> Result<Option<IdentifierNode<'ast>>> {
+        match opt_field(json, "name").and_then(Value::as_str) {
+            Some("") | None => Ok(None),
+            Some(_)         => self.name(json).map(Some),
+        }
+    }
+
+    fn parameter_list(&self, json: &Value) -> Result<ParameterList<'ast>> {
+        self.expect(json, "ParameterList")?;
+
+        self.list(array_field(json, "parameters")?, |importer, param| {
+            Ok(importer.node(src(param)?, Parameter {
+                type_name: importer.type_name(field(param, "typeName")?)?,
+                name: importer.opt_name(param)?,
+            }))
+        })
+    }
+
+    fn documentation(&self, json: Option<&Value>, parent: (u32, u32)) -> Result<Option<DocCommentNode<'ast>>> {
+        let (text, span) = match json {
+            Some(Value::String(text)) => (text.as_str(), (parent.0, parent.0)),
+            Some(json)                => (str_field(json, "text")?, src(json)?),
+            None                      => return Ok(None),
+        };
+
+        let mut tags = Vec::new();
+        let mut current: Option<(DocTagKind<'ast>, String)> = None;
+
+        for line in text.lines().map(str::trim) {
+            if let Some(line) = line.strip_prefix('@') {
+                if let Some((kind, text)) = current.take() {
+                    tags.push(self.node(span, DocTag { kind, text: self.str(text.trim()) }));
+                }
+
+                let mut words = line.splitn(2, char::is_whitespace);
+                let tag  = words.next().unwrap_or("");
+                let rest = words.next().unwrap_or("").trim_start();
+
+                let (kind, rest) = match tag {
+                    "title"  => (DocTagKind::Title, rest),
+                    "author" => (DocTagKind::Author, rest),
+                    "notice" => (DocTagKind::Notice, rest),
+                    "dev"    => (DocTagKind::Dev, rest),
+                    "return" => (DocTagKind::Return, rest),
+                    "param" | "inheritdoc" => {
+                        let mut words = rest.splitn(2, char::is_whitespace);
+                        let ident = self.str(words.next().unwrap_or(""));
+                        let rest  = words.next().unwrap_or("").trim_start();
+
+                        match tag {
+                            "param" => (DocTagKind::Param(ident), rest),
+                            _       => (DocTagKind::Inheritdoc(ident), rest),
+                        }
+                    },
+                    _ if tag.starts_with("custom:") => (DocTagKind::Custom(self.str(&tag[7..])), rest),
+                    _ => (DocTagKind::Unknown(self.str(tag)), rest),
+                };
+
+                current = Some((kind, rest.into()));
+            } else if let Some((_, ref mut text)) = current {
+                text.push(' ');
+                text.push_str(line);
+            } else if !line.is_empty() {
+                current = Some((DocTagKind::Notice, line.into()));
+            }
+        }
+
+        if let Some((kind, text)) = current {
+            tags.push(self.node(span, DocTag { kind, text: self.str(text.trim()) }));
+        }
+
+        Ok(Some(self.node(span, DocComment {
+            tags: List::from_iter(self.arena, tags),
+        })))
+    }
+
+    fn variable_declaration(&self, json: &Value) -> Result<VariableDeclarationNode<'ast>> {
+        self.expect(json, "VariableDeclaration")?;
+
+        let span = src(json)?;
+        let location = match opt_field(json, "storageLocation").and_then(Value::as_str) {
+            Some("default") | None => None,
+            Some("memory")         => Some(StorageLocation::Memory),
+            Some("storage")        => Some(StorageLocation::Storage),
+            Some(location)         => return Err(unsupported_as(json, &format!("VariableDeclaration ({})", location))),
+        };
+
+        Ok(self.node(span, VariableDeclaration {
+            type_name: self.type_name(field(json, "typeName")?)?,
+            location: location.map(|location| self.node(span, location)),
+            id: self.name(json)?,
+        }))
+    }
+
+    fn type_name(&self, json: &Value) -> Result<TypeNameNode<'ast>> {
+        let span = src(json)?;
+
+        let value = match node_type(json) {
+            "ElementaryTypeName" => self.elementary_type_name(json, str_field(json, "name")?)?.into(),
+            "UserDefinedTypeName" => TypeName::UserDefinedTypeName(self.path(json)?.value),
+            "Mapping" => {
+                let key = field(json, "keyType")?;
+
+                if node_type(key) != "ElementaryTypeName" {
+                    return Err(unsupported_as(key, "Mapping (non-elementary key)"));
+                }
+
+                let from = self.elementary_type_name(key, str_field(key, "name")?)?;
+
+                Mapping {
+                    from: self.node(src(key)?, from),
+                    to: self.type_name(field(json, "valueType")?)?,
+                }.into()
+            },
+            // The AST keeps no content for these, so they can't be imported
+            _ => return Err(unsupported(json)),
+        };
+
+        Ok(self.node(span, value))
+    }
+
+    fn elementary_type_name(&self, json: &Value, name: &str) -> Result<ElementaryTypeName> {
+        elementary_type_name(name).ok_or_else(|| invalid(json, "name"))
+    }
+
+    fn block(&self, json: &Value) -> Result<BlockNode<'ast>> {
+        self.expect(json, "Block")?;
+
+        Ok(self.node(src(json)?, Block {
+            body: self.list(array_field(json, "statements")?, Self::statement)?,
+        }))
+    }
+
+    fn statement(&self, json: &Value) -> Result<StatementNode<'ast>> {
+        let value = match node_type(json) {
+            "Block" => Statement::BlockStatement(Block {
+                body: self.list(array_field(json, "statements")?, Self::statement)?,
+            }),
+            "IfStatement" => IfStatement {
+                test: self.expression(field(json, "condition")?)?,
+                consequent: self.statement(field(json, "trueBody")?)?,
+                alternate: self.opt(opt_field(json, "falseBody"), Self::statement)?,
+            }.into(),
+            "WhileStatement" => WhileStatement {
+                test: self.expression(field(json, "condition")?)?,
+                body: self.statement(field(json, "body")?)?,
+            }.into(),
+            "DoWhileStatement" => DoWhileStatement {
+                body: self.statement(field(json, "body")?)?,
+                test: self.expression(field(json, "condition")?)?,
+            }.into(),
+            "ForStatement" => ForStatement {
+                init: self.opt(opt_field(json, "initializationExpression"), Self::simple_statement)?,
+                test: self.opt(opt_field(json, "condition"), Self::expression)?,
+                update: self.opt(opt_field(json, "loopExpression"), |importer, update| {
+                    importer.expect(update, "ExpressionStatement")?;
+                    importer.expression(field(update, "expression")?)
+                })?,
+                body: self.statement(field(json, "body")?)?,
+            }.into(),
+            "InlineAssembly" => {
+                let block = match opt_field(json, "AST") {
+                    Some(block) => block,
+                    None        => return Err(unsupported_as(json, "InlineAssembly (without Yul AST)")),
+                };
+
+                InlineAssemblyStatement {
+                    string: None,
+                    block: self.node(src(block)?, self.assembly_block(block)?),
+                }.into()
+            },
+            "Return" => ReturnStatement {
+                value: self.opt(opt_field(json, "expression"), Self::expression)?,
+            }.into(),
+            "EmitStatement"        => Statement::ExpressionStatement(self.expression(field(json, "eventCall")?)?),
+            "PlaceholderStatement" => Statement::Placeholder,
+            "Continue"             => Statement::ContinueStatement,
+            "Break"                => Statement::BreakStatement,
+            "Throw"                => Statement::ThrowStatement,
+            "ExpressionStatement" | "VariableDeclarationStatement" => {
+                match self.simple_statement(json)?.value {
+                    SimpleStatement::VariableDefinitionStatement(stmt) => stmt.into(),
+                    SimpleStatement::InferredDefinitionStatement(stmt) => stmt.into(),
+                    SimpleStatement::ExpressionStatement(expression)   => expression.into(),
+                }
+            },
+            _ => return Err(unsupported(json)),
+        };
+
+        Ok(self.node(src(json)?, value))
+    }
+
+    fn simple_statement(&self, json: &Value) -> Result<SimpleStatementNode<'ast>> {
+        let value = match node_type(json) {
+            "ExpressionStatement" => self.expression(field(json, "expression")?)?.into(),
+            "VariableDeclarationStatement" => {
+                let declarations = array_field(json, "declarations")?;
+                let init = self.opt(opt_field(json, "initialValue"), Self::expression)?;
+                let typed = declarations.iter().filter(|declaration| opt_field(declaration, "typeName").is_some()).count();
+
+                match (declarations.len(), typed) {
+                    (1, 1) => VariableDefinitionStatement {
+                        declaration: self.variable_declaration(&declarations[0])?,
+                        init,
+                    }.into(),
+                    (_, 0) => {
+                        let ids = declarations.iter().map(|declaration| match *declaration {
+                            Value::Null => Ok(None),
+                            _           => self.name(declaration).map(Some),
+                        }).collect::<Result<Vec<_>>>()?;
+
+                        InferredDefinitionStatement {
+                            ids: List::from_iter(self.arena, ids),
+                            init: init.ok_or_else(|| invalid(json, "initialValue"))?,
+                        }.into()
+                    },
+                    _ => return Err(unsupported_as(json, "VariableDeclarationStatement (typed tuple)")),
+                }
+            },
+            _ => return Err(unsupported(json)),
+        };
+
+        Ok(self.node(src(json)?, value))
+    }
+
+    fn expressions(&self, json: &[Value]) -> Result<ExpressionList<'ast>> {
+        self.list(json, Self::expression)
+    }
+
+    fn expression(&self, json: &Value) -> Result<ExpressionNode<'ast>> {
+        let span = src(json)?;
+
+        let value = match node_type(json) {
+            "Identifier" => match str_field(json, "name")? {
+                "this" => Expression::ThisExpression,
+                name   => Expression::IdentifierExpression(self.str(name)),
+            },
+            "Literal" => self.literal(json)?.into(),
+            "UnaryOperation" => {
+                let operator = str_field(json, "operator")?;
+                let operand  = self.expression(field(json, "subExpression")?)?;
+
+                if json["prefix"] == false {
+                    let operator = match operator {
+                        "++" => PostfixOperator::Increment,
+                        "--" => PostfixOperator::Decrement,
+                        _    => return Err(invalid(json, "operator")),
+                    };
+
+                    PostfixExpression {
+                        operand,
+                        operator: self.node((operand.end, span.1), operator),
+                    }.into()
+                } else {
+                    let operator = match operator {
+                        "!"      => PrefixOperator::LogicalNot,
+                        "~"      => PrefixOperator::BitNot,
+                        "delete" => PrefixOperator::Delete,
+                        "++"     => PrefixOperator::Increment,
+                        "--"     => PrefixOperator::Decrement,
+                        "+"      => PrefixOperator::Plus,
+                        "-"      => PrefixOperator::Minus,
+                        _        => return Err(invalid(json, "operator")),
+                    };
+
+                    PrefixExpression {
+                        operator: self.node((span.0, operand.start), operator),
+                        operand,
+                    }.into()
+                }
+            },
+            "BinaryOperation" => {
+                let left     = self.expression(field(json, "leftExpression")?)?;
+                let right    = self.expression(field(json, "rightExpression")?)?;
+                let operator = binary_operator(str_field(json, "operator")?).ok_or_else(|| invalid(json, "operator"))?;
+
+                BinaryExpression {
+                    left,
+                    operator: self.node(between(left, right), operator),
+                    right,
+                }.into()
+            },
+            "Assignment" => {
+                let left     = self.expression(field(json, "leftHandSide")?)?;
+                let right    = self.expression(field(json, "rightHandSide")?)?;
+                let operator = assignment_operator(str_field(json, "operator")?).ok_or_else(|| invalid(json, "operator"))?;
+
+                AssignmentExpression {
+                    left,
+                    operator: self.node(between(left, right), operator),
+                    right,
+                }.into()
+            },
+            "TupleExpression" => {
+                let components = array_field(json, "components")?;
+
+                if json["isInlineArray"] == true {
+                    return Err(unsupported_as(json, "TupleExpression (inline array)"));
+                }
+
+                if components.iter().any(Value::is_null) {
+                    return Err(unsupported_as(json, "TupleExpression (empty component)"));
+                }
+
+                TupleExpression {
+                    expressions: self.expressions(components)?,
+                }.into()
+            },
+            "FunctionCall" => {
+                if !array_field(json, "names")?.is_empty() {
+                    return Err(unsupported_as(json, "FunctionCall (named arguments)"));
+                }
+
+                CallExpression {
+                    callee: self.expression(field(json, "expression")?)?,
+                    arguments: self.expressions(array_field(json, "arguments")?)?,
+                }.into()
+            },
+            "MemberAccess" => {
+                let name = str_field(json, "memberName")?;
+
+                // Member name is at the very end, unless `solc` says otherwise
+                let member = match opt_field(json, "memberLocation") {
+                    Some(_) => location(json, "memberLocation")?,
+                    None    => (span.1.saturating_sub(name.len() as u32), span.1),
+                };
+
+                MemberAccessExpression {
+                    object: self.expression(field(json, "expression")?)?,
+                    member: self.node(member, self.str(name)),
+                }.into()
+            },
+            "IndexAccess" => IndexAccessExpression {
+                array: self.expression(field(json, "baseExpression")?)?,
+                index: self.opt(opt_field(json, "indexExpression"), Self::expression)?,
+            }.into(),
+            "Conditional" => ConditionalExpression {
+                test: self.expression(field(json, "condition")?)?,
+                consequent: self.expression(field(json, "trueExpression")?)?,
+                alternate: self.expression(field(json, "falseExpression")?)?,
+            }.into(),
+            "ElementaryTypeNameExpression" => {
+                // Older `solc` versions have the name as a string
+                let type_name = field(json, "typeName")?;
+                let name = match *type_name {
+                    Value::String(ref name) => name.as_str(),
+                    _                       => str_field(type_name, "name")?,
+                };
+
+                Expression::ElementaryTypeExpression(self.elementary_type_name(json, name)?)
+            },
+            _ => return Err(unsupported(json)),
+        };
+
+        Ok(self.node(span, value))
+    }
+
+    fn literal(&self, json: &Value) -> Result<Primitive<'ast>> {
+        let kind = str_field(json, "kind")?;
+
+        let unit = match opt_field(json, "subdenomination").and_then(Value::as_str) {
+            None => NumberUnit::None,
+            Some(unit) => number_unit(unit).ok_or_else(|| invalid(json, "subdenomination"))?,
+        };
+
+        match kind {
+            "bool" => match str_field(json, "value")? {
+                "true"  => Ok(Primitive::Bool(true)),
+                "false" => Ok(Primitive::Bool(false)),
+                _       => Err(invalid(json, "value")),
+            },
+            "number" => {
+                let value = self.str(str_field(json, "value")?);
+
+                if value.starts_with("0x") {
+                    Ok(Primitive::HexNumber(value))
+                } else if value.contains('.') {
+                    Ok(Primitive::RationalNumber(value, unit))
+                } else {
+                    Ok(Primitive::IntegerNumber(value, unit))
+                }
+            },
+            "string" | "unicodeString" => {
+                // `value` is `null` for strings that aren't valid UTF-8
+                let bytes = match opt_field(json, "hexValue").and_then(Value::as_str) {
+                    Some(hex) => decode_hex(hex).ok_or_else(|| invalid(json, "hexValue"))?,
+                    None      => str_field(json, "value")?.as_bytes().to_vec(),
+                };
+
+                let raw = match kind {
+                    "string" => quote(&bytes),
+                    _        => {
+                        let text = ::std::str::from_utf8(&bytes).map_err(|_| invalid(json, "hexValue"))?;
+
+                        format!("unicode{}", quote_unicode(text))
+                    },
+                };
+
+                Ok(Primitive::String(self.str(&raw)))
+            },
+            _ => Err(unsupported_as(json, &format!("Literal ({})", kind))),
+        }
+    }
+
+    fn assembly_block(&self, json: &Value) -> Result<InlineAssemblyBlock<'ast>> {
+        self.expect(json, "YulBlock")?;
+
+        Ok(InlineAssemblyBlock {
+            items: self.list(array_field(json, "statements")?, Self::assembly_statement)?,
+        })
+    }
+
+    fn assembly_statement(&self, json: &Value) -> Result<AssemblyItemNode<'ast>> {
+        let value = match node_type(json) {
+            "YulBlock"               => self.assembly_block(json)?.into(),
+            "YulExpressionStatement" => return self.assembly_expression(field(json, "expression")?),
+            "YulVariableDeclaration" => {
+                let variables = array_field(json, "variables")?;
+
+                if variables.len() != 1 {
+                    return Err(unsupported_as(json, "YulVariableDeclaration (multiple variables)"));
+                }
+
+                AssemblyLocalBinding {
+                    id: self.name(&variables[0])?,
+                    init: self.assembly_call(field(json, "value")?)?,
+                }.into()
+            },
+            "YulAssignment" => {
+                let names = array_field(json, "variableNames")?;
+
+                if names.len() != 1 {
+                    return Err(unsupported_as(json, "YulAssignment (multiple variables)"));
+                }
+
+                AssemblyAssignment {
+                    id: self.name(&names[0])?,
+                    init: self.assembly_call(field(json, "value")?)?,
+                }.into()
+            },
+            "YulLabel" => AssemblyLabel {
+                id: self.name(json)?,
+            }.into(),
+            _ => return Err(unsupported(json)),
+        };
+
+        Ok(self.node(src(json)?, value))
+    }
+
+    fn assembly_expression(&self, json: &Value) -> Result<AssemblyItemNode<'ast>> {
+        let value = match node_type(json) {
+            "YulIdentifier"   => AssemblyItem::Identifier(self.str(str_field(json, "name")?)),
+            "YulFunctionCall" => self.assembly_call(json)?.value.into(),
+            "YulLiteral" => match str_field(json, "kind")? {
+                "number" => {
+                    let value = self.str(str_field(json, "value")?);
+
+                    if value.starts_with("0x") {
+                        Primitive::HexNumber(value).into()
+                    } else {
+                        Primitive::IntegerNumber(value, NumberUnit::None).into()
+                    }
+                },
+                "string" => AssemblyItem::StringLiteral,
+                kind     => return Err(unsupported_as(json, &format!("YulLiteral ({})", kind))),
+            },
+            _ => return Err(unsupported(json)),
+        };
+
+        Ok(self.node(src(json)?, value))
+    }
+
+    fn assembly_call(&self, json: &Value) -> Result<FunctionalAssemblyExpressionNode<'ast>> {
+        if node_type(json) != "YulFunctionCall" {
+            return Err(unsupported_as(json, &format!("{} (as value of a Yul variable)", node_type(json))));
+        }
+
+        Ok(self.node(src(json)?, FunctionalAssemblyExpression {
+            id: self.name(field(json, "functionName")?)?,
+            arguments: self.list(array_field(json, "arguments")?, Self::assembly_expression)?,
+        }))
+    }
+}
+
+/// Location between two nodes, where the operator of a binary expression is.
+fn between<T>(left: Node<T>, right: Node<T>) -> (u32, u32) {
+    (left.end, right.start.max(left.end))
+}
+
+fn elementary_type_name(name: &str) -> Option<ElementaryTypeName> {
+    fn size(digits: &str, default: u16) -> Option<u16> {
+        match digits {
+            "" => Some(default),
+            _  => digits.parse().ok(),
+        }
+    }
+
+    fn bits(digits: &str) -> Option<u8> {
+        match size(digits, 256)? {
+            bits @ 8..=256 if bits % 8 == 0 => Some((bits / 8) as u8),
+            _                               => None,
+        }
+    }
+
+    fn fixed(digits: &str) -> Option<(u8, u8)> {
+        if digits.is_empty() {
+            return Some((16, 18));
+        }
+
+        let mut parts = digits.splitn(2, 'x');
+        let size      = bits(parts.next()?)?;
+        let decimals  = parts.next()?.parse::<u8>().ok().filter(|decimals| *decimals <= 80)?;
+
+        Some((size, decimals))
+    }
+
+    Some(match name {
+        "address" | "address payable" => ElementaryTypeName::Address,
+        "bool"                        => ElementaryTypeName::Bool,
+        "string"                      => ElementaryTypeName::String,
+        "bytes"                       => ElementaryTypeName::Bytes,
+        "byte"                        => ElementaryTypeName::Byte(1),
+        _ if name.starts_with("bytes") => match size(&name[5..], 0)? {
+            size @ 1..=32 => ElementaryTypeName::Byte(size as u8),
+            _             => return None,
+        },
+        _ if name.starts_with("uint")   => ElementaryTypeName::Uint(bits(&name[4..])?),
+        _ if name.starts_with("int")    => ElementaryTypeName::Int(bits(&name[3..])?),
+        _ if name.starts_with("ufixed") => {
+            let (size, decimals) = fixed(&name[6..])?;
+
+            ElementaryTypeName::Ufixed(size, decimals)
+        },
+        _ if name.starts_with("fixed") => {
+            let (size, decimals) = fixed(&name[5..])?;
+
+            ElementaryTypeName::Fixed(size, decimals)
+        },
+        _ => return None,
+    })
+}
+
+fn binary_operator(operator: &str) -> Option<BinaryOperator> {
+    Some(match operator {
+        "*"  => BinaryOperator::Multiplication,
+        "/"  => BinaryOperator::Division,
+        "%"  => BinaryOperator::Remainder,
+        "**" => BinaryOperator::Exponent,
+        "+"  => BinaryOperator::Addition,
+        "-"  => BinaryOperator::Subtraction,
+        "<<" => BinaryOperator::BitShiftLeft,
+        ">>" => BinaryOperator::BitShiftRight,
+        "<"  => BinaryOperator::Lesser,
+        "<=" => BinaryOperator::LesserEquals,
+        ">"  => BinaryOperator::Greater,
+        ">=" => BinaryOperator::GreaterEquals,
+        "==" => BinaryOperator::Equality,
+        "!=" => BinaryOperator::Inequality,
+        "&"  => BinaryOperator::BitAnd,
+        "^"  => BinaryOperator::BitXor,
+        "|"  => BinaryOperator::BitOr,
+        "&&" => BinaryOperator::LogicalAnd,
+        "||" => BinaryOperator::LogicalOr,
+        _    => return None,
+    })
+}
+
+fn assignment_operator(operator: &str) -> Option<AssignmentOperator> {
+    Some(match operator {
+        "="   => AssignmentOperator::Plain,
+        "+="  => AssignmentOperator::Addition,
+        "-="  => AssignmentOperator::Subtraction,
+        "*="  => AssignmentOperator::Multiplication,
+        "/="  => AssignmentOperator::Division,
+        "%="  => AssignmentOperator::Remainder,
+        "<<=" => AssignmentOperator::BitShiftLeft,
+        ">>=" => AssignmentOperator::BitShiftRight,
+        "&="  => AssignmentOperator::BitAnd,
+        "^="  => AssignmentOperator::BitXor,
+        "|="  => AssignmentOperator::BitOr,
+        _     => return None,
+    })
+}
+
+fn number_unit(unit: &str) -> Option<NumberUnit> {
+    Some(match unit {
+        "ether"   => NumberUnit::Ether(EtherUnit::Ether),
+        "finney"  => NumberUnit::Ether(EtherUnit::Finney),
+        "szabo"   => NumberUnit::Ether(EtherUnit::Szabo),
+        "gwei"    => NumberUnit::Ether(EtherUnit::Gwei),
+        "wei"     => NumberUnit::Ether(EtherUnit::Wei),
+        "years"   => NumberUnit::Time(TimeUnit::Years),
+        "months"  => NumberUnit::Time(TimeUnit::Months),
+        "weeks"   => NumberUnit::Time(TimeUnit::Weeks),
+        "days"    => NumberUnit::Time(TimeUnit::Days),
+        "hours"   => NumberUnit::Time(TimeUnit::Hours),
+        "minutes" => NumberUnit::Time(TimeUnit::Minutes),
+        "seconds" => NumberUnit::Time(TimeUnit::Seconds),
+        _         => return None,
+    })
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    hex.as_bytes().chunks(2).map(|pair| match *pair {
+        [high, low] => Some((hex_digit(high)? << 4) | hex_digit(low)?),
+        _           => None,
+    }).collect()
+}
+
+fn hex_digit(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|digit| digit as u8)
+}
+
+/// Source of a string literal with the given bytes, escaping anything
+/// but printable ASCII.
+fn quote(bytes: &[u8]) -> String {
+    let mut raw = String::with_capacity(bytes.len() + 2);
+
+    raw.push('"');
+
+    for &byte in bytes {
+        match byte {
+            b'"'         => raw.push_str("\\\""),
+            b'\\'        => raw.push_str("\\\\"),
+            b'\n'        => raw.push_str("\\n"),
+            b'\r'        => raw.push_str("\\r"),
+            b'\t'        => raw.push_str("\\t"),
+            b' '..=b'~'  => raw.push(byte as char),
+            _            => raw.push_str(&format!("\\x{:02x}", byte)),
+        }
+    }
+
+    raw.push('"');
+    raw
+}
+
+/// Same as `quote`, keeping non-ASCII characters as they are.
+fn quote_unicode(text: &str) -> String {
+    let mut raw = String::with_capacity(text.len() + 2);
+
+    raw.push('"');
+
+    for ch in text.chars() {
+        match ch {
+            '\u{0}'..='\u{7f}' => {
+                let escaped = quote(&[ch as u8]);
+
+                raw.push_str(&escaped[1..escaped.len() - 1]);
+            },
+            _ => raw.push(ch),
+        }
+    }
+
+    raw.push('"');
+    raw
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse<'ast>(json: Value) -> Program<'ast> {
+        import(&json).expect("Must import")
+    }
+
+    #[test]
+    fn import_contract() {
+        // pragma solidity ^0.4.17; contract Foo { function bar(uint a) public returns (uint) { return a * 1 ether; } }
+        let uint = |src| json!({ "nodeType": "ElementaryTypeName", "src": src, "name": "uint" });
+
+        let params = json!({
+            "nodeType": "ParameterList",
+            "src": "52:8:0",
+            "parameters": [{
+                "nodeType": "VariableDeclaration",
+                "src": "53:6:0",
+                "name": "a",
+                "nameLocation": "58:1:0",
+                "typeName": uint("53:4:0"),
+            }],
+        });
+
+        let returns = json!({
+            "nodeType": "ParameterList",
+            "src": "76:6:0",
+            "parameters": [{
+                "nodeType": "VariableDeclaration",
+                "src": "77:4:0",
+                "name": "",
+                "typeName": uint("77:4:0"),
+            }],
+        });
+
+        let value = json!({
+            "nodeType": "BinaryOperation",
+            "src": "92:5:0",
+            "operator": "*",
+            "leftExpression": { "nodeType": "Identifier", "src": "92:1:0", "name": "a" },
+            "rightExpression": {
+                "nodeType": "Literal",
+                "src": "96:7:0",
+                "kind": "number",
+                "value": "1",
+                "subdenomination": "ether",
+            },
+        });
+
+        let function = json!({
+            "nodeType": "FunctionDefinition",
+            "src": "40:59:0",
+            "kind": "function",
+            "name": "bar",
+            "nameLocation": "49:3:0",
+            "visibility": "public",
+            "stateMutability": "nonpayable",
+            "modifiers": [],
+            "parameters": params,
+            "returnParameters": returns,
+            "body": {
+                "nodeType": "Block",
+                "src": "83:16:0",
+                "statements": [{ "nodeType": "Return", "src": "85:12:0", "expression": value }],
+            },
+        });
+
+        let program = parse(json!({
+            "nodeType": "SourceUnit",
+            "src": "0:101:0",
+            "nodes": [{
+                "nodeType": "PragmaDirective",
+                "src": "0:24:0",
+                "literals": ["solidity", "^", "0.4", ".17"],
+            }, {
+                "nodeType": "ContractDefinition",
+                "src": "25:76:0",
+                "contractKind": "contract",
+                "name": "Foo",
+                "nameLocation": "34:3:0",
+                "baseContracts": [],
+                "nodes": [function],
+            }],
+        }));
+
+        let mut units = program.body().iter();
+
+        match units.next().unwrap().value {
+            SourceUnit::PragmaDirective(pragma) => assert_eq!(pragma.version, "solidity ^0.4.17"),
+            unit => panic!("Expected pragma, got {:?}", unit),
+        }
+
+        let contract = match units.next().unwrap().value {
+            SourceUnit::ContractDefinition(contract) => contract,
+            unit => panic!("Expected contract, got {:?}", unit),
+        };
+
+        assert_eq!(contract.name.value, "Foo");
+        assert_eq!((contract.name.start, contract.name.end), (34, 37));
+
+        let function = match contract.body.iter().next().unwrap().value {
+            ContractPart::FunctionDefinition(function) => function,
+            part => panic!("Expected function, got {:?}", part),
+        };
+
+        let param = function.params.iter().next().unwrap();
+
+        assert_eq!(function.name.unwrap().value, "bar");
+        assert_eq!(param.value.name.unwrap().value, "a");
+        assert_eq!(param.value.type_name.value, TypeName::ElementaryTypeName(ElementaryTypeName::Uint(32)));
+        assert_eq!(function.returns.iter().next().unwrap().value.name, None);
+        assert_eq!(function.visibility.unwrap().value, FunctionVisibility::Public);
+        assert_eq!(function.mutability, None);
+
+        let statement = function.block.unwrap().value.body.iter().next().unwrap();
+
+        let value = match statement.value {
+            Statement::ReturnStatement(ReturnStatement { value: Some(value) }) => value,
+            statement => panic!("Expected return, got {:?}", statement),
+        };
+
+        match value.value {
+            Expression::BinaryExpression(binary) => {
+                assert_eq!(binary.left.value, Expression::IdentifierExpression("a"));
+                assert_eq!(binary.operator.value, BinaryOperator::Multiplication);
+                assert_eq!(binary.right.value, Expression::PrimitiveExpression(Primitive::IntegerNumber("1", NumberUnit::Ether(EtherUnit::Ether))));
+            },
+            expression => panic!("Expected binary expression, got {:?}", expression),
+        }
+    }
+
+    #[test]
+    fn constant_functions() {
+        let exported = Program::build(|b| {
+            let view = b.function(Some("a"), b.list(None), Some(FunctionVisibility::Public), Some(StateMutability::View), b.list(None), None);
+            let constant = b.function(Some("b"), b.list(None), Some(FunctionVisibility::Public), Some(StateMutability::Constant), b.list(None), None);
+
+            b.list(vec![b.contract("Foo", &[], vec![view, constant])])
+        });
+
+        let json: Value = exported.to_solc_json("foo.sol").parse().unwrap();
+        let imported = parse(json);
+
+        assert!(structurally_eq(&exported, &imported, StructuralOptions::default()));
+    }
+
+    #[test]
+    fn import_string_literal() {
+        let program = parse(json!({
+            "nodeType": "SourceUnit",
+            "src": "0:30:0",
+            "nodes": [{
+                "nodeType": "ImportDirective",
+                "src": "0:30:0",
+                "file": "./foo.sol",
+                "unitAlias": "",
+                "symbolAliases": [],
+            }],
+        }));
+
+        match program.body().iter().next().unwrap().value {
+            SourceUnit::ImportDirective(ImportDirective::Global { source, alias: None }) => {
+                assert_eq!(source.value, "\"./foo.sol\"");
+            },
+            unit => panic!("Expected import, got {:?}", unit),
+        }
+
+        assert_eq!(quote(b"a\"b\\\n\xff"), r#""a\"b\\\n\xff""#);
+        assert_eq!(quote_unicode("\"ł\""), "\"\\\"ł\\\"\"");
+    }
+
+    #[test]
+    fn unsupported_node() {
+        let error = import(&json!({
+            "nodeType": "SourceUnit",
+            "src": "0:20:0",
+            "nodes": [{
+                "nodeType": "ContractDefinition",
+                "src": "0:20:0",
+                "contractKind": "library",
+                "name": "Foo",
+                "baseContracts": [],
+                "nodes": [],
+            }],
+        })).map(|_| ()).unwrap_err();
+
+        assert_eq!(error, ImportError::Unsupported {
+            node_type: "ContractDefinition (library)".into(),
+            src: "0:20:0".into(),
+        });
+        assert_eq!(error.to_string(), "unsupported `ContractDefinition (library)` at 0:20:0");
+    }
+
+    #[test]
+    fn invalid_field() {
+        let error = import(&json!({
+            "nodeType": "SourceUnit",
+            "src": "0:20:0",
+            "nodes": [{
+                "nodeType": "PragmaDirective",
+                "literals": ["solidity"],
+            }],
+        })).map(|_| ()).unwrap_err();
+
+        assert_eq!(error, ImportError::InvalidField {
+            node_type: "PragmaDirective".into(),
+            field: "src",
+        });
+    }
+
+    #[test]
+    fn unsupported_type_names() {
+        for &node_type in ["ArrayTypeName", "FunctionTypeName"].iter() {
+            let error = import(&json!({
+                "nodeType": "SourceUnit",
+                "src": "0:20:0",
+                "nodes": [{
+                    "nodeType": "ContractDefinition",
+                    "src": "0:20:0",
+                    "contractKind": "contract",
+                    "name": "Foo",
+                    "baseContracts": [],
+                    "nodes": [{
+                        "nodeType": "VariableDeclaration",
+                        "src": "15:3:0",
+                        "name": "a",
+                        "visibility": "internal",
+                        "typeName": {
+                            "nodeType": node_type,
+                            "src": "15:1:0",
+                        },
+                    }],
+                }],
+            })).map(|_| ()).unwrap_err();
+
+            assert_eq!(error, ImportError::Unsupported {
+                node_type: node_type.into(),
+                src: "15:1:0".into(),
+            });
+        }
+    }
+
+    #[test]
+    fn overflowing_location() {
+        let error = import(&json!({
+            "nodeType": "SourceUnit",
+            "src": "0:20:0",
+            "nodes": [{
+                "nodeType": "PragmaDirective",
+                "src": "4294967295:1:0",
+                "literals": ["solidity"],
+            }],
+        })).map(|_| ()).unwrap_err();
+
+        assert_eq!(error, ImportError::InvalidField {
+            node_type: "PragmaDirective".into(),
+            field: "src",
+        });
+    }
+
+    #[test]
+    fn elementary_type_names() {
+        assert_eq!(elementary_type_name("uint"), Some(ElementaryTypeName::Uint(32)));
+        assert_eq!(elementary_type_name("int8"), Some(ElementaryTypeName::Int(1)));
+        assert_eq!(elementary_type_name("bytes4"), Some(ElementaryTypeName::Byte(4)));
+        assert_eq!(elementary_type_name("byte"), Some(ElementaryTypeName::Byte(1)));
+        assert_eq!(elementary_type_name("address payable"), Some(ElementaryTypeName::Address));
+        assert_eq!(elementary_type_name("ufixed128x10"), Some(ElementaryTypeName::Ufixed(16, 10)));
+        assert_eq!(elementary_type_name("uint7"), None);
+        assert_eq!(elementary_type_name("bytes33"), None);
+    }
+}
@@ -0,0 +1,8 @@
+//! Conversion between the AST and the compact JSON format of
+//! `solc --ast-compact-json`, enabled with the `serde` feature.
+
+mod export;
+mod import;
+
+pub use self::export::Exporter;
+pub use self::import::{import, ImportError};
@@ -6,6 +6,21 @@ pub enum SourceUnit<'ast> {
     PragmaDirective(PragmaDirective<'ast>),
     ImportDirective(ImportDirective<'ast>),
     ContractDefinition(ContractDefinition<'ast>),
+
+    /// Source that failed to parse, only produced when parsing with error recovery
+    Error,
+}
+
+impl<'ast> SourceUnit<'ast> {
+    /// Name of the variant.
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            SourceUnit::PragmaDirective(_)    => "PragmaDirective",
+            SourceUnit::ImportDirective(_)    => "ImportDirective",
+            SourceUnit::ContractDefinition(_) => "ContractDefinition",
+            SourceUnit::Error                 => "Error",
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -0,0 +1,224 @@
+use std::fmt;
+
+use LineIndex;
+
+/// Identifier of a file in `SourceFiles`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FileId(u32);
+
+impl FileId {
+    #[inline]
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Single file of `SourceFiles`.
+#[derive(Clone, Debug)]
+pub struct SourceFile {
+    path: String,
+    source: String,
+    start: u32,
+}
+
+impl SourceFile {
+    #[inline]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    #[inline]
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Offset at which the file begins in the space of all files.
+    #[inline]
+    pub fn start(&self) -> u32 {
+        self.start
+    }
+
+    /// Offset at which the file ends in the space of all files.
+    #[inline]
+    pub fn end(&self) -> u32 {
+        self.start + self.source.len() as u32
+    }
+
+    /// Convert an offset in the space of all files to an offset in this file.
+    #[inline]
+    pub fn local(&self, offset: u32) -> u32 {
+        offset - self.start
+    }
+
+    /// Build a `LineIndex` for the source of the file. Offsets given to it
+    /// have to be converted with `local` first.
+    pub fn line_index<'a>(&'a self) -> LineIndex<'a> {
+        LineIndex::new(&self.source)
+    }
+}
+
+/// Registry of the files of a project.
+///
+/// Every file gets its own range of offsets, following the range of the
+/// file added before it, with a gap of one so that the end of one file is
+/// never the start of another. Parsing a file with `parse_file` puts the
+/// locations of nodes and errors within its range, so that any location
+/// of any file can be resolved back to it, while nodes are kept as small
+/// as when parsing a single source.
+#[derive(Clone, Debug, Default)]
+pub struct SourceFiles {
+    files: Vec<SourceFile>,
+}
+
+impl SourceFiles {
+    pub fn new() -> Self {
+        SourceFiles::default()
+    }
+
+    /// Add a file, panics if the offsets of all files no longer fit in `u32`.
+    pub fn add<P, S>(&mut self, path: P, source: S) -> FileId
+    where
+        P: Into<String>,
+        S: Into<String>,
+    {
+        let source = source.into();
+        let start = self.files.last().map_or(0, |file| file.end() as u64 + 1);
+
+        assert!(start + source.len() as u64 <= u32::MAX as u64, "Too much source to fit all offsets in u32");
+
+        self.files.push(SourceFile {
+            path: path.into(),
+            source,
+            start: start as u32,
+        });
+
+        FileId(self.files.len() as u32 - 1)
+    }
+
+    #[inline]
+    pub fn get(&self, id: FileId) -> &SourceFile {
+        &self.files[id.index()]
+    }
+
+    #[inline]
+    pub fn path(&self, id: FileId) -> &str {
+        self.get(id).path()
+    }
+
+    #[inline]
+    pub fn source(&self, id: FileId) -> &str {
+        self.get(id).source()
+    }
+
+    /// Find the file the offset belongs to, the end of a file included.
+    pub fn file_at(&self, offset: u32) -> Option<FileId> {
+        let index = match self.files.binary_search_by_key(&offset, |file| file.start) {
+            Ok(index)  => index,
+            Err(0)     => return None,
+            Err(index) => index - 1,
+        };
+
+        if offset <= self.files[index].end() {
+            Some(FileId(index as u32))
+        } else {
+            None
+        }
+    }
+
+    /// Resolve the offset to its file and the offset within that file.
+    pub fn resolve(&self, offset: u32) -> Option<Location> {
+        let file = self.file_at(offset)?;
+
+        Some(Location {
+            file,
+            offset: self.get(file).local(offset),
+        })
+    }
+
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = (FileId, &'a SourceFile)> + 'a {
+        self.files.iter().enumerate().map(|(index, file)| (FileId(index as u32), file))
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Display the offset as `path:line:column`, one-based, with the column in `char`s.
+    pub fn display<'a>(&'a self, offset: u32) -> Option<DisplayLocation<'a>> {
+        let location = self.resolve(offset)?;
+        let file = self.get(location.file);
+        let line_col = file.line_index().line_col_chars(location.offset);
+
+        Some(DisplayLocation {
+            path: file.path(),
+            line: line_col.line + 1,
+            column: line_col.column + 1,
+        })
+    }
+}
+
+/// Offset within a file of `SourceFiles`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Location {
+    pub file: FileId,
+    pub offset: u32,
+}
+
+/// Location in a file that displays as `path:line:column`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DisplayLocation<'a> {
+    pub path: &'a str,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl<'a> fmt::Display for DisplayLocation<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.path, self.line, self.column)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn file_ranges() {
+        let mut files = SourceFiles::new();
+
+        let a = files.add("a.sol", "contract A {}");
+        let b = files.add("b.sol", "contract B {}\ncontract C {}");
+        let c = files.add("c.sol", "");
+
+        assert_eq!(files.get(a).start(), 0);
+        assert_eq!(files.get(a).end(), 13);
+        assert_eq!(files.get(b).start(), 14);
+        assert_eq!(files.get(b).end(), 41);
+        assert_eq!(files.get(c).start(), 42);
+        assert_eq!(files.path(b), "b.sol");
+
+        assert_eq!(files.file_at(0), Some(a));
+        assert_eq!(files.file_at(13), Some(a));
+        assert_eq!(files.file_at(14), Some(b));
+        assert_eq!(files.file_at(41), Some(b));
+        assert_eq!(files.file_at(42), Some(c));
+        assert_eq!(files.file_at(43), None);
+
+        assert_eq!(files.resolve(37), Some(Location { file: b, offset: 23 }));
+        assert_eq!(files.display(37).unwrap().to_string(), "b.sol:2:10");
+    }
+
+    #[test]
+    fn no_files() {
+        let files = SourceFiles::new();
+
+        assert_eq!(files.file_at(0), None);
+        assert!(files.display(0).is_none());
+    }
+}
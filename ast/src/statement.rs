@@ -19,6 +19,32 @@ pub enum Statement<'ast> {
     VariableDefinitionStatement(VariableDefinitionStatement<'ast>),
     InferredDefinitionStatement(InferredDefinitionStatement<'ast>),
     ExpressionStatement(ExpressionNode<'ast>),
+
+    /// Source that failed to parse, only produced when parsing with error recovery
+    Error,
+}
+
+impl<'ast> Statement<'ast> {
+    /// Name of the variant.
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            Statement::Placeholder                    => "Placeholder",
+            Statement::IfStatement(_)                 => "IfStatement",
+            Statement::WhileStatement(_)              => "WhileStatement",
+            Statement::ForStatement(_)                => "ForStatement",
+            Statement::BlockStatement(_)              => "BlockStatement",
+            Statement::InlineAssemblyStatement(_)     => "InlineAssemblyStatement",
+            Statement::DoWhileStatement(_)            => "DoWhileStatement",
+            Statement::ContinueStatement              => "ContinueStatement",
+            Statement::BreakStatement                 => "BreakStatement",
+            Statement::ReturnStatement(_)             => "ReturnStatement",
+            Statement::ThrowStatement                 => "ThrowStatement",
+            Statement::VariableDefinitionStatement(_) => "VariableDefinitionStatement",
+            Statement::InferredDefinitionStatement(_) => "InferredDefinitionStatement",
+            Statement::ExpressionStatement(_)         => "ExpressionStatement",
+            Statement::Error                          => "Error",
+        }
+    }
 }
 
 /// Used in the `for` loop initialization.
@@ -29,6 +55,17 @@ pub enum SimpleStatement<'ast> {
     ExpressionStatement(ExpressionNode<'ast>),
 }
 
+impl<'ast> SimpleStatement<'ast> {
+    /// Name of the variant.
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            SimpleStatement::VariableDefinitionStatement(_) => "VariableDefinitionStatement",
+            SimpleStatement::InferredDefinitionStatement(_) => "InferredDefinitionStatement",
+            SimpleStatement::ExpressionStatement(_)         => "ExpressionStatement",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct IfStatement<'ast> {
     pub test: ExpressionNode<'ast>,
@@ -0,0 +1,646 @@
+//! Comparison and hashing of the AST by structure, ignoring the locations
+//! of nodes, for finding the same code in different places.
+//!
+//! Doc comments are ignored as well. With `StructuralOptions::normalize_locals`
+//! the names of parameters and local variables of functions and modifiers,
+//! including the ones declared in inline assembly, are ignored too, so that
+//! `function f(uint a) { a++; }` is the same as `function f(uint b) { b++; }`.
+//! Each local is then identified by the order of its declaration within the
+//! function instead.
+
+use std::hash::{Hash, Hasher};
+
+use toolshed::list::List;
+
+use {*};
+
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct StructuralOptions {
+    /// Ignore the names of parameters and local variables, see module docs.
+    pub normalize_locals: bool,
+}
+
+/// AST types that can be compared and hashed by structure, see module docs.
+pub trait StructuralHash<'ast> {
+    /// Write the structure of the value, this is all the other methods need.
+    fn structure(&self, structure: &mut Structure<'ast>);
+
+    fn structural_hash<H: Hasher>(&self, options: StructuralOptions, state: &mut H) {
+        Structure::of(self, options).tokens.hash(state)
+    }
+
+    fn structurally_eq(&self, other: &Self, options: StructuralOptions) -> bool {
+        Structure::of(self, options).tokens == Structure::of(other, options).tokens
+    }
+}
+
+/// Check if two values of the AST are the same, ignoring locations.
+#[inline]
+pub fn structurally_eq<'ast, T>(a: &T, b: &T, options: StructuralOptions) -> bool
+where
+    T: StructuralHash<'ast> + ?Sized,
+{
+    a.structurally_eq(b, options)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Token<'ast> {
+    Kind(&'static str),
+    Str(&'ast str),
+    Local(usize),
+    Size(u8),
+    Len(usize),
+    None,
+    Some,
+}
+
+/// Structure of a value of the AST, written by `StructuralHash::structure`.
+pub struct Structure<'ast> {
+    tokens: Vec<Token<'ast>>,
+    options: StructuralOptions,
+
+    /// Names of the locals declared so far in the current function, in order
+    locals: Vec<&'ast str>,
+
+    /// Set within functions and modifiers
+    in_function: bool,
+}
+
+impl<'ast> Structure<'ast> {
+    fn of<T>(value: &T, options: StructuralOptions) -> Self
+    where
+        T: StructuralHash<'ast> + ?Sized,
+    {
+        let mut structure = Structure {
+            tokens: Vec::new(),
+            options,
+            locals: Vec::new(),
+            in_function: false,
+        };
+
+        value.structure(&mut structure);
+        structure
+    }
+
+    #[inline]
+    fn kind(&mut self, kind: &'static str) {
+        self.tokens.push(Token::Kind(kind));
+    }
+
+    #[inline]
+    fn normalizing(&self) -> bool {
+        self.options.normalize_locals && self.in_function
+    }
+
+    /// Write the body of a function or a modifier, locals declared
+    /// within are forgotten afterwards.
+    fn function<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        let in_function = self.in_function;
+        let locals = self.locals.len();
+
+        self.in_function = true;
+        f(self);
+        self.in_function = in_function;
+        self.locals.truncate(locals);
+    }
+
+    /// Name of a newly declared local.
+    fn declare(&mut self, name: &IdentifierNode<'ast>) {
+        if self.normalizing() {
+            self.tokens.push(Token::Local(self.locals.len()));
+            self.locals.push(name.value);
+        } else {
+            name.structure(self);
+        }
+    }
+
+    /// Name that might refer to a local.
+    fn reference(&mut self, name: &'ast str) {
+        let local = match self.normalizing() {
+            true  => self.locals.iter().rposition(|local| *local == name),
+            false => None,
+        };
+
+        match local {
+            Some(index) => self.tokens.push(Token::Local(index)),
+            None        => self.tokens.push(Token::Str(name)),
+        }
+    }
+}
+
+impl<'ast> StructuralHash<'ast> for Program<'ast> {
+    fn structure(&self, s: &mut Structure<'ast>) {
+        self.body().structure(s)
+    }
+}
+
+impl<'ast, T> StructuralHash<'ast> for Node<'ast, T>
+where
+    T: StructuralHash<'ast> + 'ast,
+{
+    #[inline]
+    fn structure(&self, s: &mut Structure<'ast>) {
+        self.value.structure(s)
+    }
+}
+
+impl<'ast, T> StructuralHash<'ast> for List<'ast, T>
+where
+    T: StructuralHash<'ast> + Copy + 'ast,
+{
+    fn structure(&self, s: &mut Structure<'ast>) {
+        s.tokens.push(Token::Len(self.iter().count()));
+
+        for item in self.iter() {
+            item.structure(s);
+        }
+    }
+}
+
+impl<'ast, T> StructuralHash<'ast> for Option<T>
+where
+    T: StructuralHash<'ast>,
+{
+    fn structure(&self, s: &mut Structure<'ast>) {
+        match *self {
+            Some(ref value) => {
+                s.tokens.push(Token::Some);
+                value.structure(s);
+            },
+            None => s.tokens.push(Token::None),
+        }
+    }
+}
+
+impl<'ast> StructuralHash<'ast> for &'ast str {
+    #[inline]
+    fn structure(&self, s: &mut Structure<'ast>) {
+        s.tokens.push(Token::Str(self))
+    }
+}
+
+impl<'ast> StructuralHash<'ast> for Flag {
+    #[inline]
+    fn structure(&self, s: &mut Structure<'ast>) {
+        s.kind("Flag")
+    }
+}
+
+macro_rules! impl_as_str {
+    ($( $type:ident ),*) => ($(
+        impl<'ast> StructuralHash<'ast> for $type {
+            #[inline]
+            fn structure(&self, s: &mut Structure<'ast>) {
+                s.kind(self.as_str())
+            }
+        }
+    )*)
+}
+
+impl_as_str!(
+    StateVariableVisibility,
+    FunctionVisibility,
+    StateMutability,
+    StorageLocation,
+    PrefixOperator,
+    PostfixOperator,
+    BinaryOperator,
+    AssignmentOperator
+);
+
+/// Structs of the AST, written as their name followed by the fields.
+macro_rules! impl_fields {
+    ($( $type:ident { $( $field:ident ),* } )*) => ($(
+        impl<'ast> StructuralHash<'ast> for $type<'ast> {
+            fn structure(&self, s: &mut Structure<'ast>) {
+                s.kind(stringify!($type));
+                $( self.$field.structure(s); )*
+            }
+        }
+    )*)
+}
+
+impl_fields! {
+    PragmaDirective { version }
+    Import { symbol, alias }
+    ContractDefinition { name, inherits, body }
+    StateVariableDeclaration { type_name, visibility, constant, name, init }
+    UsingForDeclaration { id, type_name }
+    StructDefinition { name, body }
+    EventDefinition { anonymous, name, params }
+    IndexedParameter { type_name, indexed, name }
+    EnumDefinition { name, variants }
+    ModifierInvocation { id, arguments }
+    DocComment { tags }
+    Mapping { from, to }
+    VariableDeclaration { type_name, location, id }
+    IfStatement { test, consequent, alternate }
+    WhileStatement { test, body }
+    ForStatement { init, test, update, body }
+    Block { body }
+    InlineAssemblyStatement { string, block }
+    DoWhileStatement { body, test }
+    ReturnStatement { value }
+    PrefixExpression { operator, operand }
+    PostfixExpression { operand, operator }
+    BinaryExpression { left, operator, right }
+    AssignmentExpression { left, operator, right }
+    TupleExpression { expressions }
+    CallExpression { callee, arguments }
+    MemberAccessExpression { object, member }
+    IndexAccessExpression { array, index }
+    ConditionalExpression { test, consequent, alternate }
+    InlineAssemblyBlock { items }
+    AssemblyLabel { id }
+    FunctionalAssemblyExpression { id, arguments }
+}
+
+impl<'ast> StructuralHash<'ast> for SourceUnit<'ast> {
+    fn structure(&self, s: &mut Structure<'ast>) {
+        match *self {
+            SourceUnit::PragmaDirective(ref pragma)      => pragma.structure(s),
+            SourceUnit::ImportDirective(ref import)      => import.structure(s),
+            SourceUnit::ContractDefinition(ref contract) => contract.structure(s),
+            SourceUnit::Error                            => s.kind("Error"),
+        }
+    }
+}
+
+impl<'ast> StructuralHash<'ast> for ImportDirective<'ast> {
+    fn structure(&self, s: &mut Structure<'ast>) {
+        match *self {
+            ImportDirective::Global { ref source, ref alias } => {
+                s.kind("ImportGlobal");
+                source.structure(s);
+                alias.structure(s);
+            },
+            ImportDirective::From { ref symbol, ref alias, ref source } => {
+                s.kind("ImportFrom");
+                symbol.structure(s);
+                alias.structure(s);
+                source.structure(s);
+            },
+            ImportDirective::ManyFrom { ref imports, ref source } => {
+                s.kind("ImportManyFrom");
+                imports.structure(s);
+                source.structure(s);
+            },
+        }
+    }
+}
+
+impl<'ast> StructuralHash<'ast> for ContractPart<'ast> {
+    fn structure(&self, s: &mut Structure<'ast>) {
+        match *self {
+            ContractPart::StateVariableDeclaration(ref var) => var.structure(s),
+            ContractPart::UsingForDeclaration(ref using)    => using.structure(s),
+            ContractPart::StructDefinition(ref def)         => def.structure(s),
+            ContractPart::ModifierDefinition(ref def)       => def.structure(s),
+            ContractPart::FunctionDefinition(ref def)       => def.structure(s),
+            ContractPart::EventDefinition(ref def)          => def.structure(s),
+            ContractPart::EnumDefinition(ref def)           => def.structure(s),
+            ContractPart::Error                             => s.kind("Error"),
+        }
+    }
+}
+
+impl<'ast> StructuralHash<'ast> for ModifierDefinition<'ast> {
+    fn structure(&self, s: &mut Structure<'ast>) {
+        s.kind("ModifierDefinition");
+        self.name.structure(s);
+        s.function(|s| {
+            self.params.structure(s);
+            self.block.structure(s);
+        });
+    }
+}
+
+impl<'ast> StructuralHash<'ast> for FunctionDefinition<'ast> {
+    fn structure(&self, s: &mut Structure<'ast>) {
+        s.kind("FunctionDefinition");
+        self.name.structure(s);
+        self.visibility.structure(s);
+        self.mutability.structure(s);
+        s.function(|s| {
+            self.params.structure(s);
+            self.modifiers.structure(s);
+            self.returns.structure(s);
+            self.block.structure(s);
+        });
+    }
+}
+
+impl<'ast> StructuralHash<'ast> for Parameter<'ast> {
+    fn structure(&self, s: &mut Structure<'ast>) {
+        s.kind("Parameter");
+        self.type_name.structure(s);
+
+        match self.name {
+            Some(ref name) => {
+                s.tokens.push(Token::Some);
+                s.declare(name);
+            },
+            None => s.tokens.push(Token::None),
+        }
+    }
+}
+
+impl<'ast> StructuralHash<'ast> for DocTag<'ast> {
+    fn structure(&self, s: &mut Structure<'ast>) {
+        match self.kind {
+            DocTagKind::Title                => s.kind("@title"),
+            DocTagKind::Author               => s.kind("@author"),
+            DocTagKind::Notice               => s.kind("@notice"),
+            DocTagKind::Dev                  => s.kind("@dev"),
+            DocTagKind::Return               => s.kind("@return"),
+            DocTagKind::Param(ref name)      => {
+                s.kind("@param");
+                name.structure(s);
+            },
+            DocTagKind::Inheritdoc(ref name) => {
+                s.kind("@inheritdoc");
+                name.structure(s);
+            },
+            DocTagKind::Custom(ref name)     => {
+                s.kind("@custom");
+                name.structure(s);
+            },
+            DocTagKind::Unknown(ref name)    => {
+                s.kind("@unknown");
+                name.structure(s);
+            },
+        }
+
+        self.text.structure(s);
+    }
+}
+
+impl<'ast> StructuralHash<'ast> for TypeName<'ast> {
+    fn structure(&self, s: &mut Structure<'ast>) {
+        match *self {
+            TypeName::ElementaryTypeName(ref elementary) => elementary.structure(s),
+            TypeName::UserDefinedTypeName(ref name)      => {
+                s.kind("UserDefinedTypeName");
+                name.structure(s);
+            },
+            TypeName::Mapping(ref mapping)               => mapping.structure(s),
+            TypeName::ArrayTypeName                      => s.kind("ArrayTypeName"),
+            TypeName::FunctionTypeName                   => s.kind("FunctionTypeName"),
+        }
+    }
+}
+
+impl<'ast> StructuralHash<'ast> for ElementaryTypeName {
+    fn structure(&self, s: &mut Structure<'ast>) {
+        let (kind, sizes) = match *self {
+            ElementaryTypeName::Address                => ("address", None),
+            ElementaryTypeName::Bool                   => ("bool", None),
+            ElementaryTypeName::String                 => ("string", None),
+            ElementaryTypeName::Bytes                  => ("bytes", None),
+            ElementaryTypeName::Int(size)              => ("int", Some((size, 0))),
+            ElementaryTypeName::Uint(size)             => ("uint", Some((size, 0))),
+            ElementaryTypeName::Byte(size)             => ("byte", Some((size, 0))),
+            ElementaryTypeName::Fixed(size, decimals)  => ("fixed", Some((size, decimals))),
+            ElementaryTypeName::Ufixed(size, decimals) => ("ufixed", Some((size, decimals))),
+        };
+
+        s.kind(kind);
+
+        if let Some((size, decimals)) = sizes {
+            s.tokens.push(Token::Size(size));
+            s.tokens.push(Token::Size(decimals));
+        }
+    }
+}
+
+impl<'ast> StructuralHash<'ast> for Statement<'ast> {
+    fn structure(&self, s: &mut Structure<'ast>) {
+        match *self {
+            Statement::Placeholder                             => s.kind("Placeholder"),
+            Statement::IfStatement(ref stmt)                   => stmt.structure(s),
+            Statement::WhileStatement(ref stmt)                => stmt.structure(s),
+            Statement::ForStatement(ref stmt)                  => stmt.structure(s),
+            Statement::BlockStatement(ref block)               => block.structure(s),
+            Statement::InlineAssemblyStatement(ref stmt)       => stmt.structure(s),
+            Statement::DoWhileStatement(ref stmt)              => stmt.structure(s),
+            Statement::ContinueStatement                       => s.kind("ContinueStatement"),
+            Statement::BreakStatement                          => s.kind("BreakStatement"),
+            Statement::ReturnStatement(ref stmt)               => stmt.structure(s),
+            Statement::ThrowStatement                          => s.kind("ThrowStatement"),
+            Statement::VariableDefinitionStatement(ref stmt)   => stmt.structure(s),
+            Statement::InferredDefinitionStatement(ref stmt)   => stmt.structure(s),
+            Statement::ExpressionStatement(ref expression)     => {
+                s.kind("ExpressionStatement");
+                expression.structure(s);
+            },
+            Statement::Error                                   => s.kind("Error"),
+        }
+    }
+}
+
+impl<'ast> StructuralHash<'ast> for SimpleStatement<'ast> {
+    fn structure(&self, s: &mut Structure<'ast>) {
+        match *self {
+            SimpleStatement::VariableDefinitionStatement(ref stmt) => stmt.structure(s),
+            SimpleStatement::InferredDefinitionStatement(ref stmt) => stmt.structure(s),
+            SimpleStatement::ExpressionStatement(ref expression)   => {
+                s.kind("ExpressionStatement");
+                expression.structure(s);
+            },
+        }
+    }
+}
+
+impl<'ast> StructuralHash<'ast> for VariableDefinitionStatement<'ast> {
+    fn structure(&self, s: &mut Structure<'ast>) {
+        let declaration = self.declaration.value;
+
+        s.kind("VariableDefinitionStatement");
+        declaration.type_name.structure(s);
+        declaration.location.structure(s);
+        s.declare(&declaration.id);
+        self.init.structure(s);
+    }
+}
+
+impl<'ast> StructuralHash<'ast> for InferredDefinitionStatement<'ast> {
+    fn structure(&self, s: &mut Structure<'ast>) {
+        s.kind("InferredDefinitionStatement");
+        s.tokens.push(Token::Len(self.ids.iter().count()));
+
+        for id in self.ids.iter() {
+            match *id {
+                Some(ref id) => {
+                    s.tokens.push(Token::Some);
+                    s.declare(id);
+                },
+                None => s.tokens.push(Token::None),
+            }
+        }
+
+        self.init.structure(s);
+    }
+}
+
+impl<'ast> StructuralHash<'ast> for Expression<'ast> {
+    fn structure(&self, s: &mut Structure<'ast>) {
+        match *self {
+            Expression::ThisExpression                     => s.kind("ThisExpression"),
+            Expression::IdentifierExpression(name)         => {
+                s.kind("IdentifierExpression");
+                s.reference(name);
+            },
+            Expression::PrimitiveExpression(ref primitive) => primitive.structure(s),
+            Expression::PrefixExpression(ref expr)         => expr.structure(s),
+            Expression::PostfixExpression(ref expr)        => expr.structure(s),
+            Expression::BinaryExpression(ref expr)         => expr.structure(s),
+            Expression::AssignmentExpression(ref expr)     => expr.structure(s),
+            Expression::TupleExpression(ref expr)          => expr.structure(s),
+            Expression::CallExpression(ref expr)           => expr.structure(s),
+            Expression::MemberAccessExpression(ref expr)   => expr.structure(s),
+            Expression::IndexAccessExpression(ref expr)    => expr.structure(s),
+            Expression::ConditionalExpression(ref expr)    => expr.structure(s),
+            Expression::ElementaryTypeExpression(ref elementary) => {
+                s.kind("ElementaryTypeExpression");
+                elementary.structure(s);
+            },
+        }
+    }
+}
+
+impl<'ast> StructuralHash<'ast> for Primitive<'ast> {
+    fn structure(&self, s: &mut Structure<'ast>) {
+        let (kind, value, unit) = match *self {
+            Primitive::Bool(true)                   => ("true", "", NumberUnit::None),
+            Primitive::Bool(false)                  => ("false", "", NumberUnit::None),
+            Primitive::HexNumber(value)             => ("HexNumber", value, NumberUnit::None),
+            Primitive::IntegerNumber(value, unit)   => ("IntegerNumber", value, unit),
+            Primitive::RationalNumber(value, unit)  => ("RationalNumber", value, unit),
+            Primitive::String(value)                => ("String", value, NumberUnit::None),
+        };
+
+        s.kind(kind);
+        s.tokens.push(Token::Str(value));
+        s.kind(unit.as_str().unwrap_or(""));
+    }
+}
+
+impl<'ast> StructuralHash<'ast> for AssemblyItem<'ast> {
+    fn structure(&self, s: &mut Structure<'ast>) {
+        match *self {
+            AssemblyItem::Identifier(name) => {
+                s.kind("AssemblyIdentifier");
+                s.reference(name);
+            },
+            AssemblyItem::FunctionalAssemblyExpression(ref expr) => expr.structure(s),
+            AssemblyItem::InlineAssemblyBlock(ref block)         => block.structure(s),
+            AssemblyItem::AssemblyLocalBinding(ref binding)      => binding.structure(s),
+            AssemblyItem::AssemblyAssignment(ref assignment)     => assignment.structure(s),
+            AssemblyItem::AssemblyLabel(ref label)               => label.structure(s),
+            AssemblyItem::NumberLiteral(ref primitive)           => primitive.structure(s),
+            AssemblyItem::StringLiteral                          => s.kind("StringLiteral"),
+            AssemblyItem::HexLiteral                             => s.kind("HexLiteral"),
+        }
+    }
+}
+
+impl<'ast> StructuralHash<'ast> for AssemblyLocalBinding<'ast> {
+    fn structure(&self, s: &mut Structure<'ast>) {
+        s.kind("AssemblyLocalBinding");
+        s.declare(&self.id);
+        self.init.structure(s);
+    }
+}
+
+impl<'ast> StructuralHash<'ast> for AssemblyAssignment<'ast> {
+    fn structure(&self, s: &mut Structure<'ast>) {
+        s.kind("AssemblyAssignment");
+        s.reference(self.id.value);
+        self.init.structure(s);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+
+    fn function<'ast>(builder: AstBuilder<'ast>, param: &str, local: &str) -> ContractPartNode<'ast> {
+        let b = builder;
+        let uint = || b.elementary(ElementaryTypeName::Uint(32));
+        let sum = b.binary(b.identifier_expression(param), BinaryOperator::Addition, b.identifier_expression("total"));
+
+        b.function(
+            Some("add"),
+            b.list(Some(b.parameter(uint(), Some(param)))),
+            Some(FunctionVisibility::Public),
+            None,
+            b.list(Some(b.parameter(uint(), None))),
+            Some(b.block(vec![
+                b.variable_definition(b.variable_declaration(uint(), None, local), Some(sum)),
+                b.return_statement(Some(b.identifier_expression(local))),
+            ])),
+        )
+    }
+
+    fn hash<'ast, T: StructuralHash<'ast>>(value: &T, options: StructuralOptions) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        value.structural_hash(options, &mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn ignores_locations() {
+        let arena = Arena::new();
+        let b = AstBuilder::new(&arena);
+
+        let a = function(b, "value", "sum");
+        let moved = function(b.at(100, 200), "value", "sum");
+        let renamed = function(b, "amount", "result");
+        let options = StructuralOptions::default();
+
+        assert_ne!(a, moved);
+        assert!(structurally_eq(&a, &moved, options));
+        assert_eq!(hash(&a, options), hash(&moved, options));
+        assert!(!structurally_eq(&a, &renamed, options));
+        assert_ne!(hash(&a, options), hash(&renamed, options));
+    }
+
+    #[test]
+    fn normalize_locals() {
+        let arena = Arena::new();
+        let b = AstBuilder::new(&arena);
+        let options = StructuralOptions {
+            normalize_locals: true,
+        };
+
+        let a = function(b, "value", "sum");
+        let renamed = function(b.at(1, 2), "amount", "result");
+        let swapped = function(b, "sum", "value");
+        let global = function(b, "value", "total");
+
+        assert!(structurally_eq(&a, &renamed, options));
+        assert_eq!(hash(&a, options), hash(&renamed, options));
+        assert!(structurally_eq(&a, &swapped, options));
+
+        // `total` refers to the local instead of a state variable
+        assert!(!structurally_eq(&a, &global, options));
+    }
+
+    #[test]
+    fn optional_fields() {
+        let arena = Arena::new();
+        let b = AstBuilder::new(&arena);
+        let x = b.identifier_expression("x");
+        let body = b.break_statement();
+
+        let test = b.for_statement(None, Some(x), None, body);
+        let update = b.for_statement(None, None, Some(x), body);
+
+        assert!(!structurally_eq(&test, &update, StructuralOptions::default()));
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use {*};
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -9,6 +11,19 @@ pub enum TypeName<'ast> {
     FunctionTypeName,
 }
 
+impl<'ast> TypeName<'ast> {
+    /// Name of the variant.
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            TypeName::ElementaryTypeName(_)  => "ElementaryTypeName",
+            TypeName::UserDefinedTypeName(_) => "UserDefinedTypeName",
+            TypeName::Mapping(_)             => "Mapping",
+            TypeName::ArrayTypeName          => "ArrayTypeName",
+            TypeName::FunctionTypeName       => "FunctionTypeName",
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub struct VariableDeclaration<'ast> {
     pub type_name: TypeNameNode<'ast>,
@@ -22,6 +37,17 @@ pub enum StorageLocation {
     Storage,
 }
 
+impl StorageLocation {
+    /// Keyword of this storage location in Solidity source.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            StorageLocation::Memory  => "memory",
+            StorageLocation::Storage => "storage",
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum ElementaryTypeName {
     Address,
@@ -35,6 +61,24 @@ pub enum ElementaryTypeName {
     Ufixed(u8, u8),
 }
 
+/// Canonical name of the type, sizes are always written out,
+/// so both `uint` and `uint256` are displayed as `uint256`.
+impl fmt::Display for ElementaryTypeName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ElementaryTypeName::Address                => f.write_str("address"),
+            ElementaryTypeName::Bool                   => f.write_str("bool"),
+            ElementaryTypeName::String                 => f.write_str("string"),
+            ElementaryTypeName::Bytes                  => f.write_str("bytes"),
+            ElementaryTypeName::Int(size)              => write!(f, "int{}", size as u32 * 8),
+            ElementaryTypeName::Uint(size)             => write!(f, "uint{}", size as u32 * 8),
+            ElementaryTypeName::Byte(size)             => write!(f, "bytes{}", size),
+            ElementaryTypeName::Fixed(size, decimals)  => write!(f, "fixed{}x{}", size as u32 * 8, decimals),
+            ElementaryTypeName::Ufixed(size, decimals) => write!(f, "ufixed{}x{}", size as u32 * 8, decimals),
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub struct Mapping<'ast> {
     pub from: ElementaryTypeNameNode<'ast>,
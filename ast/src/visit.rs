@@ -0,0 +1,780 @@
+//! Read-only traversal of the AST.
+//!
+//! Implement `Visit` and override the methods for the nodes you are
+//! interested in. Every method defaults to the matching `walk_*` function,
+//! which visits all children of the node in source order. An overriding
+//! method should call the `walk_*` function itself if it wants the children
+//! of the node to be visited as well.
+//!
+//! Methods for the variants of the enum nodes (`SourceUnit`, `ContractPart`,
+//! `Statement`, `Expression`, `TypeName` and `AssemblyItem`), as well as for
+//! any other struct nodes, get the `start` and `end` of the `Node` holding
+//! them passed along.
+
+use {*};
+
+pub trait Visit<'ast> {
+    fn visit_program(&mut self, program: &Program<'ast>) {
+        walk_program(self, program)
+    }
+
+    fn visit_source_unit(&mut self, unit: &SourceUnitNode<'ast>) {
+        walk_source_unit(self, unit)
+    }
+
+    fn visit_pragma_directive(&mut self, _pragma: &PragmaDirective<'ast>, _start: u32, _end: u32) {}
+
+    fn visit_import_directive(&mut self, import: &ImportDirective<'ast>, _start: u32, _end: u32) {
+        walk_import_directive(self, import)
+    }
+
+    fn visit_import(&mut self, import: &Import<'ast>, _start: u32, _end: u32) {
+        walk_import(self, import)
+    }
+
+    fn visit_contract_definition(&mut self, contract: &ContractDefinition<'ast>, _start: u32, _end: u32) {
+        walk_contract_definition(self, contract)
+    }
+
+    fn visit_contract_part(&mut self, part: &ContractPartNode<'ast>) {
+        walk_contract_part(self, part)
+    }
+
+    fn visit_state_variable_declaration(&mut self, var: &StateVariableDeclaration<'ast>, _start: u32, _end: u32) {
+        walk_state_variable_declaration(self, var)
+    }
+
+    fn visit_using_for_declaration(&mut self, using: &UsingForDeclaration<'ast>, _start: u32, _end: u32) {
+        walk_using_for_declaration(self, using)
+    }
+
+    fn visit_struct_definition(&mut self, def: &StructDefinition<'ast>, _start: u32, _end: u32) {
+        walk_struct_definition(self, def)
+    }
+
+    fn visit_modifier_definition(&mut self, def: &ModifierDefinition<'ast>, _start: u32, _end: u32) {
+        walk_modifier_definition(self, def)
+    }
+
+    fn visit_function_definition(&mut self, def: &FunctionDefinition<'ast>, _start: u32, _end: u32) {
+        walk_function_definition(self, def)
+    }
+
+    fn visit_event_definition(&mut self, def: &EventDefinition<'ast>, _start: u32, _end: u32) {
+        walk_event_definition(self, def)
+    }
+
+    fn visit_enum_definition(&mut self, def: &EnumDefinition<'ast>, _start: u32, _end: u32) {
+        walk_enum_definition(self, def)
+    }
+
+    fn visit_indexed_parameter(&mut self, param: &IndexedParameter<'ast>, _start: u32, _end: u32) {
+        walk_indexed_parameter(self, param)
+    }
+
+    fn visit_parameter(&mut self, param: &Parameter<'ast>, _start: u32, _end: u32) {
+        walk_parameter(self, param)
+    }
+
+    fn visit_modifier_invocation(&mut self, modifier: &ModifierInvocation<'ast>, _start: u32, _end: u32) {
+        walk_modifier_invocation(self, modifier)
+    }
+
+    fn visit_doc_comment(&mut self, doc: &DocComment<'ast>, _start: u32, _end: u32) {
+        walk_doc_comment(self, doc)
+    }
+
+    fn visit_doc_tag(&mut self, _tag: &DocTag<'ast>, _start: u32, _end: u32) {}
+
+    fn visit_type_name(&mut self, type_name: &TypeNameNode<'ast>) {
+        walk_type_name(self, type_name)
+    }
+
+    fn visit_mapping(&mut self, mapping: &Mapping<'ast>, _start: u32, _end: u32) {
+        walk_mapping(self, mapping)
+    }
+
+    fn visit_variable_declaration(&mut self, declaration: &VariableDeclaration<'ast>, _start: u32, _end: u32) {
+        walk_variable_declaration(self, declaration)
+    }
+
+    fn visit_statement(&mut self, statement: &StatementNode<'ast>) {
+        walk_statement(self, statement)
+    }
+
+    /// Initialization of a `for` loop, the variants of `SimpleStatement`
+    /// are visited with the same methods as their `Statement` counterparts.
+    fn visit_simple_statement(&mut self, statement: &SimpleStatementNode<'ast>) {
+        walk_simple_statement(self, statement)
+    }
+
+    /// Both the bodies of functions and modifiers, and block statements.
+    fn visit_block(&mut self, block: &Block<'ast>, _start: u32, _end: u32) {
+        walk_block(self, block)
+    }
+
+    fn visit_if_statement(&mut self, stmt: &IfStatement<'ast>, _start: u32, _end: u32) {
+        walk_if_statement(self, stmt)
+    }
+
+    fn visit_while_statement(&mut self, stmt: &WhileStatement<'ast>, _start: u32, _end: u32) {
+        walk_while_statement(self, stmt)
+    }
+
+    fn visit_for_statement(&mut self, stmt: &ForStatement<'ast>, _start: u32, _end: u32) {
+        walk_for_statement(self, stmt)
+    }
+
+    fn visit_inline_assembly_statement(&mut self, stmt: &InlineAssemblyStatement<'ast>, _start: u32, _end: u32) {
+        walk_inline_assembly_statement(self, stmt)
+    }
+
+    fn visit_do_while_statement(&mut self, stmt: &DoWhileStatement<'ast>, _start: u32, _end: u32) {
+        walk_do_while_statement(self, stmt)
+    }
+
+    fn visit_return_statement(&mut self, stmt: &ReturnStatement<'ast>, _start: u32, _end: u32) {
+        walk_return_statement(self, stmt)
+    }
+
+    fn visit_variable_definition_statement(&mut self, stmt: &VariableDefinitionStatement<'ast>, _start: u32, _end: u32) {
+        walk_variable_definition_statement(self, stmt)
+    }
+
+    fn visit_inferred_definition_statement(&mut self, stmt: &InferredDefinitionStatement<'ast>, _start: u32, _end: u32) {
+        walk_inferred_definition_statement(self, stmt)
+    }
+
+    fn visit_expression(&mut self, expression: &ExpressionNode<'ast>) {
+        walk_expression(self, expression)
+    }
+
+    fn visit_prefix_expression(&mut self, expr: &PrefixExpression<'ast>, _start: u32, _end: u32) {
+        walk_prefix_expression(self, expr)
+    }
+
+    fn visit_postfix_expression(&mut self, expr: &PostfixExpression<'ast>, _start: u32, _end: u32) {
+        walk_postfix_expression(self, expr)
+    }
+
+    fn visit_binary_expression(&mut self, expr: &BinaryExpression<'ast>, _start: u32, _end: u32) {
+        walk_binary_expression(self, expr)
+    }
+
+    fn visit_assignment_expression(&mut self, expr: &AssignmentExpression<'ast>, _start: u32, _end: u32) {
+        walk_assignment_expression(self, expr)
+    }
+
+    fn visit_tuple_expression(&mut self, expr: &TupleExpression<'ast>, _start: u32, _end: u32) {
+        walk_tuple_expression(self, expr)
+    }
+
+    fn visit_call_expression(&mut self, expr: &CallExpression<'ast>, _start: u32, _end: u32) {
+        walk_call_expression(self, expr)
+    }
+
+    fn visit_member_access_expression(&mut self, expr: &MemberAccessExpression<'ast>, _start: u32, _end: u32) {
+        walk_member_access_expression(self, expr)
+    }
+
+    fn visit_index_access_expression(&mut self, expr: &IndexAccessExpression<'ast>, _start: u32, _end: u32) {
+        walk_index_access_expression(self, expr)
+    }
+
+    fn visit_conditional_expression(&mut self, expr: &ConditionalExpression<'ast>, _start: u32, _end: u32) {
+        walk_conditional_expression(self, expr)
+    }
+
+    fn visit_inline_assembly_block(&mut self, block: &InlineAssemblyBlock<'ast>, _start: u32, _end: u32) {
+        walk_inline_assembly_block(self, block)
+    }
+
+    fn visit_assembly_item(&mut self, item: &AssemblyItemNode<'ast>) {
+        walk_assembly_item(self, item)
+    }
+
+    fn visit_functional_assembly_expression(&mut self, expr: &FunctionalAssemblyExpression<'ast>, _start: u32, _end: u32) {
+        walk_functional_assembly_expression(self, expr)
+    }
+
+    fn visit_assembly_local_binding(&mut self, binding: &AssemblyLocalBinding<'ast>, _start: u32, _end: u32) {
+        walk_assembly_local_binding(self, binding)
+    }
+
+    fn visit_assembly_assignment(&mut self, assignment: &AssemblyAssignment<'ast>, _start: u32, _end: u32) {
+        walk_assembly_assignment(self, assignment)
+    }
+
+    fn visit_assembly_label(&mut self, label: &AssemblyLabel<'ast>, _start: u32, _end: u32) {
+        walk_assembly_label(self, label)
+    }
+
+    fn visit_identifier(&mut self, _identifier: &IdentifierNode<'ast>) {}
+
+    fn visit_string_literal(&mut self, _string: &StringLiteralNode<'ast>) {}
+
+    fn visit_flag(&mut self, _flag: &FlagNode<'ast>) {}
+
+    fn visit_elementary_type_name(&mut self, _type_name: &ElementaryTypeNameNode<'ast>) {}
+
+    fn visit_state_variable_visibility(&mut self, _visibility: &Node<'ast, StateVariableVisibility>) {}
+
+    fn visit_function_visibility(&mut self, _visibility: &Node<'ast, FunctionVisibility>) {}
+
+    fn visit_state_mutability(&mut self, _mutability: &Node<'ast, StateMutability>) {}
+
+    fn visit_storage_location(&mut self, _location: &Node<'ast, StorageLocation>) {}
+
+    fn visit_prefix_operator(&mut self, _operator: &Node<'ast, PrefixOperator>) {}
+
+    fn visit_postfix_operator(&mut self, _operator: &Node<'ast, PostfixOperator>) {}
+
+    fn visit_binary_operator(&mut self, _operator: &Node<'ast, BinaryOperator>) {}
+
+    fn visit_assignment_operator(&mut self, _operator: &Node<'ast, AssignmentOperator>) {}
+}
+
+pub fn walk_program<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, program: &Program<'ast>) {
+    for unit in program.body().iter() {
+        visitor.visit_source_unit(unit);
+    }
+}
+
+pub fn walk_source_unit<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, unit: &SourceUnitNode<'ast>) {
+    let (start, end) = (unit.start, unit.end);
+
+    match unit.value {
+        SourceUnit::PragmaDirective(ref pragma)      => visitor.visit_pragma_directive(pragma, start, end),
+        SourceUnit::ImportDirective(ref import)      => visitor.visit_import_directive(import, start, end),
+        SourceUnit::ContractDefinition(ref contract) => visitor.visit_contract_definition(contract, start, end),
+        SourceUnit::Error                            => {},
+    }
+}
+
+pub fn walk_import_directive<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, import: &ImportDirective<'ast>) {
+    match *import {
+        ImportDirective::Global { ref source, ref alias } => {
+            visitor.visit_string_literal(source);
+            walk_opt_identifier(visitor, alias);
+        },
+        ImportDirective::From { ref symbol, ref alias, ref source } => {
+            walk_opt_identifier(visitor, symbol);
+            walk_opt_identifier(visitor, alias);
+            visitor.visit_string_literal(source);
+        },
+        ImportDirective::ManyFrom { imports, ref source } => {
+            for import in imports.iter() {
+                visitor.visit_import(&import.value, import.start, import.end);
+            }
+
+            visitor.visit_string_literal(source);
+        },
+    }
+}
+
+pub fn walk_import<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, import: &Import<'ast>) {
+    visitor.visit_identifier(&import.symbol);
+    walk_opt_identifier(visitor, &import.alias);
+}
+
+pub fn walk_contract_definition<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, contract: &ContractDefinition<'ast>) {
+    walk_doc(visitor, &contract.doc);
+    visitor.visit_identifier(&contract.name);
+    walk_identifiers(visitor, contract.inherits);
+
+    for part in contract.body.iter() {
+        visitor.visit_contract_part(part);
+    }
+}
+
+pub fn walk_contract_part<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, part: &ContractPartNode<'ast>) {
+    let (start, end) = (part.start, part.end);
+
+    match part.value {
+        ContractPart::StateVariableDeclaration(ref var) => visitor.visit_state_variable_declaration(var, start, end),
+        ContractPart::UsingForDeclaration(ref using)    => visitor.visit_using_for_declaration(using, start, end),
+        ContractPart::StructDefinition(ref def)         => visitor.visit_struct_definition(def, start, end),
+        ContractPart::ModifierDefinition(ref def)       => visitor.visit_modifier_definition(def, start, end),
+        ContractPart::FunctionDefinition(ref def)       => visitor.visit_function_definition(def, start, end),
+        ContractPart::EventDefinition(ref def)          => visitor.visit_event_definition(def, start, end),
+        ContractPart::EnumDefinition(ref def)           => visitor.visit_enum_definition(def, start, end),
+        ContractPart::Error                             => {},
+    }
+}
+
+pub fn walk_state_variable_declaration<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, var: &StateVariableDeclaration<'ast>) {
+    walk_doc(visitor, &var.doc);
+    visitor.visit_type_name(&var.type_name);
+
+    if let Some(ref visibility) = var.visibility {
+        visitor.visit_state_variable_visibility(visibility);
+    }
+
+    if let Some(ref constant) = var.constant {
+        visitor.visit_flag(constant);
+    }
+
+    visitor.visit_identifier(&var.name);
+    walk_opt_expression(visitor, &var.init);
+}
+
+pub fn walk_using_for_declaration<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, using: &UsingForDeclaration<'ast>) {
+    visitor.visit_identifier(&using.id);
+
+    if let Some(ref type_name) = using.type_name {
+        visitor.visit_type_name(type_name);
+    }
+}
+
+pub fn walk_struct_definition<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, def: &StructDefinition<'ast>) {
+    visitor.visit_identifier(&def.name);
+
+    for declaration in def.body.iter() {
+        visitor.visit_variable_declaration(&declaration.value, declaration.start, declaration.end);
+    }
+}
+
+pub fn walk_modifier_definition<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, def: &ModifierDefinition<'ast>) {
+    walk_doc(visitor, &def.doc);
+    visitor.visit_identifier(&def.name);
+    walk_parameters(visitor, def.params);
+    visitor.visit_block(&def.block.value, def.block.start, def.block.end);
+}
+
+pub fn walk_function_definition<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, def: &FunctionDefinition<'ast>) {
+    walk_doc(visitor, &def.doc);
+    walk_opt_identifier(visitor, &def.name);
+    walk_parameters(visitor, def.params);
+
+    if let Some(ref visibility) = def.visibility {
+        visitor.visit_function_visibility(visibility);
+    }
+
+    if let Some(ref mutability) = def.mutability {
+        visitor.visit_state_mutability(mutability);
+    }
+
+    for modifier in def.modifiers.iter() {
+        visitor.visit_modifier_invocation(&modifier.value, modifier.start, modifier.end);
+    }
+
+    walk_parameters(visitor, def.returns);
+
+    if let Some(ref block) = def.block {
+        visitor.visit_block(&block.value, block.start, block.end);
+    }
+}
+
+pub fn walk_event_definition<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, def: &EventDefinition<'ast>) {
+    walk_doc(visitor, &def.doc);
+
+    if let Some(ref anonymous) = def.anonymous {
+        visitor.visit_flag(anonymous);
+    }
+
+    visitor.visit_identifier(&def.name);
+
+    for param in def.params.iter() {
+        visitor.visit_indexed_parameter(&param.value, param.start, param.end);
+    }
+}
+
+pub fn walk_enum_definition<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, def: &EnumDefinition<'ast>) {
+    visitor.visit_identifier(&def.name);
+    walk_identifiers(visitor, def.variants);
+}
+
+pub fn walk_indexed_parameter<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, param: &IndexedParameter<'ast>) {
+    visitor.visit_type_name(&param.type_name);
+
+    if let Some(ref indexed) = param.indexed {
+        visitor.visit_flag(indexed);
+    }
+
+    walk_opt_identifier(visitor, &param.name);
+}
+
+pub fn walk_parameter<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, param: &Parameter<'ast>) {
+    visitor.visit_type_name(&param.type_name);
+    walk_opt_identifier(visitor, &param.name);
+}
+
+pub fn walk_modifier_invocation<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, modifier: &ModifierInvocation<'ast>) {
+    visitor.visit_identifier(&modifier.id);
+    walk_expressions(visitor, modifier.arguments);
+}
+
+pub fn walk_doc_comment<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, doc: &DocComment<'ast>) {
+    for tag in doc.tags.iter() {
+        visitor.visit_doc_tag(&tag.value, tag.start, tag.end);
+    }
+}
+
+pub fn walk_type_name<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, type_name: &TypeNameNode<'ast>) {
+    if let TypeName::Mapping(ref mapping) = type_name.value {
+        visitor.visit_mapping(mapping, type_name.start, type_name.end);
+    }
+}
+
+pub fn walk_mapping<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, mapping: &Mapping<'ast>) {
+    visitor.visit_elementary_type_name(&mapping.from);
+    visitor.visit_type_name(&mapping.to);
+}
+
+pub fn walk_variable_declaration<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, declaration: &VariableDeclaration<'ast>) {
+    visitor.visit_type_name(&declaration.type_name);
+
+    if let Some(ref location) = declaration.location {
+        visitor.visit_storage_location(location);
+    }
+
+    visitor.visit_identifier(&declaration.id);
+}
+
+pub fn walk_statement<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, statement: &StatementNode<'ast>) {
+    let (start, end) = (statement.start, statement.end);
+
+    match statement.value {
+        Statement::IfStatement(ref stmt)                 => visitor.visit_if_statement(stmt, start, end),
+        Statement::WhileStatement(ref stmt)              => visitor.visit_while_statement(stmt, start, end),
+        Statement::ForStatement(ref stmt)                => visitor.visit_for_statement(stmt, start, end),
+        Statement::BlockStatement(ref block)             => visitor.visit_block(block, start, end),
+        Statement::InlineAssemblyStatement(ref stmt)     => visitor.visit_inline_assembly_statement(stmt, start, end),
+        Statement::DoWhileStatement(ref stmt)            => visitor.visit_do_while_statement(stmt, start, end),
+        Statement::ReturnStatement(ref stmt)             => visitor.visit_return_statement(stmt, start, end),
+        Statement::VariableDefinitionStatement(ref stmt) => visitor.visit_variable_definition_statement(stmt, start, end),
+        Statement::InferredDefinitionStatement(ref stmt) => visitor.visit_inferred_definition_statement(stmt, start, end),
+        Statement::ExpressionStatement(ref expression)   => visitor.visit_expression(expression),
+
+        Statement::Placeholder       |
+        Statement::ContinueStatement |
+        Statement::BreakStatement    |
+        Statement::ThrowStatement    |
+        Statement::Error             => {},
+    }
+}
+
+pub fn walk_simple_statement<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, statement: &SimpleStatementNode<'ast>) {
+    let (start, end) = (statement.start, statement.end);
+
+    match statement.value {
+        SimpleStatement::VariableDefinitionStatement(ref stmt) => visitor.visit_variable_definition_statement(stmt, start, end),
+        SimpleStatement::InferredDefinitionStatement(ref stmt) => visitor.visit_inferred_definition_statement(stmt, start, end),
+        SimpleStatement::ExpressionStatement(ref expression)   => visitor.visit_expression(expression),
+    }
+}
+
+pub fn walk_block<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, block: &Block<'ast>) {
+    for statement in block.body.iter() {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_if_statement<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, stmt: &IfStatement<'ast>) {
+    visitor.visit_expression(&stmt.test);
+    visitor.visit_statement(&stmt.consequent);
+
+    if let Some(ref alternate) = stmt.alternate {
+        visitor.visit_statement(alternate);
+    }
+}
+
+pub fn walk_while_statement<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, stmt: &WhileStatement<'ast>) {
+    visitor.visit_expression(&stmt.test);
+    visitor.visit_statement(&stmt.body);
+}
+
+pub fn walk_for_statement<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, stmt: &ForStatement<'ast>) {
+    if let Some(ref init) = stmt.init {
+        visitor.visit_simple_statement(init);
+    }
+
+    walk_opt_expression(visitor, &stmt.test);
+    walk_opt_expression(visitor, &stmt.update);
+    visitor.visit_statement(&stmt.body);
+}
+
+pub fn walk_inline_assembly_statement<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, stmt: &InlineAssemblyStatement<'ast>) {
+    if let Some(ref string) = stmt.string {
+        visitor.visit_string_literal(string);
+    }
+
+    visitor.visit_inline_assembly_block(&stmt.block.value, stmt.block.start, stmt.block.end);
+}
+
+pub fn walk_do_while_statement<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, stmt: &DoWhileStatement<'ast>) {
+    visitor.visit_statement(&stmt.body);
+    visitor.visit_expression(&stmt.test);
+}
+
+pub fn walk_return_statement<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, stmt: &ReturnStatement<'ast>) {
+    walk_opt_expression(visitor, &stmt.value);
+}
+
+pub fn walk_variable_definition_statement<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, stmt: &VariableDefinitionStatement<'ast>) {
+    let declaration = &stmt.declaration;
+
+    visitor.visit_variable_declaration(&declaration.value, declaration.start, declaration.end);
+    walk_opt_expression(visitor, &stmt.init);
+}
+
+pub fn walk_inferred_definition_statement<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, stmt: &InferredDefinitionStatement<'ast>) {
+    for id in stmt.ids.iter() {
+        walk_opt_identifier(visitor, id);
+    }
+
+    visitor.visit_expression(&stmt.init);
+}
+
+pub fn walk_expression<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, expression: &ExpressionNode<'ast>) {
+    let (start, end) = (expression.start, expression.end);
+
+    match expression.value {
+        Expression::PrefixExpression(ref expr)       => visitor.visit_prefix_expression(expr, start, end),
+        Expression::PostfixExpression(ref expr)      => visitor.visit_postfix_expression(expr, start, end),
+        Expression::BinaryExpression(ref expr)       => visitor.visit_binary_expression(expr, start, end),
+        Expression::AssignmentExpression(ref expr)   => visitor.visit_assignment_expression(expr, start, end),
+        Expression::TupleExpression(ref expr)        => visitor.visit_tuple_expression(expr, start, end),
+        Expression::CallExpression(ref expr)         => visitor.visit_call_expression(expr, start, end),
+        Expression::MemberAccessExpression(ref expr) => visitor.visit_member_access_expression(expr, start, end),
+        Expression::IndexAccessExpression(ref expr)  => visitor.visit_index_access_expression(expr, start, end),
+        Expression::ConditionalExpression(ref expr)  => visitor.visit_conditional_expression(expr, start, end),
+
+        Expression::ThisExpression              |
+        Expression::IdentifierExpression(_)     |
+        Expression::PrimitiveExpression(_)      |
+        Expression::ElementaryTypeExpression(_) => {},
+    }
+}
+
+pub fn walk_prefix_expression<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, expr: &PrefixExpression<'ast>) {
+    visitor.visit_prefix_operator(&expr.operator);
+    visitor.visit_expression(&expr.operand);
+}
+
+pub fn walk_postfix_expression<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, expr: &PostfixExpression<'ast>) {
+    visitor.visit_expression(&expr.operand);
+    visitor.visit_postfix_operator(&expr.operator);
+}
+
+pub fn walk_binary_expression<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, expr: &BinaryExpression<'ast>) {
+    visitor.visit_expression(&expr.left);
+    visitor.visit_binary_operator(&expr.operator);
+    visitor.visit_expression(&expr.right);
+}
+
+pub fn walk_assignment_expression<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, expr: &AssignmentExpression<'ast>) {
+    visitor.visit_expression(&expr.left);
+    visitor.visit_assignment_operator(&expr.operator);
+    visitor.visit_expression(&expr.right);
+}
+
+pub fn walk_tuple_expression<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, expr: &TupleExpression<'ast>) {
+    walk_expressions(visitor, expr.expressions);
+}
+
+pub fn walk_call_expression<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, expr: &CallExpression<'ast>) {
+    visitor.visit_expression(&expr.callee);
+    walk_expressions(visitor, expr.arguments);
+}
+
+pub fn walk_member_access_expression<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, expr: &MemberAccessExpression<'ast>) {
+    visitor.visit_expression(&expr.object);
+    visitor.visit_identifier(&expr.member);
+}
+
+pub fn walk_index_access_expression<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, expr: &IndexAccessExpression<'ast>) {
+    visitor.visit_expression(&expr.array);
+    walk_opt_expression(visitor, &expr.index);
+}
+
+pub fn walk_conditional_expression<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, expr: &ConditionalExpression<'ast>) {
+    visitor.visit_expression(&expr.test);
+    visitor.visit_expression(&expr.consequent);
+    visitor.visit_expression(&expr.alternate);
+}
+
+pub fn walk_inline_assembly_block<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, block: &InlineAssemblyBlock<'ast>) {
+    for item in block.items.iter() {
+        visitor.visit_assembly_item(item);
+    }
+}
+
+pub fn walk_assembly_item<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, item: &AssemblyItemNode<'ast>) {
+    let (start, end) = (item.start, item.end);
+
+    match item.value {
+        AssemblyItem::FunctionalAssemblyExpression(ref expr) => visitor.visit_functional_assembly_expression(expr, start, end),
+        AssemblyItem::InlineAssemblyBlock(ref block)         => visitor.visit_inline_assembly_block(block, start, end),
+        AssemblyItem::AssemblyLocalBinding(ref binding)      => visitor.visit_assembly_local_binding(binding, start, end),
+        AssemblyItem::AssemblyAssignment(ref assignment)     => visitor.visit_assembly_assignment(assignment, start, end),
+        AssemblyItem::AssemblyLabel(ref label)               => visitor.visit_assembly_label(label, start, end),
+
+        AssemblyItem::Identifier(_)    |
+        AssemblyItem::NumberLiteral(_) |
+        AssemblyItem::StringLiteral    |
+        AssemblyItem::HexLiteral       => {},
+    }
+}
+
+pub fn walk_functional_assembly_expression<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, expr: &FunctionalAssemblyExpression<'ast>) {
+    visitor.visit_identifier(&expr.id);
+
+    for item in expr.arguments.iter() {
+        visitor.visit_assembly_item(item);
+    }
+}
+
+pub fn walk_assembly_local_binding<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, binding: &AssemblyLocalBinding<'ast>) {
+    visitor.visit_identifier(&binding.id);
+    visitor.visit_functional_assembly_expression(&binding.init.value, binding.init.start, binding.init.end);
+}
+
+pub fn walk_assembly_assignment<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, assignment: &AssemblyAssignment<'ast>) {
+    visitor.visit_identifier(&assignment.id);
+    visitor.visit_functional_assembly_expression(&assignment.init.value, assignment.init.start, assignment.init.end);
+}
+
+pub fn walk_assembly_label<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, label: &AssemblyLabel<'ast>) {
+    visitor.visit_identifier(&label.id);
+}
+
+fn walk_doc<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, doc: &Option<DocCommentNode<'ast>>) {
+    if let Some(ref doc) = *doc {
+        visitor.visit_doc_comment(&doc.value, doc.start, doc.end);
+    }
+}
+
+fn walk_opt_identifier<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, id: &Option<IdentifierNode<'ast>>) {
+    if let Some(ref id) = *id {
+        visitor.visit_identifier(id);
+    }
+}
+
+fn walk_identifiers<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, list: IdentifierList<'ast>) {
+    for id in list.iter() {
+        visitor.visit_identifier(id);
+    }
+}
+
+fn walk_parameters<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, params: ParameterList<'ast>) {
+    for param in params.iter() {
+        visitor.visit_parameter(&param.value, param.start, param.end);
+    }
+}
+
+fn walk_opt_expression<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, expression: &Option<ExpressionNode<'ast>>) {
+    if let Some(ref expression) = *expression {
+        visitor.visit_expression(expression);
+    }
+}
+
+fn walk_expressions<'ast, V: ?Sized + Visit<'ast>>(visitor: &mut V, expressions: ExpressionList<'ast>) {
+    for expression in expressions.iter() {
+        visitor.visit_expression(expression);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use fixture::program;
+
+    #[derive(Default)]
+    struct Recorder {
+        visited: Vec<(&'static str, u32, u32)>,
+        identifiers: Vec<&'static str>,
+    }
+
+    impl Visit<'static> for Recorder {
+        fn visit_contract_definition(&mut self, contract: &ContractDefinition<'static>, start: u32, end: u32) {
+            self.visited.push(("ContractDefinition", start, end));
+            walk_contract_definition(self, contract);
+        }
+
+        fn visit_function_definition(&mut self, def: &FunctionDefinition<'static>, start: u32, end: u32) {
+            self.visited.push(("FunctionDefinition", start, end));
+            walk_function_definition(self, def);
+        }
+
+        fn visit_type_name(&mut self, type_name: &TypeNameNode<'static>) {
+            self.visited.push(("TypeName", type_name.start, type_name.end));
+        }
+
+        fn visit_block(&mut self, block: &Block<'static>, start: u32, end: u32) {
+            self.visited.push(("Block", start, end));
+            walk_block(self, block);
+        }
+
+        fn visit_if_statement(&mut self, stmt: &IfStatement<'static>, start: u32, end: u32) {
+            self.visited.push(("IfStatement", start, end));
+            walk_if_statement(self, stmt);
+        }
+
+        fn visit_expression(&mut self, expression: &ExpressionNode<'static>) {
+            self.visited.push(("Expression", expression.start, expression.end));
+            walk_expression(self, expression);
+        }
+
+        fn visit_binary_operator(&mut self, operator: &Node<'static, BinaryOperator>) {
+            self.visited.push(("BinaryOperator", operator.start, operator.end));
+        }
+
+        fn visit_assignment_operator(&mut self, operator: &Node<'static, AssignmentOperator>) {
+            self.visited.push(("AssignmentOperator", operator.start, operator.end));
+        }
+
+        fn visit_identifier(&mut self, identifier: &IdentifierNode<'static>) {
+            self.identifiers.push(identifier.value);
+        }
+    }
+
+    #[test]
+    fn visit_in_source_order() {
+        let program = program();
+        let mut recorder = Recorder::default();
+
+        recorder.visit_program(&program);
+
+        assert_eq!(recorder.visited, vec![
+            ("ContractDefinition", 0, 60),
+            ("FunctionDefinition", 15, 58),
+            ("TypeName", 28, 32),
+            ("Block", 36, 58),
+            ("IfStatement", 38, 56),
+            ("Expression", 42, 47),
+            ("Expression", 42, 43),
+            ("BinaryOperator", 44, 45),
+            ("Expression", 46, 47),
+            ("Expression", 49, 55),
+            ("Expression", 49, 50),
+            ("AssignmentOperator", 51, 53),
+            ("Expression", 54, 55),
+        ]);
+        assert_eq!(recorder.identifiers, vec!["Foo", "bar", "a"]);
+    }
+
+    #[test]
+    fn skip_children() {
+        struct Expressions(usize);
+
+        impl<'ast> Visit<'ast> for Expressions {
+            fn visit_expression(&mut self, expression: &ExpressionNode<'ast>) {
+                self.0 += 1;
+                walk_expression(self, expression);
+            }
+
+            fn visit_if_statement(&mut self, stmt: &IfStatement<'ast>, _start: u32, _end: u32) {
+                // Only the test expression, without the branches
+                self.visit_expression(&stmt.test);
+            }
+        }
+
+        let program = program();
+        let mut expressions = Expressions(0);
+
+        expressions.visit_program(&program);
+
+        assert_eq!(expressions.0, 3);
+    }
+}
@@ -0,0 +1,652 @@
+//! Traversal of the AST that allows replacing nodes in place.
+//!
+//! Every method of `VisitMut` gets the `Node` as it is stored in the AST.
+//! Calling `Node::replace` (or `Node::set`) on it re-points that node to
+//! a different value, typically one allocated with `Program::node`, and
+//! the rest of the AST will see the change. As with `Visit`, the default
+//! methods call the matching `walk_*` function to traverse the children.
+//!
+//! ```ignore
+//! struct ThrowToRevert<'ast> {
+//!     program: &'ast Program<'ast>,
+//! }
+//!
+//! impl<'ast> VisitMut<'ast> for ThrowToRevert<'ast> {
+//!     fn visit_statement(&mut self, statement: &StatementNode<'ast>) {
+//!         if statement.value == Statement::ThrowStatement {
+//!             let (start, end) = (statement.start, statement.end);
+//!             let revert = self.program.alloc_str("revert");
+//!             let callee = self.program.node(start, start + 6, Expression::IdentifierExpression(revert));
+//!
+//!             let call = self.program.node(start, end - 1, CallExpression {
+//!                 callee,
+//!                 arguments: NodeList::empty(),
+//!             });
+//!
+//!             return statement.replace(self.program.node(start, end, Statement::ExpressionStatement(call)));
+//!         }
+//!
+//!         walk_statement(self, statement)
+//!     }
+//! }
+//! ```
+
+use {*};
+
+pub trait VisitMut<'ast> {
+    fn visit_program(&mut self, program: &Program<'ast>) {
+        walk_program(self, program)
+    }
+
+    fn visit_source_unit(&mut self, unit: &SourceUnitNode<'ast>) {
+        walk_source_unit(self, unit)
+    }
+
+    fn visit_import(&mut self, import: &Node<'ast, Import<'ast>>) {
+        walk_import(self, import)
+    }
+
+    fn visit_contract_part(&mut self, part: &ContractPartNode<'ast>) {
+        walk_contract_part(self, part)
+    }
+
+    fn visit_indexed_parameter(&mut self, param: &Node<'ast, IndexedParameter<'ast>>) {
+        walk_indexed_parameter(self, param)
+    }
+
+    fn visit_parameter(&mut self, param: &Node<'ast, Parameter<'ast>>) {
+        walk_parameter(self, param)
+    }
+
+    fn visit_modifier_invocation(&mut self, modifier: &Node<'ast, ModifierInvocation<'ast>>) {
+        walk_modifier_invocation(self, modifier)
+    }
+
+    fn visit_doc_comment(&mut self, doc: &DocCommentNode<'ast>) {
+        walk_doc_comment(self, doc)
+    }
+
+    fn visit_doc_tag(&mut self, _tag: &DocTagNode<'ast>) {}
+
+    fn visit_type_name(&mut self, type_name: &TypeNameNode<'ast>) {
+        walk_type_name(self, type_name)
+    }
+
+    fn visit_variable_declaration(&mut self, declaration: &VariableDeclarationNode<'ast>) {
+        walk_variable_declaration(self, declaration)
+    }
+
+    /// Bodies of functions and modifiers, block statements are
+    /// visited with `visit_statement`.
+    fn visit_block(&mut self, block: &BlockNode<'ast>) {
+        walk_block(self, block)
+    }
+
+    fn visit_statement(&mut self, statement: &StatementNode<'ast>) {
+        walk_statement(self, statement)
+    }
+
+    fn visit_simple_statement(&mut self, statement: &SimpleStatementNode<'ast>) {
+        walk_simple_statement(self, statement)
+    }
+
+    fn visit_expression(&mut self, expression: &ExpressionNode<'ast>) {
+        walk_expression(self, expression)
+    }
+
+    fn visit_inline_assembly_block(&mut self, block: &InlineAssemblyBlockNode<'ast>) {
+        walk_inline_assembly_block(self, block)
+    }
+
+    fn visit_assembly_item(&mut self, item: &AssemblyItemNode<'ast>) {
+        walk_assembly_item(self, item)
+    }
+
+    fn visit_functional_assembly_expression(&mut self, expr: &FunctionalAssemblyExpressionNode<'ast>) {
+        walk_functional_assembly_expression(self, expr)
+    }
+
+    fn visit_identifier(&mut self, _identifier: &IdentifierNode<'ast>) {}
+
+    fn visit_string_literal(&mut self, _string: &StringLiteralNode<'ast>) {}
+
+    fn visit_flag(&mut self, _flag: &FlagNode<'ast>) {}
+
+    fn visit_elementary_type_name(&mut self, _type_name: &ElementaryTypeNameNode<'ast>) {}
+
+    fn visit_state_variable_visibility(&mut self, _visibility: &Node<'ast, StateVariableVisibility>) {}
+
+    fn visit_function_visibility(&mut self, _visibility: &Node<'ast, FunctionVisibility>) {}
+
+    fn visit_state_mutability(&mut self, _mutability: &Node<'ast, StateMutability>) {}
+
+    fn visit_storage_location(&mut self, _location: &Node<'ast, StorageLocation>) {}
+
+    fn visit_prefix_operator(&mut self, _operator: &Node<'ast, PrefixOperator>) {}
+
+    fn visit_postfix_operator(&mut self, _operator: &Node<'ast, PostfixOperator>) {}
+
+    fn visit_binary_operator(&mut self, _operator: &Node<'ast, BinaryOperator>) {}
+
+    fn visit_assignment_operator(&mut self, _operator: &Node<'ast, AssignmentOperator>) {}
+}
+
+pub fn walk_program<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, program: &Program<'ast>) {
+    for unit in program.body().iter() {
+        visitor.visit_source_unit(unit);
+    }
+}
+
+pub fn walk_source_unit<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, unit: &SourceUnitNode<'ast>) {
+    match unit.value {
+        SourceUnit::ImportDirective(ImportDirective::Global { ref source, ref alias }) => {
+            visitor.visit_string_literal(source);
+            walk_opt_identifier(visitor, alias);
+        },
+        SourceUnit::ImportDirective(ImportDirective::From { ref symbol, ref alias, ref source }) => {
+            walk_opt_identifier(visitor, symbol);
+            walk_opt_identifier(visitor, alias);
+            visitor.visit_string_literal(source);
+        },
+        SourceUnit::ImportDirective(ImportDirective::ManyFrom { imports, ref source }) => {
+            for import in imports.iter() {
+                visitor.visit_import(import);
+            }
+
+            visitor.visit_string_literal(source);
+        },
+        SourceUnit::ContractDefinition(ref contract) => {
+            walk_opt_doc(visitor, &contract.doc);
+            visitor.visit_identifier(&contract.name);
+            walk_identifiers(visitor, contract.inherits);
+
+            for part in contract.body.iter() {
+                visitor.visit_contract_part(part);
+            }
+        },
+        SourceUnit::PragmaDirective(_) | SourceUnit::Error => {},
+    }
+}
+
+pub fn walk_import<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, import: &Node<'ast, Import<'ast>>) {
+    visitor.visit_identifier(&import.value.symbol);
+    walk_opt_identifier(visitor, &import.value.alias);
+}
+
+pub fn walk_contract_part<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, part: &ContractPartNode<'ast>) {
+    match part.value {
+        ContractPart::StateVariableDeclaration(ref var) => {
+            walk_opt_doc(visitor, &var.doc);
+            visitor.visit_type_name(&var.type_name);
+
+            if let Some(ref visibility) = var.visibility {
+                visitor.visit_state_variable_visibility(visibility);
+            }
+
+            if let Some(ref constant) = var.constant {
+                visitor.visit_flag(constant);
+            }
+
+            visitor.visit_identifier(&var.name);
+            walk_opt_expression(visitor, &var.init);
+        },
+        ContractPart::UsingForDeclaration(ref using) => {
+            visitor.visit_identifier(&using.id);
+
+            if let Some(ref type_name) = using.type_name {
+                visitor.visit_type_name(type_name);
+            }
+        },
+        ContractPart::StructDefinition(ref def) => {
+            visitor.visit_identifier(&def.name);
+
+            for declaration in def.body.iter() {
+                visitor.visit_variable_declaration(declaration);
+            }
+        },
+        ContractPart::ModifierDefinition(ref def) => {
+            walk_opt_doc(visitor, &def.doc);
+            visitor.visit_identifier(&def.name);
+            walk_parameters(visitor, def.params);
+            visitor.visit_block(&def.block);
+        },
+        ContractPart::FunctionDefinition(ref def) => {
+            walk_opt_doc(visitor, &def.doc);
+            walk_opt_identifier(visitor, &def.name);
+            walk_parameters(visitor, def.params);
+
+            if let Some(ref visibility) = def.visibility {
+                visitor.visit_function_visibility(visibility);
+            }
+
+            if let Some(ref mutability) = def.mutability {
+                visitor.visit_state_mutability(mutability);
+            }
+
+            for modifier in def.modifiers.iter() {
+                visitor.visit_modifier_invocation(modifier);
+            }
+
+            walk_parameters(visitor, def.returns);
+
+            if let Some(ref block) = def.block {
+                visitor.visit_block(block);
+            }
+        },
+        ContractPart::EventDefinition(ref def) => {
+            walk_opt_doc(visitor, &def.doc);
+
+            if let Some(ref anonymous) = def.anonymous {
+                visitor.visit_flag(anonymous);
+            }
+
+            visitor.visit_identifier(&def.name);
+
+            for param in def.params.iter() {
+                visitor.visit_indexed_parameter(param);
+            }
+        },
+        ContractPart::EnumDefinition(ref def) => {
+            visitor.visit_identifier(&def.name);
+            walk_identifiers(visitor, def.variants);
+        },
+        ContractPart::Error => {},
+    }
+}
+
+pub fn walk_indexed_parameter<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, param: &Node<'ast, IndexedParameter<'ast>>) {
+    visitor.visit_type_name(&param.value.type_name);
+
+    if let Some(ref indexed) = param.value.indexed {
+        visitor.visit_flag(indexed);
+    }
+
+    walk_opt_identifier(visitor, &param.value.name);
+}
+
+pub fn walk_parameter<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, param: &Node<'ast, Parameter<'ast>>) {
+    visitor.visit_type_name(&param.value.type_name);
+    walk_opt_identifier(visitor, &param.value.name);
+}
+
+pub fn walk_modifier_invocation<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, modifier: &Node<'ast, ModifierInvocation<'ast>>) {
+    visitor.visit_identifier(&modifier.value.id);
+    walk_expressions(visitor, modifier.value.arguments);
+}
+
+pub fn walk_doc_comment<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, doc: &DocCommentNode<'ast>) {
+    for tag in doc.value.tags.iter() {
+        visitor.visit_doc_tag(tag);
+    }
+}
+
+pub fn walk_type_name<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, type_name: &TypeNameNode<'ast>) {
+    if let TypeName::Mapping(ref mapping) = type_name.value {
+        visitor.visit_elementary_type_name(&mapping.from);
+        visitor.visit_type_name(&mapping.to);
+    }
+}
+
+pub fn walk_variable_declaration<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, declaration: &VariableDeclarationNode<'ast>) {
+    visitor.visit_type_name(&declaration.value.type_name);
+
+    if let Some(ref location) = declaration.value.location {
+        visitor.visit_storage_location(location);
+    }
+
+    visitor.visit_identifier(&declaration.value.id);
+}
+
+pub fn walk_block<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, block: &BlockNode<'ast>) {
+    walk_statements(visitor, block.value.body);
+}
+
+pub fn walk_statement<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, statement: &StatementNode<'ast>) {
+    match statement.value {
+        Statement::IfStatement(ref stmt) => {
+            visitor.visit_expression(&stmt.test);
+            visitor.visit_statement(&stmt.consequent);
+
+            if let Some(ref alternate) = stmt.alternate {
+                visitor.visit_statement(alternate);
+            }
+        },
+        Statement::WhileStatement(ref stmt) => {
+            visitor.visit_expression(&stmt.test);
+            visitor.visit_statement(&stmt.body);
+        },
+        Statement::ForStatement(ref stmt) => {
+            if let Some(ref init) = stmt.init {
+                visitor.visit_simple_statement(init);
+            }
+
+            walk_opt_expression(visitor, &stmt.test);
+            walk_opt_expression(visitor, &stmt.update);
+            visitor.visit_statement(&stmt.body);
+        },
+        Statement::BlockStatement(ref block) => walk_statements(visitor, block.body),
+        Statement::InlineAssemblyStatement(ref stmt) => {
+            if let Some(ref string) = stmt.string {
+                visitor.visit_string_literal(string);
+            }
+
+            visitor.visit_inline_assembly_block(&stmt.block);
+        },
+        Statement::DoWhileStatement(ref stmt) => {
+            visitor.visit_statement(&stmt.body);
+            visitor.visit_expression(&stmt.test);
+        },
+        Statement::ReturnStatement(ref stmt)             => walk_opt_expression(visitor, &stmt.value),
+        Statement::VariableDefinitionStatement(ref stmt) => walk_variable_definition(visitor, stmt),
+        Statement::InferredDefinitionStatement(ref stmt) => walk_inferred_definition(visitor, stmt),
+        Statement::ExpressionStatement(ref expression)   => visitor.visit_expression(expression),
+
+        Statement::Placeholder       |
+        Statement::ContinueStatement |
+        Statement::BreakStatement    |
+        Statement::ThrowStatement    |
+        Statement::Error             => {},
+    }
+}
+
+pub fn walk_simple_statement<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, statement: &SimpleStatementNode<'ast>) {
+    match statement.value {
+        SimpleStatement::VariableDefinitionStatement(ref stmt) => walk_variable_definition(visitor, stmt),
+        SimpleStatement::InferredDefinitionStatement(ref stmt) => walk_inferred_definition(visitor, stmt),
+        SimpleStatement::ExpressionStatement(ref expression)   => visitor.visit_expression(expression),
+    }
+}
+
+pub fn walk_expression<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, expression: &ExpressionNode<'ast>) {
+    match expression.value {
+        Expression::PrefixExpression(ref expr) => {
+            visitor.visit_prefix_operator(&expr.operator);
+            visitor.visit_expression(&expr.operand);
+        },
+        Expression::PostfixExpression(ref expr) => {
+            visitor.visit_expression(&expr.operand);
+            visitor.visit_postfix_operator(&expr.operator);
+        },
+        Expression::BinaryExpression(ref expr) => {
+            visitor.visit_expression(&expr.left);
+            visitor.visit_binary_operator(&expr.operator);
+            visitor.visit_expression(&expr.right);
+        },
+        Expression::AssignmentExpression(ref expr) => {
+            visitor.visit_expression(&expr.left);
+            visitor.visit_assignment_operator(&expr.operator);
+            visitor.visit_expression(&expr.right);
+        },
+        Expression::TupleExpression(ref expr) => walk_expressions(visitor, expr.expressions),
+        Expression::CallExpression(ref expr) => {
+            visitor.visit_expression(&expr.callee);
+            walk_expressions(visitor, expr.arguments);
+        },
+        Expression::MemberAccessExpression(ref expr) => {
+            visitor.visit_expression(&expr.object);
+            visitor.visit_identifier(&expr.member);
+        },
+        Expression::IndexAccessExpression(ref expr) => {
+            visitor.visit_expression(&expr.array);
+            walk_opt_expression(visitor, &expr.index);
+        },
+        Expression::ConditionalExpression(ref expr) => {
+            visitor.visit_expression(&expr.test);
+            visitor.visit_expression(&expr.consequent);
+            visitor.visit_expression(&expr.alternate);
+        },
+
+        Expression::ThisExpression              |
+        Expression::IdentifierExpression(_)     |
+        Expression::PrimitiveExpression(_)      |
+        Expression::ElementaryTypeExpression(_) => {},
+    }
+}
+
+pub fn walk_inline_assembly_block<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, block: &InlineAssemblyBlockNode<'ast>) {
+    walk_assembly_items(visitor, block.value.items);
+}
+
+pub fn walk_assembly_item<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, item: &AssemblyItemNode<'ast>) {
+    match item.value {
+        AssemblyItem::FunctionalAssemblyExpression(ref expr) => {
+            visitor.visit_identifier(&expr.id);
+            walk_assembly_items(visitor, expr.arguments);
+        },
+        AssemblyItem::InlineAssemblyBlock(ref block) => walk_assembly_items(visitor, block.items),
+        AssemblyItem::AssemblyLocalBinding(ref binding) => {
+            visitor.visit_identifier(&binding.id);
+            visitor.visit_functional_assembly_expression(&binding.init);
+        },
+        AssemblyItem::AssemblyAssignment(ref assignment) => {
+            visitor.visit_identifier(&assignment.id);
+            visitor.visit_functional_assembly_expression(&assignment.init);
+        },
+        AssemblyItem::AssemblyLabel(ref label) => visitor.visit_identifier(&label.id),
+
+        AssemblyItem::Identifier(_)    |
+        AssemblyItem::NumberLiteral(_) |
+        AssemblyItem::StringLiteral    |
+        AssemblyItem::HexLiteral       => {},
+    }
+}
+
+pub fn walk_functional_assembly_expression<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, expr: &FunctionalAssemblyExpressionNode<'ast>) {
+    visitor.visit_identifier(&expr.value.id);
+    walk_assembly_items(visitor, expr.value.arguments);
+}
+
+fn walk_variable_definition<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, stmt: &VariableDefinitionStatement<'ast>) {
+    visitor.visit_variable_declaration(&stmt.declaration);
+    walk_opt_expression(visitor, &stmt.init);
+}
+
+fn walk_inferred_definition<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, stmt: &InferredDefinitionStatement<'ast>) {
+    for id in stmt.ids.iter() {
+        walk_opt_identifier(visitor, id);
+    }
+
+    visitor.visit_expression(&stmt.init);
+}
+
+fn walk_statements<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, statements: StatementList<'ast>) {
+    for statement in statements.iter() {
+        visitor.visit_statement(statement);
+    }
+}
+
+fn walk_assembly_items<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, items: AssemblyItemList<'ast>) {
+    for item in items.iter() {
+        visitor.visit_assembly_item(item);
+    }
+}
+
+fn walk_opt_doc<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, doc: &Option<DocCommentNode<'ast>>) {
+    if let Some(ref doc) = *doc {
+        visitor.visit_doc_comment(doc);
+    }
+}
+
+fn walk_opt_identifier<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, id: &Option<IdentifierNode<'ast>>) {
+    if let Some(ref id) = *id {
+        visitor.visit_identifier(id);
+    }
+}
+
+fn walk_identifiers<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, list: IdentifierList<'ast>) {
+    for id in list.iter() {
+        visitor.visit_identifier(id);
+    }
+}
+
+fn walk_parameters<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, params: ParameterList<'ast>) {
+    for param in params.iter() {
+        visitor.visit_parameter(param);
+    }
+}
+
+fn walk_opt_expression<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, expression: &Option<ExpressionNode<'ast>>) {
+    if let Some(ref expression) = *expression {
+        visitor.visit_expression(expression);
+    }
+}
+
+fn walk_expressions<'ast, V: ?Sized + VisitMut<'ast>>(visitor: &mut V, expressions: ExpressionList<'ast>) {
+    for expression in expressions.iter() {
+        visitor.visit_expression(expression);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// contract Foo { uint constant X = 2; function bar() { if (X > 1) throw; } }
+    fn program<'ast>() -> Program<'ast> {
+        Program::build(|b| {
+            let constant = b.at(15, 35).node(StateVariableDeclaration {
+                doc: None,
+                type_name: b.at(15, 19).elementary(ElementaryTypeName::Uint(32)),
+                visibility: None,
+                constant: Some(b.at(20, 28).flag()),
+                name: b.at(29, 30).identifier("X"),
+                init: Some(b.at(33, 34).number("2", NumberUnit::None)),
+            });
+
+            let test = b.at(57, 62).node(BinaryExpression {
+                left: b.at(57, 58).identifier_expression("X"),
+                operator: b.at(59, 60).node(BinaryOperator::Greater),
+                right: b.at(61, 62).number("1", NumberUnit::None),
+            });
+
+            let statement = b.at(53, 70).if_statement(test, b.at(64, 70).throw_statement(), None);
+
+            let function = b.at(36, 72).node(FunctionDefinition {
+                doc: None,
+                name: Some(b.at(45, 48).identifier("bar")),
+                params: NodeList::empty(),
+                visibility: None,
+                mutability: None,
+                modifiers: NodeList::empty(),
+                returns: NodeList::empty(),
+                block: Some(b.at(51, 72).block(Some(statement))),
+            });
+
+            b.list(Some(b.at(0, 74).node(ContractDefinition {
+                doc: None,
+                name: b.at(9, 12).identifier("Foo"),
+                inherits: NodeList::empty(),
+                body: b.list(vec![constant, function]),
+            })))
+        })
+    }
+
+    fn if_statement<'ast>(program: &Program<'ast>) -> IfStatement<'ast> {
+        let contract = match program.body().only_element().unwrap().value {
+            SourceUnit::ContractDefinition(contract) => contract,
+            _ => panic!("Expected a contract"),
+        };
+        let block = match contract.body.iter().nth(1).unwrap().value {
+            ContractPart::FunctionDefinition(def) => def.block.unwrap(),
+            _ => panic!("Expected a function"),
+        };
+
+        match block.value.body.only_element().unwrap().value {
+            Statement::IfStatement(stmt) => stmt,
+            _ => panic!("Expected an if statement"),
+        }
+    }
+
+    struct ThrowToRevert<'ast> {
+        program: &'ast Program<'ast>,
+    }
+
+    impl<'ast> VisitMut<'ast> for ThrowToRevert<'ast> {
+        fn visit_statement(&mut self, statement: &StatementNode<'ast>) {
+            if statement.value == Statement::ThrowStatement {
+                let (start, end) = (statement.start, statement.end);
+                let callee = self.program.node(start, start + 6, Expression::IdentifierExpression(self.program.alloc_str("revert")));
+
+                let call = self.program.node(start, end - 1, CallExpression {
+                    callee,
+                    arguments: self.program.list(None),
+                });
+
+                return statement.replace(self.program.node(start, end, Statement::ExpressionStatement(call)));
+            }
+
+            walk_statement(self, statement)
+        }
+    }
+
+    #[test]
+    fn replace_statement() {
+        let program = program();
+
+        ThrowToRevert { program: &program }.visit_program(&program);
+
+        let consequent = if_statement(&program).consequent;
+        let call = match consequent.value {
+            Statement::ExpressionStatement(expression) => expression,
+            _ => panic!("Expected an expression statement, got {:?}", consequent),
+        };
+
+        assert_eq!((consequent.start, consequent.end), (64, 70));
+        assert_eq!((call.start, call.end), (64, 69));
+
+        match call.value {
+            Expression::CallExpression(call) => {
+                assert_eq!(call.callee.value, Expression::IdentifierExpression("revert"));
+                assert_eq!(call.arguments.iter().count(), 0);
+            },
+            _ => panic!("Expected a call expression, got {:?}", call),
+        }
+    }
+
+    #[test]
+    fn inline_constants() {
+        #[derive(Default)]
+        struct Constants<'ast> {
+            values: Vec<(&'ast str, ExpressionNode<'ast>)>,
+        }
+
+        impl<'ast> VisitMut<'ast> for Constants<'ast> {
+            fn visit_contract_part(&mut self, part: &ContractPartNode<'ast>) {
+                if let ContractPart::StateVariableDeclaration(ref var) = part.value {
+                    if let (Some(_), Some(init)) = (var.constant, var.init) {
+                        self.values.push((var.name.value, init));
+                    }
+                }
+
+                walk_contract_part(self, part)
+            }
+
+            fn visit_expression(&mut self, expression: &ExpressionNode<'ast>) {
+                if let Expression::IdentifierExpression(id) = expression.value {
+                    if let Some(&(_, value)) = self.values.iter().find(|&&(name, _)| name == id) {
+                        return expression.replace(value);
+                    }
+                }
+
+                walk_expression(self, expression)
+            }
+        }
+
+        let program = program();
+
+        Constants::default().visit_program(&program);
+
+        let test = if_statement(&program).test;
+
+        match test.value {
+            Expression::BinaryExpression(binary) => {
+                assert_eq!(binary.left.value, Expression::PrimitiveExpression(Primitive::IntegerNumber("2", NumberUnit::None)));
+                assert_eq!((binary.left.start, binary.left.end), (33, 34));
+            },
+            _ => panic!("Expected a binary expression, got {:?}", test),
+        }
+
+        // The original statement is untouched
+        assert_eq!(if_statement(&program).consequent.value, Statement::ThrowStatement);
+    }
+}
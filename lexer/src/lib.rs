@@ -2,6 +2,8 @@ extern crate logos;
 
 mod token;
 
+use std::ops::Range;
+
 pub use self::token::Token;
 pub use logos::{Logos, lookup};
 pub type Lexer<S> = logos::Lexer<Token, S>;
@@ -43,6 +45,77 @@ pub fn read_pragma<'source, S: logos::Source<'source>>(lex: &mut Lexer<S>) -> S:
     }
 }
 
+/// Check the source of a string literal token, quotes and the optional
+/// `unicode` prefix included, and return the range of the first invalid
+/// part of it (relative to the start of the literal), if any.
+///
+/// Allowed escape sequences are `\\`, `\'`, `\"`, `\n`, `\r`, `\t`,
+/// `\xNN`, `\uNNNN` and a backslash followed by a line break (line
+/// continuation). Just like in solc, plain string literals can only
+/// contain ASCII characters, use `unicode"..."` for anything else.
+///
+/// Returns `None` for valid literals, as well as for any slice that
+/// isn't a string literal to begin with.
+pub fn string_literal_error(literal: &[u8]) -> Option<Range<usize>> {
+    let unicode = literal.starts_with(b"unicode");
+    let offset  = if unicode { 7 } else { 0 };
+
+    let quote = match literal.get(offset) {
+        Some(&quote) if quote == b'"' || quote == b'\'' => quote,
+        _ => return None,
+    };
+
+    let end = literal.len() - 1;
+
+    if end == offset || literal[end] != quote {
+        return None;
+    }
+
+    let mut index = offset + 1;
+
+    while index < end {
+        let byte = literal[index];
+
+        if byte == b'\\' {
+            let (len, hex) = match literal[index + 1] {
+                b'\r' if literal.get(index + 2) == Some(&b'\n') => (3, false),
+
+                b'\\' | b'\'' | b'"' | b'n' | b'r' | b't' | b'\r' | b'\n' => (2, false),
+
+                b'x' => (4, true),
+                b'u' => (6, true),
+                _    => return Some(index..char_end(literal, index + 2)),
+            };
+
+            if index + len > end {
+                return Some(index..end);
+            }
+
+            if hex && !literal[index + 2..index + len].iter().all(u8::is_ascii_hexdigit) {
+                return Some(index..char_end(literal, index + len));
+            }
+
+            index += len;
+        } else if byte >= 0x80 && !unicode {
+            return Some(index..char_end(literal, index + 1));
+        } else {
+            index += 1;
+        }
+    }
+
+    None
+}
+
+/// Move `index` forward past any UTF-8 continuation bytes, so that a
+/// range ending there doesn't split a multibyte character.
+fn char_end(literal: &[u8], mut index: usize) -> usize {
+    while index < literal.len() && literal[index] & 0xC0 == 0x80 {
+        index += 1;
+    }
+
+    index
+}
+
 
 #[cfg(test)]
 mod test {
@@ -159,6 +232,40 @@ mod test {
         );
     }
 
+    #[test]
+    fn number_separators() {
+        assert_lex(
+            "
+                1_000 0xdead_BEEF 1_000.000_1 1_0e1_0 .5_5 1001e-2 2e10000000000
+            ",
+            &[
+                (LiteralInteger, "1_000"),
+                (LiteralHex, "0xdead_BEEF"),
+                (LiteralRational, "1_000.000_1"),
+                (LiteralInteger, "1_0e1_0"),
+                (LiteralRational, ".5_5"),
+                (LiteralRational, "1001e-2"),
+                (LiteralInteger, "2e10000000000"),
+            ][..]
+        );
+    }
+
+    #[test]
+    fn invalid_number_separators() {
+        assert_lex(
+            "
+                1__0 0x_1 1_.5 1_e5 1.5_e5
+            ",
+            &[
+                (UnexpectedToken, "1__0"),
+                (UnexpectedToken, "0x_1"),
+                (UnexpectedToken, "1_.5"),
+                (UnexpectedToken, "1_e5"),
+                (UnexpectedToken, "1.5_e5"),
+            ][..]
+        );
+    }
+
     #[test]
     fn strings() {
         assert_lex(r#"
@@ -171,6 +278,67 @@ mod test {
         ])
     }
 
+    #[test]
+    fn string_escapes() {
+        assert_lex(
+            "
+                '\\\\ \\' \\\" \\n \\r \\t' \"\\x00\\xfF\\u20AC\" \"foo\\\nbar\" \"foo\\\r\nbar\"
+            ",
+            &[
+                (LiteralString, "'\\\\ \\' \\\" \\n \\r \\t'"),
+                (LiteralString, "\"\\x00\\xfF\\u20AC\""),
+                (LiteralString, "\"foo\\\nbar\""),
+                (LiteralString, "\"foo\\\r\nbar\""),
+            ][..]
+        );
+    }
+
+    #[test]
+    fn unicode_strings() {
+        assert_lex(
+            "
+                unicode\"Hello 😃\" unicode'€' unicode
+            ",
+            &[
+                (LiteralString, "unicode\"Hello 😃\""),
+                (LiteralString, "unicode'€'"),
+                (Identifier, "unicode"),
+            ][..]
+        );
+    }
+
+    #[test]
+    fn invalid_strings() {
+        assert_lex(
+            "
+                \"\\q\" '\\x1' \"\\u12G4\" \"€\" foo
+            ",
+            &[
+                (UnexpectedToken, "\"\\q\""),
+                (UnexpectedToken, "'\\x1'"),
+                (UnexpectedToken, "\"\\u12G4\""),
+                (UnexpectedToken, "\"€\""),
+                (Identifier, "foo"),
+            ][..]
+        );
+    }
+
+    #[test]
+    fn string_literal_error_spans() {
+        assert_eq!(string_literal_error(b"\"foo\""), None);
+        assert_eq!(string_literal_error(b"foo"), None);
+        assert_eq!(string_literal_error(b"\"fo\\qo\""), Some(3..5));
+        assert_eq!(string_literal_error(b"'\\x1'"), Some(1..4));
+        assert_eq!(string_literal_error(b"'\\x1z'"), Some(1..5));
+        assert_eq!(string_literal_error(b"\"\\u12G4\""), Some(1..7));
+        assert_eq!(string_literal_error("\"a€\"".as_bytes()), Some(2..5));
+        assert_eq!(string_literal_error("\"\\é\"".as_bytes()), Some(1..4));
+        assert_eq!(string_literal_error("\"\\x1é\"".as_bytes()), Some(1..6));
+        assert_eq!(string_literal_error("\"\\u12€4\"".as_bytes()), Some(1..8));
+        assert_eq!(string_literal_error("unicode\"\\u1€\"".as_bytes()), Some(8..14));
+        assert_eq!(string_literal_error("unicode\"a€\"".as_bytes()), None);
+    }
+
     #[test]
     fn keywords() {
         assert_lex(
@@ -244,11 +412,12 @@ mod test {
     fn units() {
         assert_lex(
             "
-                wei szabo finney ether
+                wei gwei szabo finney ether
                 seconds minutes hours days weeks years
             ",
              &[
                 (UnitWei, "wei"),
+                (UnitGwei, "gwei"),
                 (UnitSzabo, "szabo"),
                 (UnitFinney, "finney"),
                 (UnitEther, "ether"),
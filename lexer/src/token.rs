@@ -263,18 +263,23 @@ pub enum Token {
     #[token = "false"]
     LiteralFalse,
 
-    #[regex = "0[xX][0-9a-fA-F]+"]
+    #[regex = "0[xX][0-9a-fA-F_]+"]
+    #[callback = "validate_hex"]
     LiteralHex,
 
-    #[regex = "[0-9]+"]
+    #[regex = "[0-9][0-9_]*"]
+    #[callback = "validate_integer"]
     LiteralInteger,
 
-    #[regex = "[0-9]*\\.[0-9]+([eE][+-]?[0-9]+)?|[0-9]+[eE][+-]?[0-9]+"]
+    #[regex = "([0-9][0-9_]*)?\\.[0-9][0-9_]*([eE][+-]?[0-9][0-9_]*)?|[0-9][0-9_]*[eE][+-]?[0-9][0-9_]*"]
     #[callback = "rational_to_integer"]
     LiteralRational,
 
-    #[regex = "\"([^\"\\\\]|\\\\.)*\""]
-    #[regex = "'([^'\\\\]|\\\\.)*'"]
+    #[regex = "\"([^\"\\\\]|\\\\.|\\\\\n)*\""]
+    #[regex = "'([^'\\\\]|\\\\.|\\\\\n)*'"]
+    #[regex = "unicode\"([^\"\\\\]|\\\\.|\\\\\n)*\""]
+    #[regex = "unicode'([^'\\\\]|\\\\.|\\\\\n)*'"]
+    #[callback = "validate_string"]
     LiteralString,
 
     #[token = "ether"]
@@ -286,6 +291,9 @@ pub enum Token {
     #[token = "szabo"]
     UnitSzabo,
 
+    #[token = "gwei"]
+    UnitGwei,
+
     #[token = "wei"]
     UnitWei,
 
@@ -426,6 +434,139 @@ pub enum Token {
     UnexpectedEndOfProgram,
 }
 
+impl Token {
+    /// Human readable description of the token, used in error messages.
+    pub fn describe(&self) -> &'static str {
+        match *self {
+            Token::EndOfProgram           => "end of file",
+            Token::Semicolon              => "`;`",
+            Token::Colon                  => "`:`",
+            Token::Comma                  => "`,`",
+            Token::Accessor               => "`.`",
+            Token::ParenOpen              => "`(`",
+            Token::ParenClose             => "`)`",
+            Token::BraceOpen              => "`{`",
+            Token::BraceClose             => "`}`",
+            Token::BracketOpen            => "`[`",
+            Token::BracketClose           => "`]`",
+            Token::Arrow                  => "`=>`",
+            Token::Identifier             => "identifier",
+            Token::IdentifierBuiltin      => "built-in identifier",
+            Token::DeclarationContract    => "`contract`",
+            Token::DeclarationLibrary     => "`library`",
+            Token::DeclarationInterface   => "`interface`",
+            Token::DeclarationEnum        => "`enum`",
+            Token::DeclarationStruct      => "`struct`",
+            Token::DeclarationModifier    => "`modifier`",
+            Token::DeclarationEvent       => "`event`",
+            Token::DeclarationFunction    => "`function`",
+            Token::DeclarationVar         => "`var`",
+            Token::KeywordAnonymous       => "`anonymous`",
+            Token::KeywordAs              => "`as`",
+            Token::KeywordAssembly        => "`assembly`",
+            Token::KeywordBreak           => "`break`",
+            Token::KeywordConstant        => "`constant`",
+            Token::KeywordContinue        => "`continue`",
+            Token::KeywordDo              => "`do`",
+            Token::KeywordDelete          => "`delete`",
+            Token::KeywordElse            => "`else`",
+            Token::KeywordExternal        => "`external`",
+            Token::KeywordFor             => "`for`",
+            Token::KeywordHex             => "`hex`",
+            Token::KeywordIf              => "`if`",
+            Token::KeywordIndexed         => "`indexed`",
+            Token::KeywordInternal        => "`internal`",
+            Token::KeywordImport          => "`import`",
+            Token::KeywordIs              => "`is`",
+            Token::KeywordMapping         => "`mapping`",
+            Token::KeywordMemory          => "`memory`",
+            Token::KeywordNew             => "`new`",
+            Token::KeywordPayable         => "`payable`",
+            Token::KeywordPublic          => "`public`",
+            Token::KeywordPragma          => "`pragma`",
+            Token::KeywordPrivate         => "`private`",
+            Token::KeywordPure            => "`pure`",
+            Token::KeywordReturn          => "`return`",
+            Token::KeywordReturns         => "`returns`",
+            Token::KeywordStorage         => "`storage`",
+            Token::KeywordSuper           => "`super`",
+            Token::KeywordThis            => "`this`",
+            Token::KeywordThrow           => "`throw`",
+            Token::KeywordUsing           => "`using`",
+            Token::KeywordView            => "`view`",
+            Token::KeywordWhile           => "`while`",
+            Token::ReservedWord           => "reserved word",
+            Token::TypeBool               => "`bool`",
+            Token::TypeAddress            => "`address`",
+            Token::TypeString             => "`string`",
+            Token::TypeByte               => "fixed-size bytes type",
+            Token::TypeBytes              => "`bytes`",
+            Token::TypeInt                => "`int`",
+            Token::TypeUint               => "`uint`",
+            Token::TypeIntN               => "integer type",
+            Token::TypeUintN              => "unsigned integer type",
+            Token::TypeFixed              => "fixed point type",
+            Token::TypeUfixed             => "unsigned fixed point type",
+            Token::LiteralTrue            => "`true`",
+            Token::LiteralFalse           => "`false`",
+            Token::LiteralHex             => "hex number",
+            Token::LiteralInteger         => "integer number",
+            Token::LiteralRational        => "rational number",
+            Token::LiteralString          => "string literal",
+            Token::UnitEther              => "`ether`",
+            Token::UnitFinney             => "`finney`",
+            Token::UnitSzabo              => "`szabo`",
+            Token::UnitGwei               => "`gwei`",
+            Token::UnitWei                => "`wei`",
+            Token::UnitTimeYears          => "`years`",
+            Token::UnitTimeWeeks          => "`weeks`",
+            Token::UnitTimeDays           => "`days`",
+            Token::UnitTimeHours          => "`hours`",
+            Token::UnitTimeMinutes        => "`minutes`",
+            Token::UnitTimeSeconds        => "`seconds`",
+            Token::AssemblyBind           => "`:=`",
+            Token::AssemblyAssign         => "`=:`",
+            Token::OperatorIncrement      => "`++`",
+            Token::OperatorDecrement      => "`--`",
+            Token::OperatorLogicalNot     => "`!`",
+            Token::OperatorBitNot         => "`~`",
+            Token::OperatorMultiplication => "`*`",
+            Token::OperatorDivision       => "`/`",
+            Token::OperatorRemainder      => "`%`",
+            Token::OperatorExponent       => "`**`",
+            Token::OperatorAddition       => "`+`",
+            Token::OperatorSubtraction    => "`-`",
+            Token::OperatorBitShiftLeft   => "`<<`",
+            Token::OperatorBitShiftRight  => "`>>`",
+            Token::OperatorLesser         => "`<`",
+            Token::OperatorLesserEquals   => "`<=`",
+            Token::OperatorGreater        => "`>`",
+            Token::OperatorGreaterEquals  => "`>=`",
+            Token::OperatorEquality       => "`==`",
+            Token::OperatorInequality     => "`!=`",
+            Token::OperatorBitAnd         => "`&`",
+            Token::OperatorBitXor         => "`^`",
+            Token::OperatorBitOr          => "`|`",
+            Token::OperatorLogicalAnd     => "`&&`",
+            Token::OperatorLogicalOr      => "`||`",
+            Token::OperatorConditional    => "`?`",
+            Token::Assign                 => "`=`",
+            Token::AssignAddition         => "`+=`",
+            Token::AssignSubtraction      => "`-=`",
+            Token::AssignMultiplication   => "`*=`",
+            Token::AssignDivision         => "`/=`",
+            Token::AssignRemainder        => "`%=`",
+            Token::AssignBitShiftLeft     => "`<<=`",
+            Token::AssignBitShiftRight    => "`>>=`",
+            Token::AssignBitAnd           => "`&=`",
+            Token::AssignBitXor           => "`^=`",
+            Token::AssignBitOr            => "`|=`",
+            Token::UnexpectedToken        => "invalid token",
+            Token::UnexpectedEndOfProgram => "unexpected end of file",
+        }
+    }
+}
+
 fn ignore_comments<'source, Src: Source<'source>>(lex: &mut Lexer<Token, Src>) {
     use logos::internal::LexerInternal;
 
@@ -447,6 +588,12 @@ fn ignore_comments<'source, Src: Source<'source>>(lex: &mut Lexer<Token, Src>) {
     lex.advance();
 }
 
+fn validate_string<'source, Src: Source<'source>>(lex: &mut Lexer<Token, Src>) {
+    if ::string_literal_error(lex.slice().as_bytes()).is_some() {
+        lex.token = Token::UnexpectedToken;
+    }
+}
+
 fn validate_bytes<'source, Src: Source<'source>>(lex: &mut Lexer<Token, Src>) {
     let slice = lex.slice().as_bytes();
 
@@ -520,7 +667,35 @@ fn validate_fixed<'source, Src: Source<'source>>(lex: &mut Lexer<Token, Src>) {
     }
 }
 
+/// Underscores in number literals are only allowed between two digits.
+fn valid_separators(slice: &[u8], is_digit: fn(&u8) -> bool) -> bool {
+    slice.iter().enumerate().all(|(index, byte)| {
+        *byte != b'_' || (
+            index > 0 &&
+            is_digit(&slice[index - 1]) &&
+            matches!(slice.get(index + 1), Some(byte) if is_digit(byte))
+        )
+    })
+}
+
+fn validate_hex<'source, Src: Source<'source>>(lex: &mut Lexer<Token, Src>) {
+    if !valid_separators(lex.slice().as_bytes(), u8::is_ascii_hexdigit) {
+        lex.token = Token::UnexpectedToken;
+    }
+}
+
+fn validate_integer<'source, Src: Source<'source>>(lex: &mut Lexer<Token, Src>) {
+    if !valid_separators(lex.slice().as_bytes(), u8::is_ascii_digit) {
+        lex.token = Token::UnexpectedToken;
+    }
+}
+
 fn rational_to_integer<'source, Src: Source<'source>>(lex: &mut Lexer<Token, Src>) {
+    if !valid_separators(lex.slice().as_bytes(), u8::is_ascii_digit) {
+        lex.token = Token::UnexpectedToken;
+        return;
+    }
+
     let mut floating = 0i32;
     let mut iter = lex.slice().as_bytes().iter();
 
@@ -528,6 +703,7 @@ fn rational_to_integer<'source, Src: Source<'source>>(lex: &mut Lexer<Token, Src
         match byte {
             b'e' | b'E' => break 'outer,
             b'0' => floating += 1,
+            b'1'..=b'9' => floating = 0,
             b'.' => {
                 floating = 0;
                 let mut zeroes = 0;
@@ -536,6 +712,7 @@ fn rational_to_integer<'source, Src: Source<'source>>(lex: &mut Lexer<Token, Src
                     match byte {
                         b'e' | b'E' => break 'outer,
                         b'0' => zeroes += 1,
+                        b'_' => {},
                         _ => {
                             floating -= 1 + zeroes;
                             zeroes = 0;
@@ -553,12 +730,12 @@ fn rational_to_integer<'source, Src: Source<'source>>(lex: &mut Lexer<Token, Src
     for &byte in iter {
         match byte {
             b'-' => neg = -1,
-            b'+' => {},
-            byte => e = e * 10 + (byte - b'0') as i32,
+            b'+' | b'_' => {},
+            byte => e = e.saturating_mul(10).saturating_add((byte - b'0') as i32),
         }
     }
 
-    if floating + e * neg >= 0 {
+    if floating.saturating_add(e * neg) >= 0 {
         lex.token = Token::LiteralInteger;
     }
 }
@@ -0,0 +1,118 @@
+//! Format Solidity source files in place, or the standard input to the
+//! standard output if no files are given.
+//!
+//! ```text
+//! lunarity-fmt [--check] [--max-width N] [--tab-spaces N] [--hard-tabs]
+//!              [--brace-style same-line|next-line] [--no-sort-imports] [FILE]...
+//! ```
+//!
+//! With `--check` nothing is written, the files that aren't formatted are
+//! listed instead, and the exit code is 1 if there are any.
+
+extern crate lunarity;
+
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process;
+
+use lunarity::{format, BraceStyle, FormatOptions, Renderer};
+
+struct Args {
+    check: bool,
+    options: FormatOptions,
+    files: Vec<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        check: false,
+        options: FormatOptions::default(),
+        files: Vec::new(),
+    };
+
+    let mut iter = env::args().skip(1);
+
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| iter.next().ok_or_else(|| format!("Missing value for {}", name));
+
+        match arg.as_str() {
+            "--check"           => args.check = true,
+            "--hard-tabs"       => args.options.hard_tabs = true,
+            "--no-sort-imports" => args.options.sort_imports = false,
+            "--max-width"       => args.options.max_width = number(&arg, value(&arg)?)?,
+            "--tab-spaces"      => args.options.tab_spaces = number(&arg, value(&arg)?)?,
+            "--brace-style"     => {
+                args.options.brace_style = match value(&arg)?.as_str() {
+                    "same-line" => BraceStyle::SameLine,
+                    "next-line" => BraceStyle::NextLine,
+                    style       => return Err(format!("Unknown brace style: {}", style)),
+                };
+            },
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: {}", arg)),
+            _                          => args.files.push(arg),
+        }
+    }
+
+    Ok(args)
+}
+
+fn number(name: &str, value: String) -> Result<usize, String> {
+    value.parse().map_err(|_| format!("Invalid value for {}: {}", name, value))
+}
+
+/// Format the source, rendering the errors if it doesn't parse.
+fn format_source(file_name: &str, source: &str, options: FormatOptions) -> Result<String, String> {
+    format(source, options).map_err(|errors| Renderer::new(file_name, source).render_all(&errors))
+}
+
+/// Format the files, returns `false` if checking found any of them unformatted.
+fn run() -> Result<bool, String> {
+    let args = parse_args()?;
+
+    if args.files.is_empty() {
+        let mut source = String::new();
+
+        io::stdin().read_to_string(&mut source).map_err(|err| err.to_string())?;
+
+        let formatted = format_source("<stdin>", &source, args.options)?;
+
+        if !args.check {
+            io::stdout().write_all(formatted.as_bytes()).map_err(|err| err.to_string())?;
+        }
+
+        return Ok(!args.check || formatted == source);
+    }
+
+    let mut formatted_all = true;
+
+    for file in &args.files {
+        let source = fs::read_to_string(file).map_err(|err| format!("{}: {}", file, err))?;
+        let formatted = format_source(file, &source, args.options)?;
+
+        if formatted == source {
+            continue;
+        }
+
+        formatted_all = false;
+
+        if args.check {
+            println!("{}", file);
+        } else {
+            fs::write(file, formatted).map_err(|err| format!("{}: {}", file, err))?;
+        }
+    }
+
+    Ok(!args.check || formatted_all)
+}
+
+fn main() {
+    match run() {
+        Ok(true)  => {},
+        Ok(false) => process::exit(1),
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(2);
+        },
+    }
+}
@@ -2,4 +2,5 @@ pub extern crate lunarity_ast as ast;
 pub extern crate lunarity_lexer as lexer;
 
 extern crate lunarity_parser;
-pub use lunarity_parser::parse;
+pub use lunarity_parser::{parse, parse_with_options, parse_lossless, parse_partial, parse_owned, parse_in, parse_in_with_options, ReusableArena, reparse, reparse_with_options, TextEdit, parse_file, parse_file_with_options, ParserOptions, Error, ErrorKind, Label, Limit, InvalidEdit, Renderer, print, Printer, PrintError, format, is_formatted, FormatOptions, BraceStyle};
+pub use lunarity_parser::{SyntaxTree, SyntaxNode, SyntaxElement, SyntaxLeaf, LeafKind};
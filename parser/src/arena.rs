@@ -0,0 +1,125 @@
+use toolshed::Arena;
+
+use ast::SourceUnitList;
+use {parse_in_with_options, Error, ParserOptions};
+
+/// Size of the pages of `Arena`, anything larger is allocated on its own.
+const PAGE_SIZE: usize = 64 * 1024;
+
+/// `Arena` for parsing many sources one after another, see `parse_in`.
+///
+/// Every parse releases what the previous one allocated. The first page of
+/// the arena is kept and reused as long as the AST fits on it, otherwise
+/// the whole arena is dropped and replaced with a fresh one, so the memory
+/// held between parses never exceeds a single page.
+pub struct ReusableArena {
+    arena: Arena,
+
+    /// Address of the first page of the arena
+    first_page: usize,
+
+    /// Set when the source was too large to be copied onto the first page
+    oversized: bool,
+}
+
+impl ReusableArena {
+    pub fn new() -> Self {
+        let arena = Arena::new();
+        let first_page = current_page(&arena);
+
+        ReusableArena {
+            arena,
+            first_page,
+            oversized: false,
+        }
+    }
+
+    /// Release everything allocated on the arena.
+    pub fn reset(&mut self) {
+        if self.oversized || current_page(&self.arena) != self.first_page {
+            *self = ReusableArena::new();
+        } else {
+            // Safe, since nothing borrows from the arena while it's borrowed
+            // mutably, and everything on it is on the first page.
+            unsafe { self.arena.clear() }
+        }
+    }
+
+    /// Same as `parse_in`, releasing the AST of the previous parse first.
+    pub fn parse<'ast>(&'ast mut self, source: &str) -> Result<SourceUnitList<'ast>, Vec<Error>> {
+        self.parse_with_options(source, ParserOptions::default())
+    }
+
+    /// Same as `parse`, with non-default `ParserOptions`.
+    pub fn parse_with_options<'ast>(&'ast mut self, source: &str, options: ParserOptions) -> Result<SourceUnitList<'ast>, Vec<Error>> {
+        self.reset();
+
+        // The source is copied onto the arena along with a nul byte
+        self.oversized = source.len() + 1 > PAGE_SIZE;
+
+        parse_in_with_options(source, &self.arena, options)
+    }
+}
+
+impl Default for ReusableArena {
+    fn default() -> Self {
+        ReusableArena::new()
+    }
+}
+
+/// Address of the page the arena currently allocates from.
+fn current_page(arena: &Arena) -> usize {
+    // Allocating nothing yields the address of the next allocation
+    let next = arena.alloc_slice::<u8>(&[]).as_ptr() as usize;
+
+    next - unsafe { arena.offset() }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reuses_first_page() {
+        let mut arena = ReusableArena::new();
+        let first_page = arena.first_page;
+
+        for _ in 0..1000 {
+            let body = arena.parse("contract Foo { function bar() { uint x = 1; } }").unwrap();
+
+            assert_eq!(body.iter().count(), 1);
+        }
+
+        assert_eq!(current_page(&arena.arena), first_page);
+    }
+
+    #[test]
+    fn replaces_grown_arena() {
+        let mut arena = ReusableArena::new();
+        let first_page = arena.first_page;
+
+        // Source fits on a page, but the AST doesn't
+        let source = "contract Foo { uint x = 1; }\n".repeat(1000);
+
+        assert_eq!(arena.parse(&source).unwrap().iter().count(), 1000);
+        assert!(!arena.oversized);
+        assert_ne!(current_page(&arena.arena), first_page);
+
+        arena.reset();
+
+        assert_eq!(current_page(&arena.arena), arena.first_page);
+    }
+
+    #[test]
+    fn replaces_arena_of_oversized_source() {
+        let mut arena = ReusableArena::new();
+        let source = "contract Foo {}\n".repeat(5000);
+
+        assert_eq!(arena.parse(&source).unwrap().iter().count(), 5000);
+        assert!(arena.oversized);
+
+        arena.parse("contract Foo {}").unwrap();
+
+        assert!(!arena.oversized);
+    }
+}
@@ -24,18 +24,18 @@ impl<'ast> Parser<'ast> {
     }
 
     fn assembly_item(&mut self) -> Option<AssemblyItemNode<'ast>> {
-        match self.lexer.token {
-            Token::BraceOpen  => self.inline_assembly_block(),
-            Token::Identifier => self.assembly_identifier(),
+        self.descend(|par| match par.lexer.token {
+            Token::BraceOpen  => par.inline_assembly_block(),
+            Token::Identifier => par.assembly_identifier(),
             _                 => None,
-        }
+        })
     }
 
     fn assembly_identifier(&mut self) -> Option<AssemblyItemNode<'ast>> {
         let (start, end) = self.loc();
         let identifier = self.lexer.slice();
 
-        self.lexer.advance();
+        self.advance();
 
         if self.allow(Token::AssemblyBind) {
             let id   = self.node_at(start, end, identifier);
@@ -57,8 +57,15 @@ impl<'ast> Parser<'ast> {
 
         let arguments = GrowableList::new();
 
-        while let Some(item) = self.assembly_item() {
+        if let Some(item) = self.assembly_item() {
             arguments.push(self.arena, item);
+
+            while self.allow(Token::Comma) {
+                match self.assembly_item() {
+                    Some(item) => arguments.push(self.arena, item),
+                    None       => self.error_expected(&[Token::Identifier, Token::BraceOpen]),
+                }
+            }
         }
 
         let end = self.expect_end(Token::ParenClose);
@@ -69,3 +76,49 @@ impl<'ast> Parser<'ast> {
         })
     }
 }
+
+
+#[cfg(test)]
+mod test {
+    use ast::visit::{self, Visit};
+    use ast::*;
+    use parse;
+
+    /// Number of arguments of every functional assembly expression.
+    struct Arguments(Vec<usize>);
+
+    impl<'ast> Visit<'ast> for Arguments {
+        fn visit_functional_assembly_expression(&mut self, expr: &FunctionalAssemblyExpression<'ast>, _start: u32, _end: u32) {
+            self.0.push(expr.arguments.iter().count());
+
+            visit::walk_functional_assembly_expression(self, expr);
+        }
+    }
+
+    fn arguments(assembly: &str) -> Result<Vec<usize>, ()> {
+        let source  = format!("contract Foo {{ function() {{ assembly {{ {} }} }} }}", assembly);
+        let program = parse(&source).map_err(|_| ())?;
+        let mut arguments = Arguments(Vec::new());
+
+        arguments.visit_program(&program);
+
+        Ok(arguments.0)
+    }
+
+    #[test]
+    fn functional_expression_arguments() {
+        assert_eq!(arguments("x := add()"), Ok(vec![0]));
+        assert_eq!(arguments("x := add(a)"), Ok(vec![1]));
+        assert_eq!(arguments("x := add(a, b)"), Ok(vec![2]));
+        assert_eq!(arguments("x := add(a, b, c)"), Ok(vec![3]));
+    }
+
+    #[test]
+    fn functional_expression_commas() {
+        assert_eq!(arguments("x := add(a b)"), Err(()));
+        assert_eq!(arguments("x := add(a,, b)"), Err(()));
+        assert_eq!(arguments("x := add(a,)"), Err(()));
+        assert_eq!(arguments("x := add(, a)"), Err(()));
+        assert_eq!(arguments("x := add(,)"), Err(()));
+    }
+}
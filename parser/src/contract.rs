@@ -1,11 +1,14 @@
 use toolshed::list::{ListBuilder, GrowableList};
 
 use ast::*;
-use {Parser, ModifierContext, TOP, RegularTypeNameContext};
+use {Parser, ErrorKind, ModifierContext, TOP, RegularTypeNameContext};
+use expression::EXPRESSION;
+use type_name::TYPE_NAME;
 use lexer::Token;
 
 impl<'ast> Parser<'ast> {
     pub fn contract_definition(&mut self) -> Option<SourceUnitNode<'ast>> {
+        let doc   = self.doc_comment();
         let start = self.start_then_advance();
         let name = self.expect_str_node(Token::Identifier);
 
@@ -21,24 +24,35 @@ impl<'ast> Parser<'ast> {
             NodeList::empty()
         };
 
-        self.expect(Token::BraceOpen);
+        self.doc_start = self.expect_end(Token::BraceOpen);
 
         let builder = GrowableList::new();
 
-        while let Some(part) = self.contract_part() {
+        loop {
+            let start = self.start_item();
+
+            let part = match self.contract_part() {
+                Some(part) => part,
+                None if self.needs_recovery(start) => self.recover_contract_part(start),
+                None => break,
+            };
+
+            self.doc_start = part.end;
+
             builder.push(self.arena, part);
         }
 
         let end = self.expect_end(Token::BraceClose);
 
         self.node_at(start, end, ContractDefinition {
+            doc,
             name,
             inherits,
             body: builder.as_list(),
         })
     }
 
-    fn contract_part(&mut self) -> Option<ContractPartNode<'ast>> {
+    pub fn contract_part(&mut self) -> Option<ContractPartNode<'ast>> {
         match self.lexer.token {
             Token::KeywordUsing        => self.using_for_declaration(),
             Token::DeclarationStruct   => self.struct_defintion(),
@@ -52,6 +66,7 @@ impl<'ast> Parser<'ast> {
     }
 
     fn state_variable_declaration(&mut self) -> Option<ContractPartNode<'ast>> {
+        let doc       = self.doc_comment();
         let type_name = self.type_name::<RegularTypeNameContext>()?;
 
         let mut visibility = None;
@@ -59,10 +74,10 @@ impl<'ast> Parser<'ast> {
 
         for _ in 0..2 {
             match self.lexer.token {
-                Token::KeywordPublic   => self.unique_flag(&mut visibility, StateVariableVisibility::Public),
-                Token::KeywordInternal => self.unique_flag(&mut visibility, StateVariableVisibility::Internal),
-                Token::KeywordPrivate  => self.unique_flag(&mut visibility, StateVariableVisibility::Private),
-                Token::KeywordConstant => self.unique_flag(&mut constant, Flag),
+                Token::KeywordPublic   => self.unique_flag(&mut visibility, StateVariableVisibility::Public, ErrorKind::DuplicateVisibility),
+                Token::KeywordInternal => self.unique_flag(&mut visibility, StateVariableVisibility::Internal, ErrorKind::DuplicateVisibility),
+                Token::KeywordPrivate  => self.unique_flag(&mut visibility, StateVariableVisibility::Private, ErrorKind::DuplicateVisibility),
+                Token::KeywordConstant => self.unique_flag(&mut constant, Flag, ErrorKind::DuplicateConstant),
                 _                      => break,
             }
         }
@@ -72,7 +87,7 @@ impl<'ast> Parser<'ast> {
         let init = if self.allow(Token::Assign) {
             match self.expression(TOP) {
                 None => {
-                    self.error();
+                    self.error_expected(EXPRESSION);
 
                     None
                 },
@@ -85,6 +100,7 @@ impl<'ast> Parser<'ast> {
         let end = self.expect_end(Token::Semicolon);
 
         self.node_at(type_name.start, end, StateVariableDeclaration {
+            doc,
             type_name,
             visibility,
             constant,
@@ -138,7 +154,7 @@ impl<'ast> Parser<'ast> {
             },
             None => {
                 // Must have at least one element
-                self.error();
+                self.error_expected(TYPE_NAME);
 
                 NodeList::empty()
             }
@@ -153,6 +169,7 @@ impl<'ast> Parser<'ast> {
     }
 
     fn modifier_definition(&mut self) -> Option<ContractPartNode<'ast>> {
+        let doc   = self.doc_comment();
         let start = self.start_then_advance();
         let name  = self.expect_str_node(Token::Identifier);
 
@@ -169,6 +186,7 @@ impl<'ast> Parser<'ast> {
         let block = self.block::<ModifierContext, _>();
 
         self.node_at(start, block.end, ModifierDefinition {
+            doc,
             name,
             params,
             block,
@@ -176,6 +194,7 @@ impl<'ast> Parser<'ast> {
     }
 
     fn event_definition(&mut self) -> Option<ContractPartNode<'ast>> {
+        let doc    = self.doc_comment();
         let start  = self.start_then_advance();
         let name   = self.expect_str_node(Token::Identifier);
 
@@ -188,7 +207,7 @@ impl<'ast> Parser<'ast> {
                 while self.allow(Token::Comma) {
                     match self.indexed_parameter() {
                         Some(param) => builder.push(self.arena, param),
-                        None        => self.error(),
+                        None        => self.error_expected(TYPE_NAME),
                     }
                 }
 
@@ -203,6 +222,7 @@ impl<'ast> Parser<'ast> {
         let end       = self.expect_end(Token::Semicolon);
 
         self.node_at(start, end, EventDefinition {
+            doc,
             anonymous,
             name,
             params,
@@ -269,11 +289,13 @@ mod test {
 
         "#, [
             m.node(14, 29, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: NodeList::empty(),
             }),
             m.node(42, 69, ContractDefinition {
+                doc: None,
                 name: m.node(51, 55, "Doge"),
                 inherits: m.list([
                     m.node(59, 66, "Amazing"),
@@ -281,6 +303,7 @@ mod test {
                 body: NodeList::empty(),
             }),
             m.node(82, 114, ContractDefinition {
+                doc: None,
                 name: m.node(91, 95, "This"),
                 inherits: m.list([
                     m.node(99, 104, "Silly"),
@@ -304,10 +327,12 @@ mod test {
 
         "#, [
             m.node(14, 111, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 60, StateVariableDeclaration {
+                        doc: None,
                         type_name: m.node(45, 50, ElementaryTypeName::Int(4)),
                         visibility: None,
                         constant: None,
@@ -315,6 +340,7 @@ mod test {
                         init: m.node(57, 59, Primitive::IntegerNumber("10", NumberUnit::None)),
                     }),
                     m.node(77, 97, StateVariableDeclaration {
+                        doc: None,
                         type_name: m.node(77, 84, ElementaryTypeName::Byte(10)),
                         visibility: m.node(85, 91, StateVariableVisibility::Public),
                         constant: None,
@@ -339,6 +365,7 @@ mod test {
 
         "#, [
             m.node(14, 112, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
@@ -371,6 +398,7 @@ mod test {
 
         "#, [
             m.node(14, 202, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
@@ -416,10 +444,12 @@ mod test {
 
         "#, [
             m.node(14, 206, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 71, ModifierDefinition {
+                        doc: None,
                         name: m.node(54, 64, "only_doges"),
                         params: NodeList::empty(),
                         block: m.node(65, 71, Block {
@@ -429,6 +459,7 @@ mod test {
                         }),
                     }),
                     m.node(89, 192, ModifierDefinition {
+                        doc: None,
                         name: m.node(98, 101, "foo"),
                         params: m.list([
                             m.node(102, 111, Parameter {
@@ -468,15 +499,18 @@ mod test {
 
         "#, [
             m.node(14, 121, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 61, EventDefinition {
+                        doc: None,
                         anonymous: None,
                         name: m.node(51, 58, "Horizon"),
                         params: NodeList::empty(),
                     }),
                     m.node(78, 107, EventDefinition {
+                        doc: None,
                         anonymous: m.node(97, 106, Flag),
                         name: m.node(84, 94, "Alcoholics"),
                         params: NodeList::empty(),
@@ -498,10 +532,12 @@ mod test {
 
         "#, [
             m.node(14, 94, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 80, EventDefinition {
+                        doc: None,
                         anonymous: None,
                         name: m.node(51, 58, "Horizon"),
                         params: m.list([
@@ -534,10 +570,12 @@ mod test {
 
         "#, [
             m.node(14, 102, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 88, EventDefinition {
+                        doc: None,
                         anonymous: None,
                         name: m.node(51, 58, "Horizon"),
                         params: m.list([
@@ -571,6 +609,7 @@ mod test {
 
         "#, [
             m.node(14, 116, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
@@ -0,0 +1,392 @@
+//! Lossless concrete syntax tree.
+//!
+//! The regular AST drops all whitespace, comments and punctuation. The
+//! `SyntaxTree` keeps every byte of the source as a leaf, grouped into
+//! nodes that mirror the spans of the typed `Node`s in the AST, so that
+//! concatenating all the leaves reproduces the source exactly.
+
+use std::fmt;
+use std::ops::Range;
+
+use ast::*;
+use error::Error;
+use lexer::{self, Lexer, Token};
+use parse;
+
+/// Lossless tree of the source, see module docs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyntaxTree<'src> {
+    root: SyntaxNode<'src>,
+}
+
+/// A node of the `SyntaxTree`, each node (except for the root) has
+/// a matching `Node` with the same span in the AST.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyntaxNode<'src> {
+    /// Name of the AST type or enum variant of the node,
+    /// such as `"ContractDefinition"` or `"BinaryExpression"`.
+    pub kind: &'static str,
+    pub start: u32,
+    pub end: u32,
+    pub children: Vec<SyntaxElement<'src>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SyntaxElement<'src> {
+    Node(SyntaxNode<'src>),
+    Leaf(SyntaxLeaf<'src>),
+}
+
+/// Single token or a piece of trivia.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SyntaxLeaf<'src> {
+    pub kind: LeafKind,
+    pub start: u32,
+    pub text: &'src str,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LeafKind {
+    Token(Token),
+
+    /// Everything following `pragma`, up to the semicolon.
+    PragmaVersion,
+    Whitespace,
+    LineComment,
+    BlockComment,
+}
+
+/// Parse the Solidity source and produce both the AST and the lossless
+/// `SyntaxTree` for it.
+pub fn parse_lossless<'src, 'ast>(source: &'src str) -> Result<(Program<'ast>, SyntaxTree<'src>), Vec<Error>> {
+    let program = parse(source)?;
+    let tree = SyntaxTree::new(source, &program);
+
+    Ok((program, tree))
+}
+
+impl<'src> SyntaxTree<'src> {
+    /// Build the tree for a `Program` parsed from `source`.
+    pub fn new(source: &'src str, program: &Program) -> Self {
+        let index = AstIndex::new(program);
+        let mut spans: Vec<_> = index.iter().map(|(_, entry)| *entry).collect();
+
+        // Outer nodes go before the inner ones, ties are kept in
+        // the walk order which always puts parents first.
+        spans.sort_by_key(|span| (span.start, !span.end));
+
+        let root = SyntaxNode {
+            kind: "Program",
+            start: 0,
+            end: source.len() as u32,
+            children: Vec::new(),
+        };
+
+        let mut stack = vec![root];
+        let mut spans = spans.into_iter().peekable();
+
+        for leaf in leaves(source) {
+            while let Some(span) = spans.peek().cloned() {
+                if span.start > leaf.start {
+                    break;
+                }
+
+                spans.next();
+                close_until(&mut stack, span.end);
+
+                stack.push(SyntaxNode {
+                    kind: span.kind,
+                    start: span.start,
+                    end: span.end,
+                    children: Vec::new(),
+                });
+            }
+
+            close_until(&mut stack, leaf.start + leaf.text.len() as u32);
+
+            stack.last_mut()
+                 .expect("Root is never closed; qed")
+                 .children
+                 .push(SyntaxElement::Leaf(leaf));
+        }
+
+        close_until(&mut stack, u32::MAX);
+
+        SyntaxTree {
+            root: stack.pop().expect("Root is never closed; qed"),
+        }
+    }
+
+    #[inline]
+    pub fn root(&self) -> &SyntaxNode<'src> {
+        &self.root
+    }
+
+    /// Find the outermost `SyntaxNode` matching the span of an AST `Node`.
+    pub fn find<T>(&self, node: &Node<T>) -> Option<&SyntaxNode<'src>> {
+        self.root.find(node.start, node.end)
+    }
+}
+
+/// Pop nodes off the stack until the top one contains `end`,
+/// the root always stays on the stack.
+fn close_until(stack: &mut Vec<SyntaxNode>, end: u32) {
+    while stack.len() > 1 && stack[stack.len() - 1].end < end {
+        let node = stack.pop().expect("Checked length above; qed");
+
+        stack.last_mut()
+             .expect("Checked length above; qed")
+             .children
+             .push(SyntaxElement::Node(node));
+    }
+}
+
+impl<'src> SyntaxNode<'src> {
+    /// All leaves of this node, in source order.
+    pub fn leaves(&self) -> Vec<&SyntaxLeaf<'src>> {
+        let mut leaves = Vec::new();
+
+        self.collect_leaves(&mut leaves);
+
+        leaves
+    }
+
+    fn collect_leaves<'a>(&'a self, leaves: &mut Vec<&'a SyntaxLeaf<'src>>) {
+        for child in &self.children {
+            match *child {
+                SyntaxElement::Node(ref node) => node.collect_leaves(leaves),
+                SyntaxElement::Leaf(ref leaf) => leaves.push(leaf),
+            }
+        }
+    }
+
+    /// Find the outermost node within this node (itself included)
+    /// with the exact span.
+    pub fn find(&self, start: u32, end: u32) -> Option<&SyntaxNode<'src>> {
+        if self.start == start && self.end == end && self.kind != "Program" {
+            return Some(self);
+        }
+
+        self.children.iter().filter_map(|child| match *child {
+            SyntaxElement::Node(ref node) if node.start <= start && end <= node.end => node.find(start, end),
+            _ => None,
+        }).next()
+    }
+}
+
+impl<'src> fmt::Display for SyntaxTree<'src> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.root, f)
+    }
+}
+
+impl<'src> fmt::Display for SyntaxNode<'src> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for leaf in self.leaves() {
+            f.write_str(leaf.text)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Lex the source into a contiguous list of tokens and trivia.
+pub fn leaves<'src>(source: &'src str) -> Vec<SyntaxLeaf<'src>> {
+    let mut leaves = Vec::new();
+    let mut lex = Lexer::new(source);
+    let mut last = 0;
+
+    loop {
+        let range = lex.range();
+
+        trivia(source, last..range.start, &mut leaves);
+        last = range.start;
+
+        if lex.token == Token::EndOfProgram {
+            break;
+        }
+
+        let is_pragma = lex.token == Token::KeywordPragma;
+
+        leaves.push(leaf(source, LeafKind::Token(lex.token), range.clone()));
+        last = range.end;
+
+        if lex.token == Token::UnexpectedEndOfProgram {
+            break;
+        }
+
+        lex.advance();
+
+        if is_pragma && lex.token == Token::Identifier {
+            let start = lex.range().start;
+
+            trivia(source, last..start, &mut leaves);
+
+            let version = lexer::read_pragma(&mut lex);
+
+            last = start + version.len();
+            leaves.push(leaf(source, LeafKind::PragmaVersion, start..last));
+
+            if lex.token != Token::Semicolon {
+                break;
+            }
+
+            let end = lex.range().end;
+
+            leaves.push(leaf(source, LeafKind::Token(Token::Semicolon), last..end));
+            last = end;
+
+            lex.advance();
+        }
+    }
+
+    trivia(source, last..source.len(), &mut leaves);
+
+    leaves
+}
+
+/// Split whitespace and comments between two tokens into leaves.
+fn trivia<'src>(source: &'src str, range: Range<usize>, leaves: &mut Vec<SyntaxLeaf<'src>>) {
+    let bytes = &source.as_bytes()[..range.end];
+    let mut index = range.start;
+
+    while index < range.end {
+        let start = index;
+        let rest = &bytes[index..];
+
+        let kind = if rest.starts_with(b"//") {
+            index += rest.iter().position(|&byte| byte == b'\n').unwrap_or(rest.len());
+
+            LeafKind::LineComment
+        } else if rest.starts_with(b"/*") {
+            index += rest.windows(2).skip(2).position(|pair| pair == b"*/").map_or(rest.len(), |pos| pos + 4);
+
+            LeafKind::BlockComment
+        } else {
+            index += rest.iter().position(|&byte| byte == b'/').unwrap_or(rest.len()).max(1);
+
+            LeafKind::Whitespace
+        };
+
+        leaves.push(leaf(source, kind, start..index));
+    }
+}
+
+#[inline]
+fn leaf<'src>(source: &'src str, kind: LeafKind, range: Range<usize>) -> SyntaxLeaf<'src> {
+    SyntaxLeaf {
+        kind,
+        start: range.start as u32,
+        text: &source[range],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SOURCE: &str = r#"// SPDX-License-Identifier: MIT
+pragma  solidity ^0.4.17 ;
+
+/* Such */ contract Doge is /* very */ Shibe {
+    /// Number of barks
+    uint  barks = 10 * 2; // many
+
+    function bark(address target) public {
+        barks += 1;
+        assembly { barks := extcodesize(target) }
+    }
+}
+// trailing
+"#;
+
+    fn nodes<'a, 'src>(node: &'a SyntaxNode<'src>) -> Vec<&'a SyntaxNode<'src>> {
+        node.children.iter().filter_map(|child| match *child {
+            SyntaxElement::Node(ref node) => Some(node),
+            _                             => None,
+        }).collect()
+    }
+
+    fn kinds(node: &SyntaxNode) -> Vec<&'static str> {
+        nodes(node).iter().map(|node| node.kind).collect()
+    }
+
+    #[test]
+    fn lossless_round_trip() {
+        let (_, tree) = parse_lossless(SOURCE).unwrap();
+
+        assert_eq!(tree.to_string(), SOURCE);
+    }
+
+    #[test]
+    fn lossless_round_trip_second_price_auction() {
+        let source = include_str!("../../lunarity/benches/second-price-auction.sol");
+        let (_, tree) = parse_lossless(source).unwrap();
+
+        assert_eq!(tree.to_string(), source);
+    }
+
+    #[test]
+    fn lossless_leaves() {
+        let (_, tree) = parse_lossless(SOURCE).unwrap();
+        let leaves = tree.root().leaves();
+
+        assert_eq!(leaves[0].kind, LeafKind::LineComment);
+        assert_eq!(leaves[0].text, "// SPDX-License-Identifier: MIT");
+        assert_eq!(leaves[1].kind, LeafKind::Whitespace);
+        assert_eq!(leaves[2].kind, LeafKind::Token(Token::KeywordPragma));
+        assert_eq!(leaves[3].text, "  ");
+        assert_eq!(leaves[4].kind, LeafKind::PragmaVersion);
+        assert_eq!(leaves[4].text, "solidity ^0.4.17 ");
+        assert_eq!(leaves[5].kind, LeafKind::Token(Token::Semicolon));
+        assert_eq!(leaves[7].kind, LeafKind::BlockComment);
+        assert_eq!(leaves[7].text, "/* Such */");
+
+        let mut offset = 0;
+
+        for leaf in leaves {
+            assert_eq!(leaf.start, offset);
+            offset += leaf.text.len() as u32;
+        }
+
+        assert_eq!(offset, SOURCE.len() as u32);
+    }
+
+    #[test]
+    fn lossless_nodes() {
+        let (program, tree) = parse_lossless(SOURCE).unwrap();
+
+        assert_eq!(kinds(tree.root()), vec!["PragmaDirective", "ContractDefinition"]);
+
+        let contract = program.body().iter().nth(1).unwrap();
+        let node = tree.find(contract).unwrap();
+
+        assert_eq!(node.kind, "ContractDefinition");
+        assert_eq!(kinds(node), vec!["Identifier", "Identifier", "StateVariableDeclaration", "FunctionDefinition"]);
+        assert!(node.to_string().starts_with("contract Doge is /* very */ Shibe {"));
+
+        let var = nodes(node)[2];
+
+        assert_eq!(var.to_string(), "uint  barks = 10 * 2;");
+        assert_eq!(kinds(var), vec!["ElementaryTypeName", "Identifier", "BinaryExpression"]);
+    }
+
+    #[test]
+    fn lossless_doc_comments() {
+        let source = "contract Doge {\n    /// Barks\n    function bark() {}\n}";
+        let program = ::parse_with_options(source, ::ParserOptions { doc_comments: true, ..Default::default() }).unwrap();
+        let tree = SyntaxTree::new(source, &program);
+
+        assert_eq!(tree.to_string(), source);
+
+        let contract = tree.root().find(0, source.len() as u32).unwrap();
+
+        // Doc comment precedes the definition, so it's a sibling node
+        assert_eq!(kinds(contract), vec!["Identifier", "DocComment", "FunctionDefinition"]);
+
+        let doc = nodes(contract)[1];
+
+        assert_eq!(doc.to_string(), "/// Barks");
+        assert_eq!(doc.leaves()[0].kind, LeafKind::LineComment);
+    }
+}
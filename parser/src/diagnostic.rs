@@ -0,0 +1,413 @@
+use std::cmp::Reverse;
+use std::fmt::Write;
+use std::ops::Range;
+
+use ast::{FileId, LineIndex, SourceFiles};
+use Error;
+
+const TAB_WIDTH: usize = 4;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Style {
+    Plain,
+    Bold,
+    Primary,
+    Secondary,
+}
+
+impl Style {
+    fn ansi(self) -> &'static str {
+        match self {
+            Style::Plain     => "\x1b[0m",
+            Style::Bold      => "\x1b[1m",
+            Style::Primary   => "\x1b[1;31m",
+            Style::Secondary => "\x1b[1;34m",
+        }
+    }
+}
+
+/// Underlined part of a single source line, in display columns.
+struct Annotation {
+    start: usize,
+    end: usize,
+    message: &'static str,
+    style: Style,
+}
+
+/// Renders `Error`s as human readable diagnostics: location of the error,
+/// followed by the offending source lines with the error and any of its
+/// labels underlined.
+///
+/// ```text
+/// error[E0004]: visibility is already declared, found another `external`
+///  --> foo.sol:1:34
+///   |
+/// 1 | contract Foo { function() public external; }
+///   |                           ------ ^^^^^^^^
+///   |                           |
+///   |                           previous visibility declared here
+/// ```
+pub struct Renderer<'src> {
+    file_name: &'src str,
+    index: LineIndex<'src>,
+    colors: bool,
+
+    /// Offset of the source in the space of all files of `SourceFiles`.
+    offset: usize,
+}
+
+impl<'src> Renderer<'src> {
+    /// Create a renderer for errors produced by parsing `source`,
+    /// the output is plain text without colors.
+    pub fn new(file_name: &'src str, source: &'src str) -> Self {
+        Renderer {
+            file_name,
+            index: LineIndex::new(source),
+            colors: false,
+            offset: 0,
+        }
+    }
+
+    /// Create a renderer for errors produced by `parse_file`.
+    pub fn for_file(files: &'src SourceFiles, id: FileId) -> Self {
+        let file = files.get(id);
+
+        Renderer {
+            file_name: file.path(),
+            index: LineIndex::new(file.source()),
+            colors: false,
+            offset: file.start() as usize,
+        }
+    }
+
+    /// Enable or disable ANSI colors in the output.
+    pub fn colors(mut self, colors: bool) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    /// Render all errors, separated by empty lines.
+    pub fn render_all(&self, errors: &[Error]) -> String {
+        let mut out = String::new();
+
+        for (index, error) in errors.iter().enumerate() {
+            if index != 0 {
+                out.push('\n');
+            }
+
+            out.push_str(&self.render(error));
+        }
+
+        out
+    }
+
+    pub fn render(&self, error: &Error) -> String {
+        let mut out = String::new();
+
+        let local = |span: &Range<usize>| span.start - self.offset..span.end - self.offset;
+
+        let location = self.index.line_col_chars((error.span.start - self.offset) as u32);
+        let mut spans = vec![(local(&error.span), "", Style::Primary)];
+
+        spans.extend(error.labels.iter().map(|label| (local(&label.span), label.message, Style::Secondary)));
+
+        // Group the underlined spans by the line they start on
+        let mut lines: Vec<(u32, Vec<Annotation>)> = Vec::new();
+
+        for (span, message, style) in spans {
+            let line = self.index.line_col(span.start as u32).line;
+            let annotation = self.annotation(line, span, message, style);
+
+            match lines.iter().position(|&(number, _)| number == line) {
+                Some(index) => lines[index].1.push(annotation),
+                None        => lines.push((line, vec![annotation])),
+            }
+        }
+
+        lines.sort_by_key(|&(line, _)| line);
+
+        let last  = lines.last().map_or(0, |&(line, _)| line);
+        let width = (last + 1).to_string().len();
+        let gutter = " ".repeat(width);
+
+        self.paint(&mut out, Style::Primary, &format!("error[{}]", error.code()));
+        self.paint(&mut out, Style::Bold, &format!(": {}", error.summary()));
+        out.push('\n');
+
+        out.push_str(&gutter);
+        self.paint(&mut out, Style::Secondary, "-->");
+        let _ = writeln!(out, " {}:{}:{}", self.file_name, location.line + 1, location.column + 1);
+
+        self.gutter(&mut out, &gutter);
+        out.push('\n');
+
+        let mut previous = None;
+
+        for (line, annotations) in lines {
+            if let Some(previous) = previous {
+                if line > previous + 1 {
+                    self.paint(&mut out, Style::Secondary, "...");
+                    out.push('\n');
+                }
+            }
+
+            previous = Some(line);
+
+            let number = format!("{:>width$} |", line + 1, width = width);
+            let text   = expand_tabs(self.index.line_text(line).unwrap_or(""));
+
+            self.paint(&mut out, Style::Secondary, &number);
+
+            if !text.is_empty() {
+                out.push(' ');
+                out.push_str(&text);
+            }
+
+            out.push('\n');
+
+            self.underlines(&mut out, &gutter, annotations);
+        }
+
+        out
+    }
+
+    fn annotation(&self, line: u32, span: Range<usize>, message: &'static str, style: Style) -> Annotation {
+        let range = self.index.line_range(line).unwrap_or(0..0);
+        let text  = self.index.line_text(line).unwrap_or("");
+
+        // Spans running past the end of the line are cut off at the end of it
+        let start = self.index.line_col(span.start as u32).column.min(range.end - range.start);
+        let end   = if span.end as u32 > range.end {
+            range.end - range.start
+        } else {
+            self.index.line_col(span.end as u32).column.max(start)
+        };
+
+        let start = display_width(&text[..start as usize]);
+        let end   = display_width(&text[..end as usize]).max(start + 1);
+
+        Annotation {
+            start,
+            end,
+            message,
+            style,
+        }
+    }
+
+    fn underlines(&self, out: &mut String, gutter: &str, mut annotations: Vec<Annotation>) {
+        // Rightmost annotations get their messages printed first
+        annotations.sort_by_key(|annotation| Reverse(annotation.start));
+
+        let mut row = Vec::new();
+
+        // Draw secondary underlines first so that the primary one is never hidden
+        for annotation in annotations.iter().rev().filter(|annotation| annotation.style != Style::Primary) {
+            put(&mut row, annotation.start, &"-".repeat(annotation.end - annotation.start), annotation.style);
+        }
+
+        for annotation in annotations.iter().filter(|annotation| annotation.style == Style::Primary) {
+            put(&mut row, annotation.start, &"^".repeat(annotation.end - annotation.start), annotation.style);
+        }
+
+        let mut labeled: Vec<&Annotation> = annotations.iter().filter(|annotation| !annotation.message.is_empty()).collect();
+
+        // Message of the rightmost annotation fits on the same row, unless
+        // another underline follows it
+        if let Some(first) = labeled.first().cloned() {
+            if annotations.iter().all(|annotation| annotation.start <= first.start) {
+                let end = row.len();
+
+                put(&mut row, end + 1, first.message, first.style);
+                labeled.remove(0);
+            }
+        }
+
+        self.row(out, gutter, &row);
+
+        if labeled.is_empty() {
+            return;
+        }
+
+        let mut row = Vec::new();
+
+        for annotation in &labeled {
+            put(&mut row, annotation.start, "|", annotation.style);
+        }
+
+        self.row(out, gutter, &row);
+
+        for (index, annotation) in labeled.iter().enumerate() {
+            let mut row = Vec::new();
+
+            for other in &labeled[index + 1..] {
+                put(&mut row, other.start, "|", other.style);
+            }
+
+            put(&mut row, annotation.start, annotation.message, annotation.style);
+
+            self.row(out, gutter, &row);
+        }
+    }
+
+    fn row(&self, out: &mut String, gutter: &str, row: &[(char, Style)]) {
+        self.gutter(out, gutter);
+        out.push(' ');
+
+        let mut current = Style::Plain;
+
+        for &(ch, style) in row {
+            if self.colors && style != current {
+                out.push_str(style.ansi());
+                current = style;
+            }
+
+            out.push(ch);
+        }
+
+        if current != Style::Plain {
+            out.push_str(Style::Plain.ansi());
+        }
+
+        out.push('\n');
+    }
+
+    fn gutter(&self, out: &mut String, gutter: &str) {
+        out.push_str(gutter);
+        self.paint(out, Style::Secondary, " |");
+    }
+
+    fn paint(&self, out: &mut String, style: Style, text: &str) {
+        if self.colors {
+            out.push_str(style.ansi());
+            out.push_str(text);
+            out.push_str(Style::Plain.ansi());
+        } else {
+            out.push_str(text);
+        }
+    }
+}
+
+/// Write `text` into the row of cells starting at `column`, padding it with
+/// spaces as necessary.
+fn put(row: &mut Vec<(char, Style)>, column: usize, text: &str, style: Style) {
+    for (offset, ch) in text.chars().enumerate() {
+        let column = column + offset;
+
+        while row.len() <= column {
+            row.push((' ', Style::Plain));
+        }
+
+        row[column] = (ch, style);
+    }
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars().map(|ch| if ch == '\t' { TAB_WIDTH } else { 1 }).sum()
+}
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use parse;
+
+    fn render(source: &str) -> String {
+        match parse(source) {
+            Ok(_)       => panic!("Expected {:?} to fail", source),
+            Err(errors) => Renderer::new("foo.sol", source).render_all(&errors),
+        }
+    }
+
+    #[test]
+    fn unexpected_token() {
+        let source = "contract Foo {\n    function() {\n        1 + ;\n    }\n}\n";
+
+        assert_eq!(render(source), "\
+error[E0001]: unexpected `;`
+ --> foo.sol:3:13
+  |
+3 |         1 + ;
+  |             ^
+");
+    }
+
+    #[test]
+    fn duplicate_flag_label() {
+        let source = "contract Foo { function() public external; }";
+
+        assert_eq!(render(source), "\
+error[E0004]: visibility is already declared, found another `external`
+ --> foo.sol:1:34
+  |
+1 | contract Foo { function() public external; }
+  |                           ------ ^^^^^^^^
+  |                           |
+  |                           previous visibility declared here
+");
+    }
+
+    #[test]
+    fn label_on_another_line() {
+        let source = "contract Foo {\n    function()\n        pure\n\n\n\n\n\n\n\n        view;\n}";
+
+        assert_eq!(render(source), "\
+error[E0005]: state mutability is already declared, found another `view`
+  --> foo.sol:11:9
+   |
+ 3 |         pure
+   |         ---- previous state mutability declared here
+...
+11 |         view;
+   |         ^^^^
+");
+    }
+
+    #[test]
+    fn end_of_file() {
+        let source = "contract Foo {\n";
+
+        assert_eq!(render(source), "\
+error[E0002]: unexpected end of file, expected `}`
+ --> foo.sol:2:1
+  |
+2 |
+  | ^
+");
+    }
+
+    #[test]
+    fn tabs_and_multiple_errors() {
+        let source = "contract Foo {\n\tuint a = 1\n\tuint b;\n\tuint c = 2 + ;\n}";
+
+        assert_eq!(render(source), "\
+error[E0003]: missing `;`, found `uint`
+ --> foo.sol:3:2
+  |
+3 |     uint b;
+  |     ^^^^
+
+error[E0001]: unexpected `;`
+ --> foo.sol:4:15
+  |
+4 |     uint c = 2 + ;
+  |                  ^
+");
+    }
+
+    #[test]
+    fn colors() {
+        let source = "contract Foo { function() public external; }";
+        let errors = match parse(source) {
+            Ok(_)       => panic!("Expected {:?} to fail", source),
+            Err(errors) => errors,
+        };
+        let output = Renderer::new("foo.sol", source).colors(true).render(&errors[0]);
+
+        assert!(output.starts_with("\x1b[1;31merror[E0004]\x1b[0m\x1b[1m: visibility"));
+        assert!(output.contains("\x1b[1;34m------\x1b[0m \x1b[1;31m^^^^^^^^\x1b[0m"));
+        assert!(output.contains("\x1b[1;34mprevious visibility declared here\x1b[0m"));
+    }
+}
@@ -0,0 +1,369 @@
+use std::ops::Range;
+
+use toolshed::list::GrowableList;
+
+use ast::*;
+use Parser;
+
+/// Single line of a doc comment with the comment markers stripped,
+/// along with its offset in the source.
+struct DocLine<'ast> {
+    start: u32,
+    text: &'ast str,
+}
+
+struct PendingTag<'ast> {
+    kind: DocTagKind<'ast>,
+    start: u32,
+    end: u32,
+    lines: Vec<&'ast str>,
+}
+
+impl<'ast> Parser<'ast> {
+    /// Read the NatSpec comment preceding the current token, if any. The
+    /// comment has to be located after the end of the previous definition.
+    pub fn doc_comment(&mut self) -> Option<DocCommentNode<'ast>> {
+        if !self.options.doc_comments {
+            return None;
+        }
+
+        let source: &'ast str = *self.lexer.source;
+        let offset = self.doc_start as usize;
+        let trivia = source.get(offset - self.offset as usize..self.lexer.range().start)?;
+        let range = last_doc_comment(trivia)?;
+        let comment = &trivia[range.clone()];
+
+        let lines = if comment.starts_with("///") {
+            line_comment_lines(comment, offset + range.start)
+        } else {
+            block_comment_lines(comment, offset + range.start)
+        };
+
+        let tags = GrowableList::new();
+        let mut pending: Option<PendingTag<'ast>> = None;
+
+        for line in lines {
+            let end = line.start + line.text.len() as u32;
+
+            if line.text.starts_with('@') {
+                if let Some(tag) = pending.take() {
+                    tags.push(self.arena, self.doc_tag(tag));
+                }
+
+                let (kind, text) = doc_tag_kind(line.text);
+
+                pending = Some(PendingTag {
+                    kind,
+                    start: line.start,
+                    end,
+                    lines: if text.is_empty() { Vec::new() } else { vec![text] },
+                });
+
+                continue;
+            }
+
+            match pending {
+                Some(ref mut tag) => {
+                    tag.lines.push(line.text);
+                    tag.end = end;
+                },
+                None => pending = Some(PendingTag {
+                    kind: DocTagKind::Notice,
+                    start: line.start,
+                    end,
+                    lines: vec![line.text],
+                }),
+            }
+        }
+
+        if let Some(tag) = pending {
+            tags.push(self.arena, self.doc_tag(tag));
+        }
+
+        let start = (offset + range.start) as u32;
+        let end   = (offset + range.end) as u32;
+
+        self.node_at(start, end, DocComment {
+            tags: tags.as_list(),
+        })
+    }
+
+    fn doc_tag(&mut self, tag: PendingTag<'ast>) -> DocTagNode<'ast> {
+        let text = match tag.lines.len() {
+            0 => "",
+            1 => tag.lines[0],
+            _ => self.arena.alloc_str(&tag.lines.join("\n")),
+        };
+
+        self.node_at(tag.start, tag.end, DocTag {
+            kind: tag.kind,
+            text,
+        })
+    }
+}
+
+/// Find the last doc comment in the whitespace and comments between
+/// two tokens. Consecutive `///` lines are merged into one comment.
+fn last_doc_comment(trivia: &str) -> Option<Range<usize>> {
+    let bytes = trivia.as_bytes();
+    let mut doc: Option<Range<usize>> = None;
+    let mut line_run = false;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let rest = &bytes[index..];
+
+        if rest.starts_with(b"//") {
+            let end = rest.iter().position(|&byte| byte == b'\n').map_or(bytes.len(), |pos| index + pos);
+
+            if rest.starts_with(b"///") && !rest.starts_with(b"////") {
+                doc = match doc {
+                    Some(ref doc) if line_run => Some(doc.start..end),
+                    _                         => Some(index..end),
+                };
+                line_run = true;
+            } else {
+                line_run = false;
+            }
+
+            index = end;
+        } else if rest.starts_with(b"/*") {
+            let end = rest.windows(2).skip(2).position(|pair| pair == b"*/").map_or(bytes.len(), |pos| index + pos + 4);
+
+            if rest.starts_with(b"/**") && !rest.starts_with(b"/**/") && !rest.starts_with(b"/***") {
+                doc = Some(index..end);
+            }
+
+            line_run = false;
+            index = end;
+        } else {
+            index += 1;
+        }
+    }
+
+    doc
+}
+
+fn line_comment_lines<'ast>(comment: &'ast str, offset: usize) -> Vec<DocLine<'ast>> {
+    let mut lines = Vec::new();
+    let mut start = offset;
+
+    for line in comment.split('\n') {
+        let indent = line.len() - line.trim_start().len();
+
+        if line.trim_start().starts_with("///") {
+            push_doc_line(&mut lines, &line[indent + 3..], start + indent + 3);
+        }
+
+        start += line.len() + 1;
+    }
+
+    lines
+}
+
+fn block_comment_lines<'ast>(comment: &'ast str, offset: usize) -> Vec<DocLine<'ast>> {
+    let body = &comment[3..];
+    let body = body.strip_suffix("*/").unwrap_or(body);
+    let mut lines = Vec::new();
+    let mut start = offset + 3;
+
+    for line in body.split('\n') {
+        let indent = line.len() - line.trim_start().len();
+        let skip = if line[indent..].starts_with('*') { indent + 1 } else { indent };
+
+        push_doc_line(&mut lines, &line[skip..], start + skip);
+
+        start += line.len() + 1;
+    }
+
+    lines
+}
+
+fn push_doc_line<'ast>(lines: &mut Vec<DocLine<'ast>>, line: &'ast str, start: usize) {
+    let text = line.trim();
+
+    if !text.is_empty() {
+        let indent = line.len() - line.trim_start().len();
+
+        lines.push(DocLine {
+            start: (start + indent) as u32,
+            text,
+        });
+    }
+}
+
+/// Split a line starting with `@` into the tag kind and the remaining text.
+fn doc_tag_kind(line: &str) -> (DocTagKind<'_>, &str) {
+    let (name, text) = split_word(&line[1..]);
+
+    let kind = match name {
+        "title"   => DocTagKind::Title,
+        "author"  => DocTagKind::Author,
+        "notice"  => DocTagKind::Notice,
+        "dev"     => DocTagKind::Dev,
+        "return"  => DocTagKind::Return,
+        "param" | "inheritdoc" => {
+            let (ident, text) = split_word(text);
+            let kind = match name {
+                "param" => DocTagKind::Param(ident),
+                _       => DocTagKind::Inheritdoc(ident),
+            };
+
+            return (kind, text);
+        },
+        _ if name.starts_with("custom:") => DocTagKind::Custom(&name[7..]),
+        _ => DocTagKind::Unknown(name),
+    };
+
+    (kind, text)
+}
+
+fn split_word(text: &str) -> (&str, &str) {
+    match text.find(char::is_whitespace) {
+        Some(index) => (&text[..index], text[index..].trim_start()),
+        None        => (text, ""),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use {parse, parse_with_options, ParserOptions};
+
+    fn options() -> ParserOptions {
+        ParserOptions {
+            doc_comments: true,
+            ..ParserOptions::default()
+        }
+    }
+
+    fn tags<'ast>(doc: Option<DocCommentNode<'ast>>) -> Vec<(DocTagKind<'ast>, &'ast str)> {
+        doc.expect("Missing doc comment")
+           .value.tags
+           .iter()
+           .map(|tag| (tag.value.kind, tag.value.text))
+           .collect()
+    }
+
+    fn contract<'ast>(program: &::ast::Program<'ast>, index: usize) -> ContractDefinition<'ast> {
+        match program.body().iter().nth(index).map(|unit| unit.value) {
+            Some(SourceUnit::ContractDefinition(contract)) => contract,
+            _ => panic!("Expected a contract"),
+        }
+    }
+
+    fn part_doc<'ast>(part: ContractPartNode<'ast>) -> Option<DocCommentNode<'ast>> {
+        match part.value {
+            ContractPart::StateVariableDeclaration(ref part) => part.doc,
+            ContractPart::ModifierDefinition(ref part)       => part.doc,
+            ContractPart::FunctionDefinition(ref part)       => part.doc,
+            ContractPart::EventDefinition(ref part)          => part.doc,
+            _                                                => None,
+        }
+    }
+
+    const SOURCE: &str = r#"
+        pragma solidity ^0.4.17;
+
+        /**
+         * @title Doge
+         * @author Such Wow
+         */
+        contract Doge is Shibe {
+            /// @dev Number of barks
+            uint barks;
+
+            /// Emitted on bark,
+            /// very loud.
+            event Bark(uint volume);
+
+            /// @custom:security non-reentrant
+            modifier quiet { _; }
+
+            // Not a doc comment
+            function wag() {}
+
+            /// @notice Bark at someone
+            /// @param target Who to bark at
+            /// @param volume How loud
+            ///   really
+            /// @return Whether it worked
+            function bark(address target, uint volume) returns (bool) {}
+
+            /// @inheritdoc Shibe
+            //// Not a doc comment either
+            function sit() {}
+        }
+    "#;
+
+    #[test]
+    fn natspec_tags() {
+        let program = parse_with_options(SOURCE, options()).unwrap();
+        let doge = contract(&program, 1);
+        let parts: Vec<_> = doge.body.iter().cloned().collect();
+
+        assert_eq!(tags(doge.doc), vec![
+            (DocTagKind::Title, "Doge"),
+            (DocTagKind::Author, "Such Wow"),
+        ]);
+        assert_eq!(tags(part_doc(parts[0])), vec![
+            (DocTagKind::Dev, "Number of barks"),
+        ]);
+        assert_eq!(tags(part_doc(parts[1])), vec![
+            (DocTagKind::Notice, "Emitted on bark,\nvery loud."),
+        ]);
+        assert_eq!(tags(part_doc(parts[2])), vec![
+            (DocTagKind::Custom("security"), "non-reentrant"),
+        ]);
+        assert_eq!(part_doc(parts[3]), None);
+        assert_eq!(tags(part_doc(parts[4])), vec![
+            (DocTagKind::Notice, "Bark at someone"),
+            (DocTagKind::Param("target"), "Who to bark at"),
+            (DocTagKind::Param("volume"), "How loud\nreally"),
+            (DocTagKind::Return, "Whether it worked"),
+        ]);
+        assert_eq!(tags(part_doc(parts[5])), vec![
+            (DocTagKind::Inheritdoc("Shibe"), ""),
+        ]);
+    }
+
+    #[test]
+    fn natspec_spans() {
+        let program = parse_with_options(SOURCE, options()).unwrap();
+        let doge = contract(&program, 1);
+        let doc = doge.doc.unwrap();
+        let title = doc.value.tags.iter().next().unwrap();
+
+        assert_eq!(&SOURCE[doc.start as usize..doc.end as usize], "/**\n         * @title Doge\n         * @author Such Wow\n         */");
+        assert_eq!(&SOURCE[title.start as usize..title.end as usize], "@title Doge");
+    }
+
+    #[test]
+    fn natspec_disabled_by_default() {
+        let program = parse(SOURCE).unwrap();
+        let doge = contract(&program, 1);
+
+        assert_eq!(doge.doc, None);
+
+        for part in doge.body.iter() {
+            assert_eq!(part_doc(*part), None);
+        }
+    }
+
+    #[test]
+    fn natspec_second_price_auction() {
+        let source = include_str!("../../lunarity/benches/second-price-auction.sol");
+        let program = parse_with_options(source, options()).unwrap();
+
+        assert_eq!(tags(contract(&program, 1).doc), vec![
+            (DocTagKind::Notice, "Stripped down ERC20 standard token interface."),
+        ]);
+        assert_eq!(contract(&program, 2).doc, None);
+        assert_eq!(tags(contract(&program, 3).doc), vec![
+            (DocTagKind::Notice, "Simple modified second price auction contract. Price starts high and monotonically decreases\n\
+                                  until all tokens are sold at the current price with currently received funds.\n\
+                                  The price curve has been chosen to resemble a logarithmic curve\n\
+                                  and produce a reasonable auction timeline."),
+        ]);
+    }
+}
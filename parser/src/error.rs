@@ -1,13 +1,179 @@
-use std::fmt::{self, Debug};
+use std::fmt::{self, Debug, Display};
 use std::ops::Range;
 use Token;
+use expression::EXPRESSION;
+use statement::STATEMENT;
+use type_name::TYPE_NAME;
 
 /// Error type used by the tokenizer and the parser internally.
 #[derive(PartialEq, Clone)]
 pub struct Error {
+    pub kind: ErrorKind,
     pub token: Token,
     pub raw: Box<str>,
     pub span: Range<usize>,
+
+    /// Tokens that would have been accepted at this position,
+    /// empty if the parser couldn't tell.
+    pub expected: Vec<Token>,
+
+    /// Other locations in the source related to this error.
+    pub labels: Vec<Label>,
+}
+
+/// Secondary location attached to an `Error`, such as the previous
+/// declaration of a duplicate flag.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Label {
+    pub span: Range<usize>,
+    pub message: &'static str,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ErrorKind {
+    UnexpectedToken,
+    UnexpectedEndOfProgram,
+    MissingSemicolon,
+    DuplicateVisibility,
+    DuplicateMutability,
+    DuplicateConstant,
+    BreakOutsideLoop,
+    ContinueOutsideLoop,
+    PlaceholderOutsideModifier,
+    UnterminatedComment,
+    UnterminatedString,
+    InvalidStringLiteral,
+    InvalidNumber,
+    LimitExceeded(Limit),
+    InvalidEdit(InvalidEdit),
+}
+
+/// Limit from `ParserOptions` that has been exceeded.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Limit {
+    Depth,
+    Tokens,
+    SourceLength,
+}
+
+/// Reason a `TextEdit` passed to `reparse` can't be applied.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum InvalidEdit {
+    OutOfBounds,
+    Reversed,
+    CharBoundary,
+    Overlapping,
+}
+
+impl ErrorKind {
+    /// Stable code identifying the kind of the error.
+    pub fn code(&self) -> &'static str {
+        match *self {
+            ErrorKind::UnexpectedToken            => "E0001",
+            ErrorKind::UnexpectedEndOfProgram     => "E0002",
+            ErrorKind::MissingSemicolon           => "E0003",
+            ErrorKind::DuplicateVisibility        => "E0004",
+            ErrorKind::DuplicateMutability        => "E0005",
+            ErrorKind::DuplicateConstant          => "E0006",
+            ErrorKind::BreakOutsideLoop           => "E0007",
+            ErrorKind::ContinueOutsideLoop        => "E0008",
+            ErrorKind::UnterminatedComment        => "E0009",
+            ErrorKind::UnterminatedString         => "E0010",
+            ErrorKind::InvalidStringLiteral       => "E0011",
+            ErrorKind::InvalidNumber              => "E0012",
+            ErrorKind::LimitExceeded(_)           => "E0013",
+            ErrorKind::PlaceholderOutsideModifier => "E0014",
+            ErrorKind::InvalidEdit(_)             => "E0015",
+        }
+    }
+}
+
+impl Error {
+    #[inline]
+    pub fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+
+    /// Human readable description of the error, without the location.
+    pub fn message(&self) -> String {
+        match self.kind {
+            ErrorKind::UnexpectedToken            => format!("unexpected {}", self.found()),
+            ErrorKind::UnexpectedEndOfProgram     => "unexpected end of file".into(),
+            ErrorKind::MissingSemicolon           => format!("missing `;`, found {}", self.found()),
+            ErrorKind::DuplicateVisibility        => format!("visibility is already declared, found another `{}`", self.raw),
+            ErrorKind::DuplicateMutability        => format!("state mutability is already declared, found another `{}`", self.raw),
+            ErrorKind::DuplicateConstant          => "`constant` is already declared".into(),
+            ErrorKind::BreakOutsideLoop           => "`break` outside of a loop".into(),
+            ErrorKind::ContinueOutsideLoop        => "`continue` outside of a loop".into(),
+            ErrorKind::PlaceholderOutsideModifier => "placeholder `_` outside of a modifier".into(),
+            ErrorKind::UnterminatedComment        => "unterminated block comment".into(),
+            ErrorKind::UnterminatedString         => "unterminated string literal".into(),
+            ErrorKind::InvalidStringLiteral if self.raw.starts_with('\\') => {
+                format!("invalid escape sequence `{}` in string literal", self.raw)
+            },
+            ErrorKind::InvalidStringLiteral       => "non-ASCII character in string literal, use `unicode\"...\"` instead".into(),
+            ErrorKind::InvalidNumber              => format!("invalid number literal `{}`", self.raw),
+            ErrorKind::LimitExceeded(Limit::Depth)        => "maximum nesting depth exceeded".into(),
+            ErrorKind::LimitExceeded(Limit::Tokens)       => "maximum number of tokens exceeded".into(),
+            ErrorKind::LimitExceeded(Limit::SourceLength) => "source exceeds the maximum length".into(),
+            ErrorKind::InvalidEdit(InvalidEdit::OutOfBounds)  => "edit is out of bounds of the source".into(),
+            ErrorKind::InvalidEdit(InvalidEdit::Reversed)     => "edit ends before it starts".into(),
+            ErrorKind::InvalidEdit(InvalidEdit::CharBoundary) => "edit is not on a character boundary".into(),
+            ErrorKind::InvalidEdit(InvalidEdit::Overlapping)  => "edit overlaps another edit".into(),
+        }
+    }
+
+    /// Same as `message`, followed by the expected tokens if there are any.
+    pub fn summary(&self) -> String {
+        let mut summary = self.message();
+
+        // Expected `;` is already part of the message
+        if self.kind == ErrorKind::MissingSemicolon {
+            return summary;
+        }
+
+        // Tokens beginning a whole construct are too many to list
+        if let Some(construct) = self.expected_construct() {
+            summary.push_str(", expected ");
+            summary.push_str(construct);
+
+            return summary;
+        }
+
+        for (index, token) in self.expected.iter().enumerate() {
+            summary.push_str(match index {
+                0 if self.expected.len() == 1 => ", expected ",
+                0                             => ", expected one of ",
+                _                             => ", ",
+            });
+            summary.push_str(token.describe());
+        }
+
+        summary
+    }
+
+    /// Name of the construct the expected tokens begin, if they are
+    /// all of the tokens that can begin it.
+    fn expected_construct(&self) -> Option<&'static str> {
+        let expected = &*self.expected;
+
+        if expected == EXPRESSION {
+            Some("expression")
+        } else if expected == STATEMENT {
+            Some("statement")
+        } else if expected == TYPE_NAME {
+            Some("type name")
+        } else {
+            None
+        }
+    }
+
+    fn found(&self) -> String {
+        match self.token {
+            Token::EndOfProgram | Token::UnexpectedEndOfProgram => self.token.describe().into(),
+            _ => format!("`{}`", self.raw),
+        }
+    }
 }
 
 impl Debug for Error {
@@ -16,3 +182,184 @@ impl Debug for Error {
         write!(f, "Unexpected {:?}({}) at {}:{}", &self.token, &*self.raw, self.span.start, self.span.end)
     }
 }
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "error[{}]: {}", self.code(), self.summary())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use parse;
+
+    fn errors(source: &str) -> Vec<Error> {
+        match parse(source) {
+            Ok(_)       => panic!("Expected {:?} to fail", source),
+            Err(errors) => errors,
+        }
+    }
+
+    fn first(source: &str) -> Error {
+        errors(source).remove(0)
+    }
+
+    #[test]
+    fn unexpected_token() {
+        let error = first("contract Foo { function() { 1 + ; } }");
+
+        assert_eq!(error.kind, ErrorKind::UnexpectedToken);
+        assert_eq!(error.code(), "E0001");
+        assert_eq!(error.to_string(), "error[E0001]: unexpected `;`");
+    }
+
+    #[test]
+    fn expected_tokens() {
+        let error = first("contract Foo { struct Bar { uint a; } uint }");
+
+        assert_eq!(error.expected, vec![Token::Identifier]);
+        assert_eq!(error.to_string(), "error[E0001]: unexpected `}`, expected identifier");
+
+        let error = first("contract Foo {} }");
+
+        assert_eq!(error.expected, vec![Token::KeywordPragma, Token::KeywordImport, Token::DeclarationContract]);
+        assert_eq!(error.to_string(), "error[E0001]: unexpected `}`, expected one of `pragma`, `import`, `contract`");
+    }
+
+    #[test]
+    fn expected_constructs() {
+        let error = first("contract Foo { uint a = ; }");
+
+        assert_eq!(error.expected, EXPRESSION);
+        assert_eq!(error.to_string(), "error[E0001]: unexpected `;`, expected expression");
+
+        let error = first("contract Foo { function() { if (true) {} else } }");
+
+        assert_eq!(error.to_string(), "error[E0001]: unexpected `}`, expected statement");
+
+        let error = first("contract Foo { event Bar(uint a, ); }");
+
+        assert_eq!(error.to_string(), "error[E0001]: unexpected `)`, expected type name");
+
+        let error = first("contract Foo { function() { assembly \"evmasm\" ; } }");
+
+        assert_eq!(error.expected, vec![Token::BraceOpen]);
+    }
+
+    #[test]
+    fn unexpected_end_of_program() {
+        let error = first("contract Foo {");
+
+        assert_eq!(error.kind, ErrorKind::UnexpectedEndOfProgram);
+        assert_eq!(error.to_string(), "error[E0002]: unexpected end of file, expected `}`");
+    }
+
+    #[test]
+    fn missing_semicolon() {
+        let source = "contract Foo { uint a = 1 uint b; }";
+        let error = first(source);
+
+        assert_eq!(error.kind, ErrorKind::MissingSemicolon);
+        assert_eq!(error.expected, vec![Token::Semicolon]);
+        assert_eq!(&source[error.span.clone()], "uint");
+        assert_eq!(error.to_string(), "error[E0003]: missing `;`, found `uint`");
+    }
+
+    #[test]
+    fn duplicate_flags() {
+        let source = "contract Foo { function() public pure external view; }";
+        let errors = errors(source);
+
+        assert_eq!(errors[0].kind, ErrorKind::DuplicateVisibility);
+        assert_eq!(&source[errors[0].span.clone()], "external");
+        assert_eq!(errors[0].to_string(), "error[E0004]: visibility is already declared, found another `external`");
+        assert_eq!(&source[errors[0].labels[0].span.clone()], "public");
+        assert_eq!(errors[0].labels[0].message, "previous visibility declared here");
+        assert_eq!(errors[1].kind, ErrorKind::DuplicateMutability);
+        assert_eq!(&source[errors[1].span.clone()], "view");
+        assert_eq!(&source[errors[1].labels[0].span.clone()], "pure");
+
+        let error = first("contract Foo { uint constant constant FOO = 1; }");
+
+        assert_eq!(error.kind, ErrorKind::DuplicateConstant);
+    }
+
+    #[test]
+    fn break_and_continue_outside_loops() {
+        let source = "contract Foo { function() { break; continue; } }";
+        let errors = errors(source);
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].kind, ErrorKind::BreakOutsideLoop);
+        assert_eq!(&source[errors[0].span.clone()], "break");
+        assert_eq!(errors[1].kind, ErrorKind::ContinueOutsideLoop);
+        assert_eq!(errors[1].to_string(), "error[E0008]: `continue` outside of a loop");
+    }
+
+    #[test]
+    fn placeholder_outside_modifier() {
+        let source = "contract Foo { function() { while (true) { _; } } }";
+        let error = first(source);
+
+        assert_eq!(error.kind, ErrorKind::PlaceholderOutsideModifier);
+        assert_eq!(&source[error.span.clone()], "_");
+        assert_eq!(error.to_string(), "error[E0014]: placeholder `_` outside of a modifier");
+        assert!(parse("contract Foo { modifier bar() { while (true) { _; } } }").is_ok());
+    }
+
+    #[test]
+    fn unterminated_comment() {
+        let source = "contract Foo { /* doge }";
+        let error = first(source);
+
+        assert_eq!(error.kind, ErrorKind::UnterminatedComment);
+        assert_eq!(&source[error.span.clone()], "/* doge }");
+    }
+
+    #[test]
+    fn unterminated_string() {
+        let error = first("contract Foo { function() { \"doge; } }");
+
+        assert_eq!(error.kind, ErrorKind::UnterminatedString);
+        assert_eq!(error.to_string(), "error[E0010]: unterminated string literal");
+    }
+
+    #[test]
+    fn invalid_string_literal() {
+        let error = first(r#"contract Foo { function() { "doge\q"; } }"#);
+
+        assert_eq!(error.kind, ErrorKind::InvalidStringLiteral);
+        assert_eq!(error.to_string(), "error[E0011]: invalid escape sequence `\\q` in string literal");
+
+        let error = first("contract Foo { function() { \"döge\"; } }");
+
+        assert_eq!(error.kind, ErrorKind::InvalidStringLiteral);
+        assert_eq!(&*error.raw, "ö");
+    }
+
+    #[test]
+    fn invalid_string_literal_multibyte() {
+        let escapes = [
+            ("contract Foo { function() { \"\\é\"; } }", "\\é"),
+            ("contract Foo { function() { \"\\x1é\"; } }", "\\x1é"),
+            ("contract Foo { function() { \"\\u12€4\"; } }", "\\u12€"),
+            ("contract Foo { function() { unicode\"\\u€\"; } }", "\\u€"),
+        ];
+
+        for &(source, raw) in escapes.iter() {
+            let error = first(source);
+
+            assert_eq!(error.kind, ErrorKind::InvalidStringLiteral);
+            assert_eq!(&*error.raw, raw);
+        }
+    }
+
+    #[test]
+    fn invalid_number() {
+        let error = first("contract Foo { uint a = 1__000; }");
+
+        assert_eq!(error.kind, ErrorKind::InvalidNumber);
+        assert_eq!(error.to_string(), "error[E0012]: invalid number literal `1__000`");
+    }
+}
@@ -6,6 +6,36 @@ use lexer::{Token, Logos, lookup};
 
 type HandlerFn = for<'ast> fn(&mut Parser<'ast>) -> Option<ExpressionNode<'ast>>;
 
+/// Tokens that can begin an expression, see `EXPRESSION_LUT`.
+pub const EXPRESSION: &[Token] = &[
+    Token::KeywordThis,
+    Token::Identifier,
+    Token::IdentifierBuiltin,
+    Token::ParenOpen,
+    Token::OperatorLogicalNot,
+    Token::OperatorBitNot,
+    Token::KeywordDelete,
+    Token::OperatorIncrement,
+    Token::OperatorDecrement,
+    Token::OperatorAddition,
+    Token::OperatorSubtraction,
+    Token::LiteralTrue,
+    Token::LiteralFalse,
+    Token::LiteralHex,
+    Token::LiteralInteger,
+    Token::LiteralRational,
+    Token::LiteralString,
+    Token::TypeBool,
+    Token::TypeAddress,
+    Token::TypeString,
+    Token::TypeByte,
+    Token::TypeBytes,
+    Token::TypeInt,
+    Token::TypeUint,
+    Token::TypeFixed,
+    Token::TypeUfixed,
+];
+
 static EXPRESSION_LUT: [HandlerFn; Token::SIZE] = lookup! {
     Token::KeywordThis         => |par| par.node_at_token(ThisExpression),
     Token::Identifier          => |par| par.node_from_slice(|ident| ident),
@@ -21,8 +51,8 @@ static EXPRESSION_LUT: [HandlerFn; Token::SIZE] = lookup! {
     Token::LiteralTrue         => |par| par.node_at_token(Primitive::Bool(true)),
     Token::LiteralFalse        => |par| par.node_at_token(Primitive::Bool(false)),
     Token::LiteralHex          => |par| par.node_from_slice(|slice| Primitive::HexNumber(slice)),
-    Token::LiteralInteger      => |par| par.integer_number(),
-    Token::LiteralRational     => |par| par.node_from_slice(|slice| Primitive::RationalNumber(slice)),
+    Token::LiteralInteger      => |par| par.number(Primitive::IntegerNumber),
+    Token::LiteralRational     => |par| par.number(Primitive::RationalNumber),
     Token::LiteralString       => |par| par.node_from_slice(|slice| Primitive::String(slice)),
     Token::TypeBool            => |par| par.node_at_token(ElementaryTypeName::Bool),
     Token::TypeAddress         => |par| par.node_at_token(ElementaryTypeName::Address),
@@ -61,8 +91,10 @@ static EXPRESSION_LUT: [HandlerFn; Token::SIZE] = lookup! {
 impl<'ast> Parser<'ast> {
     #[inline]
     pub fn expression(&mut self, precedence: Precedence) -> Option<ExpressionNode<'ast>> {
-        EXPRESSION_LUT[self.lexer.token as usize](self)
-            .map(|expression| self.nested_expression(expression, precedence))
+        self.descend(|par| {
+            EXPRESSION_LUT[par.lexer.token as usize](par)
+                .map(|expression| par.nested_expression(expression, precedence))
+        })
     }
 
     #[inline]
@@ -75,7 +107,7 @@ impl<'ast> Parser<'ast> {
         while self.allow(Token::Comma) {
             match self.expression(TOP) {
                 Some(expression) => builder.push(self.arena, expression),
-                None             => self.error(),
+                None             => self.error_expected(EXPRESSION),
             }
         }
 
@@ -102,16 +134,20 @@ impl<'ast> Parser<'ast> {
         })
     }
 
-    fn integer_number(&mut self) -> Option<ExpressionNode<'ast>> {
+    fn number<F>(&mut self, primitive: F) -> Option<ExpressionNode<'ast>>
+    where
+        F: FnOnce(&'ast str, NumberUnit) -> Primitive<'ast>,
+    {
         let number = self.lexer.slice();
         let (start, end) = self.loc();
 
-        self.lexer.advance();
+        self.advance();
 
         let unit = match self.lexer.token {
             Token::UnitEther       => NumberUnit::Ether(EtherUnit::Ether),
             Token::UnitFinney      => NumberUnit::Ether(EtherUnit::Finney),
             Token::UnitSzabo       => NumberUnit::Ether(EtherUnit::Szabo),
+            Token::UnitGwei        => NumberUnit::Ether(EtherUnit::Gwei),
             Token::UnitWei         => NumberUnit::Ether(EtherUnit::Wei),
             Token::UnitTimeYears   => NumberUnit::Time(TimeUnit::Years),
             Token::UnitTimeWeeks   => NumberUnit::Time(TimeUnit::Weeks),
@@ -120,12 +156,12 @@ impl<'ast> Parser<'ast> {
             Token::UnitTimeMinutes => NumberUnit::Time(TimeUnit::Minutes),
             Token::UnitTimeSeconds => NumberUnit::Time(TimeUnit::Seconds),
 
-            _ => return self.node_at(start, end, Primitive::IntegerNumber(number, NumberUnit::None)),
+            _ => return self.node_at(start, end, primitive(number, NumberUnit::None)),
         };
 
         let end = self.end_then_advance();
 
-        self.node_at(start, end, Primitive::IntegerNumber(number, unit))
+        self.node_at(start, end, primitive(number, unit))
     }
 }
 
@@ -155,10 +191,12 @@ mod test {
 
         "#, [
             m.node(14, 286, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 272, FunctionDefinition {
+                        doc: None,
                         name: None,
                         params: NodeList::empty(),
                         visibility: None,
@@ -202,4 +240,47 @@ mod test {
             }),
         ]);
     }
+
+    #[test]
+    fn number_literals() {
+        let m = Mock::new();
+
+        assert_units(r#"
+
+            contract Foo {
+                function() {
+                    0.5 ether;
+                    1_000 gwei;
+                    2 days;
+                    0x10;
+                }
+            }
+
+        "#, [
+            m.node(14, 206, ContractDefinition {
+                doc: None,
+                name: m.node(23, 26, "Foo"),
+                inherits: NodeList::empty(),
+                body: m.list([
+                    m.node(45, 192, FunctionDefinition {
+                        doc: None,
+                        name: None,
+                        params: NodeList::empty(),
+                        visibility: None,
+                        mutability: None,
+                        modifiers: NodeList::empty(),
+                        returns: NodeList::empty(),
+                        block: m.node(56, 192, Block {
+                            body: m.list([
+                                m.stmt_expr(78, 87, 88, Primitive::RationalNumber("0.5", NumberUnit::Ether(EtherUnit::Ether))),
+                                m.stmt_expr(109, 119, 120, Primitive::IntegerNumber("1_000", NumberUnit::Ether(EtherUnit::Gwei))),
+                                m.stmt_expr(141, 147, 148, Primitive::IntegerNumber("2", NumberUnit::Time(TimeUnit::Days))),
+                                m.stmt_expr(169, 173, 174, Primitive::HexNumber("0x10")),
+                            ]),
+                        }),
+                    }),
+                ]),
+            }),
+        ]);
+    }
 }
@@ -0,0 +1,319 @@
+//! Opinionated formatting of Solidity source.
+//!
+//! The source is parsed and printed back with the `Printer`, so that the
+//! layout doesn't depend on how the source was written: one definition or
+//! statement per line, empty lines around functions, modifiers and structs,
+//! and attributes of functions in the order of visibility, mutability,
+//! modifiers and `returns`. Lists of parameters and arguments that exceed
+//! the line width are put one item per line, and the parameters and
+//! attributes of long function headers get a line each. Other long lines
+//! are kept as they are.
+//!
+//! All comments are kept. Comments between definitions or statements stay
+//! where they are, along with single empty lines, while comments within
+//! expressions or headers are moved before the next definition or statement.
+//!
+//! Only whitespace and comments are moved, the tokens are printed as they
+//! were written, so `uint` stays `uint` and `uint256` stays `uint256`.
+//!
+//! Formatting is idempotent, formatting the formatted source returns it
+//! unchanged.
+
+use error::Error;
+use parse;
+use printer::Printer;
+
+/// Placement of opening braces of contracts, functions and blocks.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BraceStyle {
+    /// `function f() {`
+    SameLine,
+
+    /// The opening brace on a line of its own.
+    NextLine,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FormatOptions {
+    /// Width of lines at which lists get broken into multiple lines.
+    pub max_width: usize,
+
+    /// Number of spaces per level of indentation, also the width of a tab.
+    pub tab_spaces: usize,
+
+    /// Indent with tabs instead of spaces.
+    pub hard_tabs: bool,
+
+    pub brace_style: BraceStyle,
+
+    /// Sort consecutive imports by the path of the imported file, and
+    /// symbols imported from a file by name.
+    pub sort_imports: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            max_width: 100,
+            tab_spaces: 4,
+            hard_tabs: false,
+            brace_style: BraceStyle::SameLine,
+            sort_imports: true,
+        }
+    }
+}
+
+/// Format the Solidity source, see module docs.
+pub fn format(source: &str, options: FormatOptions) -> Result<String, Vec<Error>> {
+    let program = parse(source)?;
+    let mut printer = Printer::with_source(source, options);
+
+    printer.program(&program);
+
+    Ok(printer.finish().expect("Parsed programs have no unprintable nodes"))
+}
+
+/// Check if the source is formatted, that is it wouldn't change by `format`.
+pub fn is_formatted(source: &str, options: FormatOptions) -> Result<bool, Vec<Error>> {
+    format(source, options).map(|formatted| formatted == source)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cst::{leaves, LeafKind};
+    use ast::*;
+    use mock::remove_spans;
+
+    fn comments(source: &str) -> Vec<&str> {
+        leaves(source)
+            .into_iter()
+            .filter(|leaf| matches!(leaf.kind, LeafKind::LineComment | LeafKind::BlockComment))
+            .map(|leaf| leaf.text.trim_end())
+            .collect()
+    }
+
+    /// Source units of the AST without locations, with imports sorted the
+    /// way `sort_imports` sorts them if it's set.
+    fn units(program: &Program, options: FormatOptions) -> Vec<String> {
+        let mut units: Vec<_> = program.body().iter().cloned().collect();
+
+        if options.sort_imports {
+            for run in units.split_mut(|unit| !matches!(unit.value, SourceUnit::ImportDirective(_))) {
+                run.sort_by_key(|unit| match unit.value {
+                    SourceUnit::ImportDirective(ImportDirective::Global { source, .. })   |
+                    SourceUnit::ImportDirective(ImportDirective::From { source, .. })     |
+                    SourceUnit::ImportDirective(ImportDirective::ManyFrom { source, .. }) => source.value,
+                    _                                                                      => "",
+                });
+            }
+        }
+
+        units.iter().map(|unit| match unit.value {
+            SourceUnit::ImportDirective(ImportDirective::ManyFrom { imports, source }) if options.sort_imports => {
+                let mut imports: Vec<_> = imports.iter().cloned().collect();
+
+                imports.sort_by_key(|import| import.value.symbol.value);
+
+                remove_spans(&format!("ManyFrom {:?} from {:?}", imports, source))
+            },
+            _ => remove_spans(&format!("{:?}", unit)),
+        }).collect()
+    }
+
+    /// Format the source, check that it's idempotent and that neither the AST
+    /// nor the comments change, and return the formatted source.
+    fn assert_format(source: &str, options: FormatOptions) -> String {
+        let formatted = match format(source, options) {
+            Ok(formatted) => formatted,
+            Err(errors)   => panic!("Formatting failed: {:?}", errors),
+        };
+
+        let program = parse(source).unwrap();
+        let reformatted = match parse(&formatted) {
+            Ok(program) => program,
+            Err(errors) => panic!("Formatted source doesn't parse: {:?}\n{}", errors, formatted),
+        };
+
+        assert_eq!(units(&program, options), units(&reformatted, options), "{}", formatted);
+
+        let mut before = comments(source);
+        let mut after = comments(&formatted);
+
+        before.sort();
+        after.sort();
+
+        assert_eq!(before, after, "{}", formatted);
+        assert_eq!(format(&formatted, options).unwrap(), formatted);
+        assert!(is_formatted(&formatted, options).unwrap());
+
+        formatted
+    }
+
+    const CONTRACT: &str = r#"
+        pragma solidity ^0.4.17;
+        import "./b.sol";
+        import { z, a as b } from "./a.sol";
+
+
+        /// @title Wow
+        contract Doge is Shiba, Inu { // such contract
+            uint public constant x=1 ether; // much constant
+            /* many */ mapping(address=>uint) balances;
+
+            event Moon(address indexed a, uint b);
+            struct Coin { uint value; /* very */ address owner; }
+            enum Color { Red, Green }
+
+            function f(uint a) public view only(a) always returns (uint) {
+                // such comment
+                uint b = a + /* inner */ 2;
+
+                if (a > 2) { return a; } else return b;
+
+
+                assembly { b := add(a, b) } // wow
+            }
+
+            function() payable {}
+            // the end
+        }
+        // really the end
+    "#;
+
+    #[test]
+    fn format_contract() {
+        let formatted = assert_format(CONTRACT, FormatOptions::default());
+
+        assert_eq!(formatted, r#"pragma solidity ^0.4.17;
+import { a as b, z } from "./a.sol";
+import "./b.sol";
+
+/// @title Wow
+contract Doge is Shiba, Inu {
+    // such contract
+    uint public constant x = 1 ether; // much constant
+    /* many */
+    mapping(address => uint) balances;
+
+    event Moon(address indexed a, uint b);
+
+    struct Coin {
+        uint value; /* very */
+        address owner;
+    }
+
+    enum Color { Red, Green }
+
+    function f(uint a) public view only(a) always returns (uint) {
+        // such comment
+        uint b = a + 2;
+        /* inner */
+
+        if (a > 2) {
+            return a;
+        } else return b;
+
+        assembly {
+            b := add(a, b)
+        } // wow
+    }
+
+    function() payable {}
+    // the end
+}
+// really the end
+"#);
+    }
+
+    #[test]
+    fn brace_style_and_indentation() {
+        let options = FormatOptions {
+            hard_tabs: true,
+            brace_style: BraceStyle::NextLine,
+            sort_imports: false,
+            ..FormatOptions::default()
+        };
+
+        let formatted = assert_format(r#"
+            contract Foo {
+                function foo(uint a) { if (a > 1) { a; } else { } do { a--; } while (a > 0); }
+                function bar() {}
+            }
+        "#, options);
+
+        assert_eq!(formatted, "contract Foo\n{\n\tfunction foo(uint a)\n\t{\n\t\tif (a > 1)\n\t\t{\n\t\t\ta;\n\t\t}\n\t\telse {}\n\t\tdo\n\t\t{\n\t\t\ta--;\n\t\t}\n\t\twhile (a > 0);\n\t}\n\n\tfunction bar() {}\n}\n");
+    }
+
+    #[test]
+    fn line_width() {
+        let options = FormatOptions {
+            max_width: 40,
+            ..FormatOptions::default()
+        };
+
+        let formatted = assert_format(r#"
+            contract Foo {
+                enum Things { Alpha, Beta, Gamma, Delta, Epsilon }
+                event Transfer(address indexed sender, address indexed receiver);
+                function transfer(address to, uint value) public returns (bool) {
+                    log(msg.sender, to, value);
+                    return doTransfer(msg.sender, to, add(value, 1));
+                }
+            }
+        "#, options);
+
+        assert_eq!(formatted, r#"contract Foo {
+    enum Things {
+        Alpha,
+        Beta,
+        Gamma,
+        Delta,
+        Epsilon
+    }
+
+    event Transfer(
+        address indexed sender,
+        address indexed receiver
+    );
+
+    function transfer(
+        address to,
+        uint value
+    )
+        public
+        returns (bool)
+    {
+        log(msg.sender, to, value);
+        return doTransfer(
+            msg.sender,
+            to,
+            add(value, 1)
+        );
+    }
+}
+"#);
+    }
+
+    #[test]
+    fn second_price_auction() {
+        let source = include_str!("../../lunarity/benches/second-price-auction.sol");
+
+        for &brace_style in &[BraceStyle::SameLine, BraceStyle::NextLine] {
+            for &max_width in &[60, 100, 120] {
+                assert_format(source, FormatOptions {
+                    max_width,
+                    brace_style,
+                    ..FormatOptions::default()
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn check() {
+        assert_eq!(is_formatted("contract Foo {}\n", FormatOptions::default()), Ok(true));
+        assert_eq!(is_formatted("contract Foo { }", FormatOptions::default()), Ok(false));
+        assert!(is_formatted("contract {", FormatOptions::default()).is_err());
+    }
+}
@@ -1,11 +1,13 @@
 use toolshed::list::{ListBuilder, GrowableList};
 
 use ast::*;
-use {Parser, FunctionContext, RegularTypeNameContext};
+use {Parser, ErrorKind, FunctionContext, RegularTypeNameContext};
+use type_name::TYPE_NAME;
 use lexer::Token;
 
 impl<'ast> Parser<'ast> {
     pub fn function_definition(&mut self) -> Option<ContractPartNode<'ast>> {
+        let doc   = self.doc_comment();
         let start = self.start_then_advance();
 
         let name = match self.lexer.token {
@@ -25,15 +27,15 @@ impl<'ast> Parser<'ast> {
 
         loop {
             match self.lexer.token {
-                Token::KeywordExternal => self.unique_flag(&mut visibility, FunctionVisibility::External),
-                Token::KeywordPublic   => self.unique_flag(&mut visibility, FunctionVisibility::Public),
-                Token::KeywordInternal => self.unique_flag(&mut visibility, FunctionVisibility::Internal),
-                Token::KeywordPrivate  => self.unique_flag(&mut visibility, FunctionVisibility::Private),
+                Token::KeywordExternal => self.unique_flag(&mut visibility, FunctionVisibility::External, ErrorKind::DuplicateVisibility),
+                Token::KeywordPublic   => self.unique_flag(&mut visibility, FunctionVisibility::Public, ErrorKind::DuplicateVisibility),
+                Token::KeywordInternal => self.unique_flag(&mut visibility, FunctionVisibility::Internal, ErrorKind::DuplicateVisibility),
+                Token::KeywordPrivate  => self.unique_flag(&mut visibility, FunctionVisibility::Private, ErrorKind::DuplicateVisibility),
 
-                Token::KeywordPure     => self.unique_flag(&mut mutability, StateMutability::Pure),
-                Token::KeywordConstant => self.unique_flag(&mut mutability, StateMutability::Constant),
-                Token::KeywordView     => self.unique_flag(&mut mutability, StateMutability::View),
-                Token::KeywordPayable  => self.unique_flag(&mut mutability, StateMutability::Payable),
+                Token::KeywordPure     => self.unique_flag(&mut mutability, StateMutability::Pure, ErrorKind::DuplicateMutability),
+                Token::KeywordConstant => self.unique_flag(&mut mutability, StateMutability::Constant, ErrorKind::DuplicateMutability),
+                Token::KeywordView     => self.unique_flag(&mut mutability, StateMutability::View, ErrorKind::DuplicateMutability),
+                Token::KeywordPayable  => self.unique_flag(&mut mutability, StateMutability::Payable, ErrorKind::DuplicateMutability),
 
                 _ => match self.modifier_invocation() {
                     Some(modifier) => modifiers.push(self.arena, modifier),
@@ -65,6 +67,7 @@ impl<'ast> Parser<'ast> {
         };
 
         self.node_at(start, end, FunctionDefinition {
+            doc,
             name,
             params,
             visibility,
@@ -103,7 +106,7 @@ impl<'ast> Parser<'ast> {
                 while self.allow(Token::Comma) {
                     match self.parameter() {
                         Some(param) => builder.push(self.arena, param),
-                        None        => self.error(),
+                        None        => self.error_expected(TYPE_NAME),
                     }
                 }
 
@@ -144,10 +147,12 @@ mod test {
 
         "#, [
             m.node(14, 102, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 56, FunctionDefinition {
+                        doc: None,
                         name: None,
                         params: NodeList::empty(),
                         visibility: None,
@@ -157,6 +162,7 @@ mod test {
                         block: None,
                     }),
                     m.node(73, 88, FunctionDefinition {
+                        doc: None,
                         name: m.node(82, 85, "bar"),
                         params: NodeList::empty(),
                         visibility: None,
@@ -182,10 +188,12 @@ mod test {
 
         "#, [
             m.node(14, 82, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 68, FunctionDefinition {
+                        doc: None,
                         name: None,
                         params: m.list([
                             m.node(54, 60, Parameter {
@@ -220,10 +228,12 @@ mod test {
 
         "#, [
             m.node(14, 96, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 82, FunctionDefinition {
+                        doc: None,
                         name: m.node(54, 58, "doge"),
                         params: m.list([
                             m.node(59, 69, Parameter {
@@ -258,10 +268,12 @@ mod test {
 
         "#, [
             m.node(14, 98, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 84, FunctionDefinition {
+                        doc: None,
                         name: m.node(54, 58, "doge"),
                         params: NodeList::empty(),
                         visibility: None,
@@ -299,10 +311,12 @@ mod test {
 
         "#, [
             m.node(14, 217, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 74, FunctionDefinition {
+                        doc: None,
                         name: m.node(54, 57, "wow"),
                         params: NodeList::empty(),
                         visibility: m.node(65, 73, FunctionVisibility::External),
@@ -312,6 +326,7 @@ mod test {
                         block: None,
                     }),
                     m.node(91, 121, FunctionDefinition {
+                        doc: None,
                         name: m.node(100, 104, "such"),
                         params: NodeList::empty(),
                         visibility: m.node(107, 115, FunctionVisibility::Internal),
@@ -321,6 +336,7 @@ mod test {
                         block: None,
                     }),
                     m.node(138, 162, FunctionDefinition {
+                        doc: None,
                         name: m.node(147, 151, "very"),
                         params: NodeList::empty(),
                         visibility: m.node(154, 161, FunctionVisibility::Private),
@@ -330,6 +346,7 @@ mod test {
                         block: None,
                     }),
                     m.node(179, 203, FunctionDefinition {
+                        doc: None,
                         name: m.node(188, 192, "much"),
                         params: NodeList::empty(),
                         visibility: None,
@@ -355,10 +372,12 @@ mod test {
 
         "#, [
             m.node(14, 96, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 82, FunctionDefinition {
+                        doc: None,
                         name: None,
                         params: NodeList::empty(),
                         visibility: None,
@@ -397,10 +416,12 @@ mod test {
 
         "#, [
             m.node(14, 102, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 88, FunctionDefinition {
+                        doc: None,
                         name: None,
                         params: NodeList::empty(),
                         visibility: None,
@@ -430,10 +451,16 @@ mod test {
     fn function_flags_are_unique_per_kind() {
         use parse;
 
-        // TODO: Better errors
-        assert!(parse("contract Foo { function() public public; }").is_err());
-        assert!(parse("contract Foo { function() pure pure; }").is_err());
-        assert!(parse("contract Foo { function() internal external; }").is_err());
-        assert!(parse("contract Foo { function() payable constant; }").is_err());
+        fn kind(source: &str) -> ErrorKind {
+            match parse(source) {
+                Ok(_)       => panic!("Duplicate flags must not parse"),
+                Err(errors) => errors[0].kind,
+            }
+        }
+
+        assert_eq!(kind("contract Foo { function() public public; }"), ErrorKind::DuplicateVisibility);
+        assert_eq!(kind("contract Foo { function() pure pure; }"), ErrorKind::DuplicateMutability);
+        assert_eq!(kind("contract Foo { function() internal external; }"), ErrorKind::DuplicateVisibility);
+        assert_eq!(kind("contract Foo { function() payable constant; }"), ErrorKind::DuplicateMutability);
     }
 }
@@ -0,0 +1,761 @@
+use std::ops::Range;
+
+use toolshed::Arena;
+
+use ast::*;
+use ast::visit_mut::{self, VisitMut};
+use lexer::Token;
+use {parse_at_offset, Error, ErrorKind, InvalidEdit, Parser, ParserOptions, FunctionContext, ModifierContext};
+
+/// Replacement of a byte range of the source with new text, see `reparse`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+impl TextEdit {
+    pub fn new<S: Into<String>>(range: Range<usize>, text: S) -> Self {
+        TextEdit {
+            range,
+            text: text.into(),
+        }
+    }
+}
+
+/// Apply the edits to the source of the `Program` and parse it again, see
+/// `reparse_with_options`.
+pub fn reparse<'ast>(program: Program<'ast>, edits: &[TextEdit]) -> (Program<'ast>, Vec<Error>) {
+    reparse_with_options(program, edits, ParserOptions::default())
+}
+
+/// Apply the edits to the source of the `Program` and parse it again,
+/// recovering from errors the same as `parse_partial`. Only the innermost
+/// source unit, contract part, or statement of a function or modifier body,
+/// containing all the edits is parsed, the rest of the AST is kept, with the
+/// locations of the nodes following the edits shifted.
+///
+/// Ranges of the edits are byte ranges of the current source, and can't
+/// overlap. If any of them is invalid, the `Program` is returned as it is,
+/// along with an `InvalidEdit` error. The result is the same as parsing the
+/// edited source from scratch, provided the `Program` was parsed with the
+/// same options. A `Program` parsed with errors is always parsed again from
+/// scratch, so that the errors in the rest of the source are reported again.
+///
+/// Replaced nodes are left on the `Arena` of the `Program`, so the memory it
+/// uses grows with every reparse, until the source is parsed from scratch.
+pub fn reparse_with_options<'ast>(program: Program<'ast>, edits: &[TextEdit], options: ParserOptions) -> (Program<'ast>, Vec<Error>) {
+    if edits.is_empty() {
+        return (program, Vec::new());
+    }
+
+    let offset = program.offset();
+    let previous = program.source();
+    let (source, start, end) = match apply(previous, edits) {
+        Ok(applied) => applied,
+        Err((invalid, range)) => {
+            let error = invalid_edit(previous, offset, invalid, range);
+
+            return (program, vec![error]);
+        },
+    };
+    let edit = Edit {
+        start: offset + start as u32,
+        end: offset + end as u32,
+        delta: source.len() as i64 - previous.len() as i64,
+    };
+
+    // Tokens are only counted when parsing from scratch
+    if !program.is_partial() && options.max_tokens == usize::MAX && source.len() <= options.max_source_len {
+        let (body, arena) = program.into_parts();
+
+        let reparsed = {
+            let list: SourceUnitList = unsafe { body.into_list() };
+
+            candidates(list, previous, offset, &edit)
+                .iter()
+                .rev()
+                .filter_map(|candidate| candidate.reparse(&arena, &source, offset, &edit, options))
+                .next()
+                .map(|replaced| {
+                    let mut shift = Shift {
+                        arena: &arena,
+                        edit: &edit,
+                        replaced,
+                    };
+
+                    for unit in list.iter() {
+                        shift.visit_source_unit(unit);
+                    }
+
+                    arena.alloc_str(&source) as *const str
+                })
+        };
+
+        if let Some(source) = reparsed {
+            return (Program::with_source(body, arena, unsafe { &*source }, offset), Vec::new());
+        }
+    }
+
+    parse_at_offset(&source, offset, options)
+}
+
+/// Apply the edits, returning the edited source along with the range
+/// of the previous source they replace, or the first invalid edit.
+fn apply(source: &str, edits: &[TextEdit]) -> Result<(String, usize, usize), (InvalidEdit, Range<usize>)> {
+    let mut edits: Vec<_> = edits.iter().collect();
+
+    edits.sort_by_key(|edit| edit.range.start);
+
+    let mut edited = String::with_capacity(source.len());
+    let mut last = 0;
+
+    for edit in &edits {
+        if let Some(invalid) = check(source, &edit.range, last) {
+            return Err((invalid, edit.range.clone()));
+        }
+
+        edited.push_str(&source[last..edit.range.start]);
+        edited.push_str(&edit.text);
+
+        last = edit.range.end;
+    }
+
+    edited.push_str(&source[last..]);
+
+    Ok((edited, edits[0].range.start, last))
+}
+
+/// Check the range of an edit, given the end of the edit before it.
+fn check(source: &str, range: &Range<usize>, last: usize) -> Option<InvalidEdit> {
+    if range.start > range.end {
+        Some(InvalidEdit::Reversed)
+    } else if range.end > source.len() {
+        Some(InvalidEdit::OutOfBounds)
+    } else if !source.is_char_boundary(range.start) || !source.is_char_boundary(range.end) {
+        Some(InvalidEdit::CharBoundary)
+    } else if range.start < last {
+        Some(InvalidEdit::Overlapping)
+    } else {
+        None
+    }
+}
+
+/// Error for an invalid edit, pointing at the part of its range within the source.
+fn invalid_edit(source: &str, offset: u32, invalid: InvalidEdit, range: Range<usize>) -> Error {
+    let clamp = |index: usize| {
+        let mut index = index.min(source.len());
+
+        while !source.is_char_boundary(index) {
+            index -= 1;
+        }
+
+        index
+    };
+
+    let start = clamp(range.start);
+    let end = clamp(range.end).max(start);
+    let offset = offset as usize;
+
+    Error {
+        kind: ErrorKind::InvalidEdit(invalid),
+        token: Token::EndOfProgram,
+        raw: source[start..end].into(),
+        span: start + offset..end + offset,
+        expected: Vec::new(),
+        labels: Vec::new(),
+    }
+}
+
+/// Range of the previous source replaced by the edits, as locations,
+/// along with the change of length of the source.
+struct Edit {
+    start: u32,
+    end: u32,
+    delta: i64,
+}
+
+impl Edit {
+    #[inline]
+    fn shift(&self, location: u32) -> u32 {
+        (location as i64 + self.delta) as u32
+    }
+
+    /// Check if the item spanning from the end of the item before it
+    /// to `end` contains the edit. Edits touching the last token of the
+    /// item might change where the item ends, so they don't count.
+    #[inline]
+    fn within(&self, start: u32, end: u32) -> bool {
+        start <= self.start && self.end < end
+    }
+}
+
+/// Item of a list that can be parsed on its own, as it's done when
+/// parsing the whole list.
+enum Item<'a> {
+    SourceUnit(&'a SourceUnitNode<'a>),
+    ContractPart(&'a ContractPartNode<'a>),
+    FunctionStatement(&'a StatementNode<'a>),
+    ModifierStatement(&'a StatementNode<'a>),
+}
+
+struct Candidate<'a> {
+    item: Item<'a>,
+
+    /// End of the item before it, or of the token opening the list,
+    /// where the parser would begin parsing this item.
+    start: u32,
+
+    /// Nesting depth the parser would begin parsing this item at.
+    depth: usize,
+}
+
+impl<'a> Candidate<'a> {
+    /// Parse the edited source of the item, and if it parses to a single
+    /// item without errors, put it in place of the item and return its address.
+    fn reparse(&self, arena: &'a Arena, source: &str, offset: u32, edit: &Edit, options: ParserOptions) -> Option<usize> {
+        let end = edit.shift(match self.item {
+            Item::SourceUnit(unit)                 => unit.end,
+            Item::ContractPart(part)               => part.end,
+            Item::FunctionStatement(statement)     |
+            Item::ModifierStatement(statement)     => statement.end,
+        });
+
+        let slice = &source[(self.start - offset) as usize..(end - offset) as usize];
+        let mut parser = Parser::at_offset(slice, self.start, arena, options);
+
+        parser.depth = self.depth;
+
+        match self.item {
+            Item::SourceUnit(unit) => {
+                let parsed = parser.source_unit();
+
+                replace(unit, parsed, &parser, end)
+            },
+            Item::ContractPart(part) => {
+                let parsed = parser.contract_part();
+
+                replace(part, parsed, &parser, end)
+            },
+            Item::FunctionStatement(statement) => {
+                let parsed = parser.statement::<FunctionContext>();
+
+                replace(statement, parsed, &parser, end)
+            },
+            Item::ModifierStatement(statement) => {
+                let parsed = parser.statement::<ModifierContext>();
+
+                replace(statement, parsed, &parser, end)
+            },
+        }
+    }
+}
+
+fn replace<'a, T>(node: &Node<'a, T>, parsed: Option<Node<'a, T>>, parser: &Parser<'a>, end: u32) -> Option<usize> {
+    let parsed = parsed?;
+
+    if parsed.end != end || parser.lexer.token != Token::EndOfProgram || !parser.errors.is_empty() {
+        return None;
+    }
+
+    let replaced = address(&parsed);
+
+    node.replace(parsed);
+
+    Some(replaced)
+}
+
+/// Get the items containing the edit, outermost first.
+fn candidates<'a>(body: SourceUnitList<'a>, source: &str, offset: u32, edit: &Edit) -> Vec<Candidate<'a>> {
+    let mut candidates = Vec::new();
+
+    nested_candidates(&mut candidates, body, source, offset, edit);
+
+    candidates
+}
+
+fn nested_candidates<'a>(
+    candidates: &mut Vec<Candidate<'a>>,
+    body: SourceUnitList<'a>,
+    source: &str,
+    offset: u32,
+    edit: &Edit,
+) -> Option<()> {
+    let (unit, start) = containing(body, offset, edit)?;
+
+    // Source units, contract parts and the blocks of functions and modifiers
+    // don't add to the depth, only the statements within the blocks do
+    candidates.push(Candidate { item: Item::SourceUnit(unit), start, depth: 0 });
+
+    let contract = match unit.value {
+        SourceUnit::ContractDefinition(ref contract) => contract,
+        _ => return None,
+    };
+
+    let before = contract.inherits.iter().last().unwrap_or(&contract.name).end;
+    let brace = brace_end(source, (before - offset) as usize)? as u32 + offset;
+    let (part, start) = containing(contract.body, brace, edit)?;
+
+    candidates.push(Candidate { item: Item::ContractPart(part), start, depth: 0 });
+
+    let (block, modifier) = match part.value {
+        ContractPart::FunctionDefinition(ref def) => (def.block?, false),
+        ContractPart::ModifierDefinition(ref def) => (def.block, true),
+        _ => return None,
+    };
+
+    let (statement, start) = containing(block.value.body, block.start + 1, edit)?;
+
+    candidates.push(Candidate {
+        item: match modifier {
+            true  => Item::ModifierStatement(statement),
+            false => Item::FunctionStatement(statement),
+        },
+        start,
+        depth: 0,
+    });
+
+    Some(())
+}
+
+/// Find the item of the list containing the edit, along with the end of the
+/// item before it, given the end of the token opening the list.
+fn containing<'a, T>(list: NodeList<'a, T>, mut start: u32, edit: &Edit) -> Option<(&'a Node<'a, T>, u32)> {
+    for node in list.iter() {
+        if edit.within(start, node.end) {
+            return Some((node, start));
+        }
+
+        if node.end > edit.start {
+            return None;
+        }
+
+        start = node.end;
+    }
+
+    None
+}
+
+/// Find the end of the `{` opening the body of a contract, given the end of
+/// the token before it. There can only be whitespace and comments between.
+fn brace_end(source: &str, from: usize) -> Option<usize> {
+    let mut rest = source.get(from..)?;
+
+    loop {
+        rest = rest.trim_start();
+
+        if rest.starts_with("//") {
+            rest = rest.find('\n').map_or("", |newline| &rest[newline..]);
+        } else if rest.starts_with("/*") {
+            rest = &rest[rest[2..].find("*/")? + 4..];
+        } else if rest.starts_with('{') {
+            return Some(source.len() - rest.len() + 1);
+        } else {
+            return None;
+        }
+    }
+}
+
+#[inline]
+fn address<T>(node: &Node<T>) -> usize {
+    &**node as *const NodeInner<T> as usize
+}
+
+/// Moves the nodes following the edit, and extends the nodes containing it.
+struct Shift<'a, 'e> {
+    arena: &'a Arena,
+    edit: &'e Edit,
+
+    /// Address of the reparsed node, which is already in place.
+    replaced: usize,
+}
+
+impl<'a, 'e> Shift<'a, 'e> {
+    /// Shift the node, returns `false` if its children need no shifting.
+    fn shift<T: Copy>(&self, node: &Node<'a, T>) -> bool {
+        if node.end <= self.edit.start || address(node) == self.replaced {
+            return false;
+        }
+
+        let start = match node.start >= self.edit.end {
+            true  => self.edit.shift(node.start),
+            false => node.start,
+        };
+
+        node.set(self.arena.alloc(NodeInner::new(start, self.edit.shift(node.end), node.value)));
+
+        true
+    }
+}
+
+impl<'a, 'e> VisitMut<'a> for Shift<'a, 'e> {
+    fn visit_source_unit(&mut self, unit: &SourceUnitNode<'a>) {
+        if self.shift(unit) {
+            visit_mut::walk_source_unit(self, unit)
+        }
+    }
+
+    fn visit_import(&mut self, import: &Node<'a, Import<'a>>) {
+        if self.shift(import) {
+            visit_mut::walk_import(self, import)
+        }
+    }
+
+    fn visit_contract_part(&mut self, part: &ContractPartNode<'a>) {
+        if self.shift(part) {
+            visit_mut::walk_contract_part(self, part)
+        }
+    }
+
+    fn visit_indexed_parameter(&mut self, param: &Node<'a, IndexedParameter<'a>>) {
+        if self.shift(param) {
+            visit_mut::walk_indexed_parameter(self, param)
+        }
+    }
+
+    fn visit_parameter(&mut self, param: &Node<'a, Parameter<'a>>) {
+        if self.shift(param) {
+            visit_mut::walk_parameter(self, param)
+        }
+    }
+
+    fn visit_modifier_invocation(&mut self, modifier: &Node<'a, ModifierInvocation<'a>>) {
+        if self.shift(modifier) {
+            visit_mut::walk_modifier_invocation(self, modifier)
+        }
+    }
+
+    fn visit_doc_comment(&mut self, doc: &DocCommentNode<'a>) {
+        if self.shift(doc) {
+            visit_mut::walk_doc_comment(self, doc)
+        }
+    }
+
+    fn visit_doc_tag(&mut self, tag: &DocTagNode<'a>) {
+        self.shift(tag);
+    }
+
+    fn visit_type_name(&mut self, type_name: &TypeNameNode<'a>) {
+        if self.shift(type_name) {
+            visit_mut::walk_type_name(self, type_name)
+        }
+    }
+
+    fn visit_variable_declaration(&mut self, declaration: &VariableDeclarationNode<'a>) {
+        if self.shift(declaration) {
+            visit_mut::walk_variable_declaration(self, declaration)
+        }
+    }
+
+    fn visit_block(&mut self, block: &BlockNode<'a>) {
+        if self.shift(block) {
+            visit_mut::walk_block(self, block)
+        }
+    }
+
+    fn visit_statement(&mut self, statement: &StatementNode<'a>) {
+        if self.shift(statement) {
+            visit_mut::walk_statement(self, statement)
+        }
+    }
+
+    fn visit_simple_statement(&mut self, statement: &SimpleStatementNode<'a>) {
+        if self.shift(statement) {
+            visit_mut::walk_simple_statement(self, statement)
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &ExpressionNode<'a>) {
+        if self.shift(expression) {
+            visit_mut::walk_expression(self, expression)
+        }
+    }
+
+    fn visit_inline_assembly_block(&mut self, block: &InlineAssemblyBlockNode<'a>) {
+        if self.shift(block) {
+            visit_mut::walk_inline_assembly_block(self, block)
+        }
+    }
+
+    fn visit_assembly_item(&mut self, item: &AssemblyItemNode<'a>) {
+        if self.shift(item) {
+            visit_mut::walk_assembly_item(self, item)
+        }
+    }
+
+    fn visit_functional_assembly_expression(&mut self, expr: &FunctionalAssemblyExpressionNode<'a>) {
+        if self.shift(expr) {
+            visit_mut::walk_functional_assembly_expression(self, expr)
+        }
+    }
+
+    fn visit_identifier(&mut self, identifier: &IdentifierNode<'a>) {
+        self.shift(identifier);
+    }
+
+    fn visit_string_literal(&mut self, string: &StringLiteralNode<'a>) {
+        self.shift(string);
+    }
+
+    fn visit_flag(&mut self, flag: &FlagNode<'a>) {
+        self.shift(flag);
+    }
+
+    fn visit_elementary_type_name(&mut self, type_name: &ElementaryTypeNameNode<'a>) {
+        self.shift(type_name);
+    }
+
+    fn visit_state_variable_visibility(&mut self, visibility: &Node<'a, StateVariableVisibility>) {
+        self.shift(visibility);
+    }
+
+    fn visit_function_visibility(&mut self, visibility: &Node<'a, FunctionVisibility>) {
+        self.shift(visibility);
+    }
+
+    fn visit_state_mutability(&mut self, mutability: &Node<'a, StateMutability>) {
+        self.shift(mutability);
+    }
+
+    fn visit_storage_location(&mut self, location: &Node<'a, StorageLocation>) {
+        self.shift(location);
+    }
+
+    fn visit_prefix_operator(&mut self, operator: &Node<'a, PrefixOperator>) {
+        self.shift(operator);
+    }
+
+    fn visit_postfix_operator(&mut self, operator: &Node<'a, PostfixOperator>) {
+        self.shift(operator);
+    }
+
+    fn visit_binary_operator(&mut self, operator: &Node<'a, BinaryOperator>) {
+        self.shift(operator);
+    }
+
+    fn visit_assignment_operator(&mut self, operator: &Node<'a, AssignmentOperator>) {
+        self.shift(operator);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use {parse, parse_with_options, parse_partial, parse_file};
+
+    const SOURCE: &str = "pragma solidity ^0.4.17;
+
+contract Foo is Bar {
+    uint constant x = 1;
+
+    /// @notice Does things
+    function a(uint b) public returns (uint) {
+        uint c = b * 2;
+        return c + x;
+    }
+
+    modifier only() {
+        require(msg.sender == owner);
+        _;
+    }
+
+    event E(uint indexed a);
+}
+
+contract Baz {}
+";
+
+    fn edit(range: Range<usize>, text: &str) -> TextEdit {
+        TextEdit::new(range, text)
+    }
+
+    fn at(pattern: &str) -> Range<usize> {
+        let start = SOURCE.find(pattern).unwrap();
+
+        start..start + pattern.len()
+    }
+
+    /// Reparse the edits and compare with a full parse, returning the
+    /// reparsed program along with the original one.
+    fn check<'ast>(edits: &[TextEdit], options: ParserOptions) -> Program<'ast> {
+        let program = parse_with_options(SOURCE, options).unwrap();
+        let (edited, _, _) = apply(SOURCE, edits).unwrap();
+        let expected = parse_with_options(&edited, options).unwrap();
+        let (reparsed, errors) = reparse_with_options(program, edits, options);
+
+        assert_eq!(errors, vec![]);
+        assert_eq!(reparsed.source(), edited);
+        assert_eq!(reparsed.body(), expected.body());
+
+        reparsed
+    }
+
+    fn contract_parts<'ast>(program: &Program<'ast>) -> Vec<ContractPartNode<'ast>> {
+        match program.body().iter().nth(1).unwrap().value {
+            SourceUnit::ContractDefinition(ref contract) => contract.body.iter().cloned().collect(),
+            _ => panic!("Expected a contract"),
+        }
+    }
+
+    #[test]
+    fn reparses_only_the_statement() {
+        let program = parse(SOURCE).unwrap();
+        let before: Vec<_> = contract_parts(&program).iter().map(address).collect();
+        let (reparsed, _) = reparse(program, &[edit(at("b * 2"), "b * 200 + 1")]);
+        let after: Vec<_> = contract_parts(&reparsed).iter().map(address).collect();
+
+        // Parts before the edit are kept as they are, the rest is shifted
+        assert_eq!(before[0], after[0]);
+        assert!(before[1] != after[1] && before[2] != after[2]);
+
+        check(&[edit(at("b * 2"), "b * 200 + 1")], ParserOptions::default());
+    }
+
+    #[test]
+    fn statements_and_parts() {
+        let options = ParserOptions::default();
+
+        check(&[edit(at("return c + x;"), "if (c > 0) { return c; }")], options);
+        check(&[edit(at("_;"), "_;\n        _;")], options);
+        check(&[edit(at("uint b"), "uint b, bool d")], options);
+        check(&[edit(at("indexed a"), "a")], options);
+        check(&[edit(at("x = 1"), "x = 0x10"), edit(at("c + x"), "c - x")], options);
+        check(&[edit(at("contract Baz {}"), "contract Baz is Foo {}")], options);
+    }
+
+    #[test]
+    fn edits_across_items() {
+        let options = ParserOptions::default();
+
+        // New parts and statements are parsed along with the list
+        check(&[edit(at("        return c + x;").start..at("        return c + x;").start, "        c++;\n")], options);
+        check(&[edit(at("\n\n    event").start..at("\n\n    event").start, "\n\n    function b() {}")], options);
+
+        // Edits joining or splitting items
+        check(&[edit(at("2;\n        return c"), "2 + c")], options);
+        check(&[edit(at("uint constant x = 1;\n"), "")], options);
+        check(&[edit(0..0, "pragma solidity ^0.4.18;\n")], options);
+    }
+
+    #[test]
+    fn doc_comments() {
+        let options = ParserOptions {
+            doc_comments: true,
+            ..ParserOptions::default()
+        };
+
+        check(&[edit(at("Does things"), "Does other things")], options);
+        check(&[edit(at("/// @notice Does things"), "/* not a doc */")], options);
+        check(&[edit(at("    modifier"), "    /// @dev Modifies\n    modifier")], options);
+        check(&[edit(at("is Bar {"), "is Bar /* { */ {")], options);
+    }
+
+    #[test]
+    fn errors_and_offsets() {
+        let program = parse(SOURCE).unwrap();
+        let (edited, _, _) = apply(SOURCE, &[edit(at("c + x;"), "c + ;")]).unwrap();
+        let (reparsed, errors) = reparse(program, &[edit(at("c + x;"), "c + ;")]);
+        let (expected, expected_errors) = parse_partial(&edited, ParserOptions::default());
+
+        assert_eq!(reparsed.body(), expected.body());
+        assert_eq!(errors, expected_errors);
+
+        let mut files = SourceFiles::new();
+
+        files.add("a.sol", "contract A {}");
+
+        let id = files.add("b.sol", SOURCE);
+        let program = parse_file(&files, id).unwrap();
+        let (reparsed, _) = reparse(program, &[edit(at("b * 2"), "b")]);
+        let (edited, _, _) = apply(SOURCE, &[edit(at("b * 2"), "b")]).unwrap();
+        let mut files = SourceFiles::new();
+
+        files.add("a.sol", "contract A {}");
+
+        let id = files.add("b.sol", edited);
+
+        assert_eq!(reparsed.body(), parse_file(&files, id).unwrap().body());
+    }
+
+    #[test]
+    fn edits_after_errors() {
+        let options = ParserOptions::default();
+        let broken = [edit(at("c + x;"), "c + ;")];
+        let (program, errors) = reparse(parse(SOURCE).unwrap(), &broken);
+
+        assert!(program.is_partial());
+        assert_eq!(errors.len(), 1);
+
+        // An edit that parses on its own must not lose the error before it
+        let (edited, _, _) = apply(SOURCE, &broken).unwrap();
+        let (reparsed, errors) = reparse(program, &[edit(at("b * 2"), "b * 3")]);
+        let (edited, _, _) = apply(&edited, &[edit(at("b * 2"), "b * 3")]).unwrap();
+        let (expected, expected_errors) = parse_partial(&edited, options);
+
+        assert_eq!(reparsed.body(), expected.body());
+        assert_eq!(errors, expected_errors);
+        assert_eq!(errors.len(), 1);
+
+        // Fixing the error gets the program parsed in full again
+        let start = edited.find("c + ;").unwrap();
+        let fixed = [edit(start..start + 5, "c + x;")];
+        let (reparsed, errors) = reparse(reparsed, &fixed);
+
+        assert_eq!(errors, vec![]);
+        assert!(!reparsed.is_partial());
+
+        check(&[edit(at("b * 2"), "b * 3")], options);
+    }
+
+    #[test]
+    fn nesting_limit() {
+        let edits = [edit(at("c + x"), "((c + x))")];
+        let (edited, _, _) = apply(SOURCE, &edits).unwrap();
+        let mut exceeded = false;
+
+        for max_depth in 1..16 {
+            let options = ParserOptions {
+                max_depth,
+                ..ParserOptions::default()
+            };
+
+            let program = match parse_with_options(SOURCE, options) {
+                Ok(program) => program,
+                Err(_)      => continue,
+            };
+
+            let (reparsed, errors) = reparse_with_options(program, &edits, options);
+            let (expected, expected_errors) = parse_partial(&edited, options);
+
+            assert_eq!(reparsed.body(), expected.body());
+            assert_eq!(errors, expected_errors);
+
+            exceeded |= !errors.is_empty();
+        }
+
+        assert!(exceeded);
+    }
+
+    #[test]
+    fn invalid_edits() {
+        let source = "contract Foo { /* ü */ }";
+        let invalid = |edits: &[TextEdit]| {
+            let (program, errors) = reparse(parse(source).unwrap(), edits);
+
+            assert_eq!(program.source(), source);
+            assert_eq!(program.body(), parse(source).unwrap().body());
+
+            errors.iter().map(|error| (error.kind, error.span.clone())).collect::<Vec<_>>()
+        };
+
+        assert_eq!(invalid(&[edit(Range { start: 20, end: 10 }, "")]), vec![(ErrorKind::InvalidEdit(InvalidEdit::Reversed), 20..20)]);
+        assert_eq!(invalid(&[edit(22..40, "")]), vec![(ErrorKind::InvalidEdit(InvalidEdit::OutOfBounds), 22..25)]);
+        assert_eq!(invalid(&[edit(19..20, "u")]), vec![(ErrorKind::InvalidEdit(InvalidEdit::CharBoundary), 18..20)]);
+        assert_eq!(invalid(&[edit(15..21, ""), edit(9..18, "")]), vec![(ErrorKind::InvalidEdit(InvalidEdit::Overlapping), 15..21)]);
+        assert_eq!(invalid(&[edit(0..0, "")]), vec![]);
+
+        let (_, errors) = reparse(parse(source).unwrap(), &[edit(Range { start: 20, end: 10 }, "")]);
+
+        assert_eq!(errors[0].to_string(), "error[E0015]: edit ends before it starts");
+    }
+}
@@ -19,6 +19,16 @@ mod nested;
 mod statement;
 mod assembly;
 mod error;
+mod doc;
+mod cst;
+mod recover;
+mod diagnostic;
+mod printer;
+mod format;
+mod arena;
+mod incremental;
+
+use std::ops::Range;
 
 use toolshed::{Arena, NulTermStr};
 use toolshed::list::GrowableList;
@@ -26,42 +36,183 @@ use toolshed::list::GrowableList;
 pub use self::statement::{StatementContext, FunctionContext, ModifierContext};
 pub use self::type_name::{TypeNameContext, RegularTypeNameContext, StatementTypeNameContext};
 pub use self::nested::*;
+pub use self::cst::{SyntaxTree, SyntaxNode, SyntaxElement, SyntaxLeaf, LeafKind, parse_lossless};
 
 use ast::*;
-use error::Error;
+pub use self::error::{Error, ErrorKind, Label, Limit, InvalidEdit};
+pub use self::diagnostic::Renderer;
+pub use self::printer::{print, Printer, PrintError};
+pub use self::format::{format, is_formatted, FormatOptions, BraceStyle};
+pub use self::arena::ReusableArena;
+pub use self::incremental::{reparse, reparse_with_options, TextEdit};
 use lexer::{Lexer, Token};
 use lexer::Token::*;
 
 
+/// Options for the `Parser`, the defaults produce the leanest AST. Only the
+/// nesting depth is limited by default, to keep the parser from overflowing
+/// the stack. Set the other limits when parsing untrusted sources.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParserOptions {
+    /// Attach NatSpec comments to the definitions they precede.
+    pub doc_comments: bool,
+
+    /// Maximum nesting depth of expressions, statements, type names
+    /// and inline assembly.
+    pub max_depth: usize,
+
+    /// Maximum number of tokens in the source.
+    pub max_tokens: usize,
+
+    /// Maximum length of the source in bytes.
+    pub max_source_len: usize,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        ParserOptions {
+            doc_comments: false,
+            max_depth: 256,
+            max_tokens: usize::MAX,
+            max_source_len: usize::MAX,
+        }
+    }
+}
+
 pub struct Parser<'ast> {
     arena: &'ast Arena,
 
+    options: ParserOptions,
+
     /// Lexer will produce tokens from the source
     lexer: Lexer<NulTermStr<'ast>>,
 
+    /// Offset of the source in the space of all files of `SourceFiles`,
+    /// added to all locations produced by the parser
+    offset: u32,
+
     /// Errors occurred during parsing
     errors: Vec<Error>,
 
     /// AST under construction
     body: SourceUnitList<'ast>,
+
+    /// End of the last definition, doc comments are looked up from here
+    doc_start: u32,
+
+    /// Set after a syntax error, further syntax errors are not reported
+    /// until the parser gets back in sync at a `;` or a `}`
+    recovering: bool,
+
+    /// Set after a syntax error within the list item (source unit,
+    /// contract part or statement) that is currently being parsed
+    broken: bool,
+
+    /// Current nesting depth, see `ParserOptions::max_depth`
+    depth: usize,
+
+    /// Number of tokens read so far, excluding the end of the program
+    tokens: usize,
+
+    /// Set once a limit has been exceeded, the parser then behaves as if
+    /// it has reached the end of the source
+    halted: bool,
 }
 
 impl<'ast> Parser<'ast> {
     pub fn new(source: &str, arena: &'ast Arena) -> Self {
-        let source = arena.alloc_nul_term_str(source);
+        Parser::with_options(source, arena, ParserOptions::default())
+    }
 
-        Parser {
+    pub fn with_options(source: &str, arena: &'ast Arena, options: ParserOptions) -> Self {
+        Parser::at_offset(source, 0, arena, options)
+    }
+
+    /// Create a parser for a source that begins at `offset`, see `parse_file`.
+    pub fn at_offset(source: &str, offset: u32, arena: &'ast Arena, options: ParserOptions) -> Self {
+        let too_large = source.len() > options.max_source_len;
+
+        // Don't bother copying a source that won't be parsed
+        let source = arena.alloc_nul_term_str(if too_large { "" } else { source });
+
+        let mut parser = Parser {
             arena,
+            options,
             lexer: Lexer::new(source),
+            offset,
             errors: Vec::new(),
             body: NodeList::empty(),
+            doc_start: offset,
+            recovering: false,
+            broken: false,
+            depth: 0,
+            tokens: 1,
+            halted: false,
+        };
+
+        if too_large {
+            parser.halt(Limit::SourceLength);
         }
+
+        parser
+    }
+
+    #[inline]
+    fn advance(&mut self) {
+        if self.halted {
+            return;
+        }
+
+        self.lexer.advance();
+
+        if self.lexer.token != EndOfProgram {
+            self.tokens += 1;
+
+            if self.tokens > self.options.max_tokens {
+                self.halt(Limit::Tokens);
+            }
+        }
+    }
+
+    /// Parse a construct that can contain itself, halting the parser
+    /// instead if it's nested too deep.
+    #[inline]
+    fn descend<T, F>(&mut self, parse: F) -> Option<T>
+    where
+        F: FnOnce(&mut Self) -> Option<T>,
+    {
+        if self.depth >= self.options.max_depth {
+            self.halt(Limit::Depth);
+
+            return None;
+        }
+
+        self.depth += 1;
+
+        let result = parse(self);
+
+        self.depth -= 1;
+
+        result
+    }
+
+    /// Report the exceeded limit and stop parsing, the current token is
+    /// replaced with the end of the program to unwind the parser.
+    fn halt(&mut self, limit: Limit) {
+        if !self.halted {
+            self.error_kind(ErrorKind::LimitExceeded(limit));
+        }
+
+        self.halted     = true;
+        self.recovering = true;
+        self.broken     = true;
+        self.lexer.token = EndOfProgram;
     }
 
     #[inline]
     fn allow(&mut self, token: Token) -> bool {
         if self.lexer.token == token {
-            self.lexer.advance();
+            self.advance();
             true
         } else {
             false
@@ -71,24 +222,28 @@ impl<'ast> Parser<'ast> {
     #[inline]
     fn expect(&mut self, token: Token) {
         if self.lexer.token == token {
-            self.lexer.advance();
+            if token == Semicolon || token == BraceClose {
+                self.recovering = false;
+            }
+
+            self.advance();
         } else {
-            self.error();
+            self.error_expected(&[token]);
         }
     }
 
     #[inline]
     fn expect_exact(&mut self, token: Token, expected: &str) {
         if self.lexer.token == token && self.lexer.slice() == expected {
-            self.lexer.advance();
+            self.advance();
         } else {
-            self.error();
+            self.error_expected(&[token]);
         }
     }
 
     #[inline]
     fn expect_end(&mut self, token: Token) -> u32 {
-        let end = self.lexer.range().end as u32;
+        let end = self.token_end();
 
         self.expect(token);
 
@@ -135,39 +290,116 @@ impl<'ast> Parser<'ast> {
 
     #[inline]
     fn loc(&mut self) -> (u32, u32) {
-        let range = self.lexer.range();
+        (self.token_start(), self.token_end())
+    }
 
-        (range.start as u32, range.end as u32)
+    /// Source being parsed, as a pointer, so that it can outlive the borrow
+    /// of the `Arena` it's allocated on when put into a `Program`.
+    #[inline]
+    fn source(&self) -> *const str {
+        *self.lexer.source
+    }
+
+    #[inline]
+    fn token_start(&self) -> u32 {
+        self.offset + self.lexer.range().start as u32
+    }
+
+    #[inline]
+    fn token_end(&self) -> u32 {
+        self.offset + self.lexer.range().end as u32
     }
 
     #[inline]
     fn start_then_advance(&mut self) -> u32 {
-        let start = self.lexer.range().start as u32;
+        let start = self.token_start();
 
-        self.lexer.advance();
+        self.advance();
 
         start
     }
 
     #[inline]
     fn end_then_advance(&mut self) -> u32 {
-        let end = self.lexer.range().end as u32;
+        let end = self.token_end();
 
-        self.lexer.advance();
+        self.advance();
 
         end
     }
 
+    #[inline]
     fn error(&mut self) {
-        let token = self.lexer.token;
-        let raw   = self.lexer.slice().into();
-        let span  = self.lexer.range();
+        self.error_expected(&[]);
+    }
+
+    fn error_expected(&mut self, expected: &[Token]) {
+        self.broken = true;
+
+        if self.recovering || self.halted {
+            return;
+        }
+
+        self.recovering = true;
+
+        let token    = self.lexer.token;
+        let slice    = self.lexer.slice();
+        let mut span = self.lexer.range();
+
+        let kind = match token {
+            UnexpectedToken => {
+                let bytes = slice.as_bytes();
+
+                if let Some(invalid) = lexer::string_literal_error(bytes) {
+                    // Point directly at the invalid part of a malformed string literal
+                    span = span.start + invalid.start..span.start + invalid.end;
+
+                    ErrorKind::InvalidStringLiteral
+                } else if bytes.starts_with(b"\"") || bytes.starts_with(b"'") || bytes.starts_with(b"unicode\"") || bytes.starts_with(b"unicode'") {
+                    ErrorKind::UnterminatedString
+                } else if matches!(bytes.first(), Some(byte) if byte.is_ascii_digit()) {
+                    ErrorKind::InvalidNumber
+                } else {
+                    ErrorKind::UnexpectedToken
+                }
+            },
+            UnexpectedEndOfProgram if slice.starts_with("/*") => ErrorKind::UnterminatedComment,
+            UnexpectedEndOfProgram | EndOfProgram => ErrorKind::UnexpectedEndOfProgram,
+            _ if expected == [Semicolon] => ErrorKind::MissingSemicolon,
+            _ => ErrorKind::UnexpectedToken,
+        };
+
+        // Expected tokens are irrelevant if the source can't be tokenized
+        let expected = match kind {
+            ErrorKind::UnexpectedToken | ErrorKind::UnexpectedEndOfProgram | ErrorKind::MissingSemicolon => expected,
+            _ => &[],
+        };
+
+        self.push_error(kind, span, expected);
+    }
+
+    #[inline]
+    fn error_kind(&mut self, kind: ErrorKind) -> &mut Error {
+        let span = self.lexer.range();
+
+        self.push_error(kind, span, &[])
+    }
+
+    /// Push an error at the `span` within the source being parsed.
+    fn push_error(&mut self, kind: ErrorKind, span: Range<usize>, expected: &[Token]) -> &mut Error {
+        let source: &str = *self.lexer.source;
+        let offset = self.offset as usize;
 
         self.errors.push(Error {
-            token,
-            raw,
-            span,
+            kind,
+            token: self.lexer.token,
+            raw: source[span.clone()].into(),
+            span: span.start + offset..span.end + offset,
+            expected: expected.to_vec(),
+            labels: Vec::new(),
         });
+
+        self.errors.last_mut().expect("Error was just pushed")
     }
 
     #[inline]
@@ -197,7 +429,7 @@ impl<'ast> Parser<'ast> {
     {
         let (start, end) = self.loc();
 
-        self.lexer.advance();
+        self.advance();
 
         self.node_at(start, end, item)
     }
@@ -213,7 +445,7 @@ impl<'ast> Parser<'ast> {
         let slice = self.lexer.slice();
         let (start, end) = self.loc();
 
-        self.lexer.advance();
+        self.advance();
 
         self.node_at(start, end, func(slice))
     }
@@ -222,7 +454,19 @@ impl<'ast> Parser<'ast> {
     fn parse(&mut self) {
         let builder = GrowableList::new();
 
-        while let Some(unit) = self.source_unit() {
+        loop {
+            let start = self.start_item();
+
+            let unit = match self.source_unit() {
+                Some(unit) => unit,
+                None => match self.lexer.token {
+                    EndOfProgram | UnexpectedEndOfProgram => break,
+                    _ => self.recover_source_unit(start),
+                },
+            };
+
+            self.doc_start = unit.end;
+
             builder.push(self.arena, unit);
         }
 
@@ -232,15 +476,23 @@ impl<'ast> Parser<'ast> {
     }
 
     #[inline]
-    fn unique_flag<F>(&mut self, at: &mut Option<Node<'ast, F>>, flag: F)
+    fn unique_flag<F>(&mut self, at: &mut Option<Node<'ast, F>>, flag: F, duplicate: ErrorKind)
     where
         F: Copy,
     {
-        if at.is_some() {
-            self.lexer.advance();
-
-            // TODO: More descriptive errors, something like "Can't redeclare visibility/mutability"
-            return self.error();
+        if let Some(previous) = *at {
+            let message = match duplicate {
+                ErrorKind::DuplicateVisibility => "previous visibility declared here",
+                ErrorKind::DuplicateMutability => "previous state mutability declared here",
+                _                              => "first declared here",
+            };
+
+            self.error_kind(duplicate).labels.push(Label {
+                span: previous.start as usize..previous.end as usize,
+                message,
+            });
+
+            return self.advance();
         }
 
         *at = self.node_at_token(flag);
@@ -249,22 +501,89 @@ impl<'ast> Parser<'ast> {
 
 /// Parse the Solidity source from `&str` and produce an Abstract Syntax Tree for it.
 pub fn parse<'src, 'ast>(source: &'src str) -> Result<Program<'ast>, Vec<Error>> {
+    parse_with_options(source, ParserOptions::default())
+}
+
+/// Same as `parse`, with non-default `ParserOptions`.
+pub fn parse_with_options<'ast>(source: &str, options: ParserOptions) -> Result<Program<'ast>, Vec<Error>> {
+    let (program, errors) = parse_partial(source, options);
+
+    match errors.len() {
+        0 => Ok(program),
+        _ => Err(errors)
+    }
+}
+
+/// Parse a file of `SourceFiles`. Locations in the produced AST and in the
+/// errors are offsets in the space of all the files, which `SourceFiles`
+/// resolves back to the file.
+pub fn parse_file<'ast>(files: &SourceFiles, id: FileId) -> Result<Program<'ast>, Vec<Error>> {
+    parse_file_with_options(files, id, ParserOptions::default())
+}
+
+/// Same as `parse_file`, with non-default `ParserOptions`.
+pub fn parse_file_with_options<'ast>(files: &SourceFiles, id: FileId, options: ParserOptions) -> Result<Program<'ast>, Vec<Error>> {
+    let file = files.get(id);
+    let (program, errors) = parse_at_offset(file.source(), file.start(), options);
+
+    match errors.len() {
+        0 => Ok(program),
+        _ => Err(errors)
+    }
+}
+
+/// Parse the Solidity source, recovering from errors. The produced `Program`
+/// is always complete, with any source that failed to parse replaced by
+/// `Error` nodes, and is returned along with all the errors encountered.
+pub fn parse_partial<'ast>(source: &str, options: ParserOptions) -> (Program<'ast>, Vec<Error>) {
+    parse_at_offset(source, 0, options)
+}
+
+/// Same as `parse_partial`, for a source that begins at `offset`.
+fn parse_at_offset<'ast>(source: &str, offset: u32, options: ParserOptions) -> (Program<'ast>, Vec<Error>) {
     let arena = Arena::new();
 
-    let (body, errors) = {
-        let mut parser = Parser::new(source, &arena);
+    let (body, source, errors) = {
+        let mut parser = Parser::at_offset(source, offset, &arena, options);
 
         parser.parse();
 
-        (parser.body.into_unsafe(), parser.errors)
+        (parser.body.into_unsafe(), parser.source(), parser.errors)
     };
 
-    match errors.len() {
-        0 => Ok(Program::new(body, arena)),
-        _ => Err(errors)
+    let mut program = Program::with_source(body, arena, unsafe { &*source }, offset);
+
+    program.set_partial(!errors.is_empty());
+
+    (program, errors)
+}
+
+/// Parse the Solidity source onto the `Arena` instead of a new one owned by
+/// the `Program`. The source is copied onto the arena, so the produced AST
+/// only borrows the arena, see `ReusableArena` for reusing it.
+pub fn parse_in<'ast>(source: &str, arena: &'ast Arena) -> Result<SourceUnitList<'ast>, Vec<Error>> {
+    parse_in_with_options(source, arena, ParserOptions::default())
+}
+
+/// Same as `parse_in`, with non-default `ParserOptions`.
+pub fn parse_in_with_options<'ast>(source: &str, arena: &'ast Arena, options: ParserOptions) -> Result<SourceUnitList<'ast>, Vec<Error>> {
+    let mut parser = Parser::with_options(source, arena, options);
+
+    parser.parse();
+
+    match parser.errors.len() {
+        0 => Ok(parser.body),
+        _ => Err(parser.errors)
     }
 }
 
+/// Same as `parse`, taking ownership of the source. The `NulTermStr` the
+/// lexer needs can only be made by copying, so the source is copied onto
+/// the `Arena` just like with `parse`, and dropped once it's parsed.
+pub fn parse_owned<'ast>(source: String) -> Result<Program<'ast>, Vec<Error>> {
+    parse(&source)
+}
+
 
 #[cfg(test)]
 mod test {
@@ -276,4 +595,335 @@ mod test {
 
         parse(source).unwrap();
     }
+
+    #[test]
+    fn invalid_string_escape_error_span() {
+        let source = r#"contract Foo { function() { "doge\q"; } }"#;
+
+        let errors = match parse(source) {
+            Ok(_)       => panic!("Invalid escape sequence must not parse"),
+            Err(errors) => errors,
+        };
+
+        assert_eq!(errors[0].token, UnexpectedToken);
+        assert_eq!(&source[errors[0].span.clone()], "\\q");
+    }
+
+    fn limit_errors(source: &str, options: ParserOptions) -> Vec<Error> {
+        let (_, errors) = parse_partial(source, options);
+
+        assert_eq!(errors.len(), 1, "Expected a single error, got {:?}", errors);
+
+        errors
+    }
+
+    fn nested(open: &str, close: &str) -> String {
+        format!("contract Foo {{ function() {{ {}{} }} }}", open.repeat(100_000), close.repeat(100_000))
+    }
+
+    #[test]
+    fn adversarial_nesting() {
+        let sources = [
+            nested("(", ")"),
+            nested("a[", "]"),
+            nested("{", "}"),
+            nested("!", "a;"),
+            nested("a = ", "a;"),
+            nested("a ? b : ", "c;"),
+            nested("foo(", ");"),
+            nested("if (a) ", "b;"),
+            nested("while (a) ", ";"),
+            nested("mapping(uint => ", "uint) a;"),
+            format!("contract Foo {{ function() {{ assembly {{ {}{} }} }} }}", "{ ".repeat(100_000), "} ".repeat(100_000)),
+            format!("contract Foo {{ mapping(uint => {}uint{} a; }}", "mapping(uint => ".repeat(100_000), ")".repeat(100_000)),
+        ];
+
+        for source in sources.iter() {
+            let errors = limit_errors(source, ParserOptions::default());
+
+            assert_eq!(errors[0].kind, ErrorKind::LimitExceeded(Limit::Depth));
+        }
+    }
+
+    #[test]
+    fn nesting_within_limits() {
+        let options = ParserOptions {
+            max_depth: 64,
+            ..ParserOptions::default()
+        };
+
+        // The statement and the innermost expression take up a level each
+        let source = format!("contract Foo {{ function() {{ {}a{}; }} }}", "(".repeat(62), ")".repeat(62));
+
+        assert!(parse_with_options(&source, options).is_ok());
+
+        let source = format!("contract Foo {{ function() {{ {}a{}; }} }}", "(".repeat(63), ")".repeat(63));
+        let errors = limit_errors(&source, options);
+
+        assert_eq!(errors[0].kind, ErrorKind::LimitExceeded(Limit::Depth));
+        assert_eq!(&source[errors[0].span.clone()], "a");
+        assert_eq!(errors[0].to_string(), "error[E0013]: maximum nesting depth exceeded");
+    }
+
+    #[test]
+    fn token_limit() {
+        let source = "contract Foo { uint a = 1; uint b = 2; }";
+        let options = ParserOptions {
+            max_tokens: 10,
+            ..ParserOptions::default()
+        };
+
+        let errors = limit_errors(source, options);
+
+        assert_eq!(errors[0].kind, ErrorKind::LimitExceeded(Limit::Tokens));
+        assert_eq!(&source[errors[0].span.clone()], "=");
+
+        let options = ParserOptions {
+            max_tokens: 14,
+            ..ParserOptions::default()
+        };
+
+        assert!(parse_with_options(source, options).is_ok());
+
+        let source = "contract Foo { function() { a = 1; } }".repeat(100_000);
+        let options = ParserOptions {
+            max_tokens: 1_000,
+            ..ParserOptions::default()
+        };
+
+        assert_eq!(limit_errors(&source, options)[0].kind, ErrorKind::LimitExceeded(Limit::Tokens));
+    }
+
+    #[test]
+    fn source_length_limit() {
+        let source = "contract Foo {}";
+        let options = ParserOptions {
+            max_source_len: source.len() - 1,
+            ..ParserOptions::default()
+        };
+
+        let (program, errors) = parse_partial(source, options);
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, ErrorKind::LimitExceeded(Limit::SourceLength));
+        assert_eq!(program.body().iter().count(), 0);
+
+        let options = ParserOptions {
+            max_source_len: source.len(),
+            ..ParserOptions::default()
+        };
+
+        assert!(parse_with_options(source, options).is_ok());
+    }
+
+    #[test]
+    fn parse_files() {
+        let mut files = SourceFiles::new();
+        let source = "contract Foo {\n    /// Doge\n    function wow() {}\n}";
+
+        files.add("a.sol", source);
+
+        let b = files.add("b.sol", source);
+        let options = ParserOptions {
+            doc_comments: true,
+            ..ParserOptions::default()
+        };
+
+        let single = parse_with_options(source, options).unwrap();
+        let program = parse_file_with_options(&files, b, options).unwrap();
+        let offset = files.get(b).start();
+
+        let shift = |(start, end): (u32, u32)| (start + offset, end + offset);
+        let spans = |program: &Program| {
+            let index = AstIndex::new(program);
+            let spans: Vec<_> = index.iter().map(|(_, entry)| (entry.start, entry.end)).collect();
+
+            spans
+        };
+
+        assert_eq!(spans(&program), spans(&single).into_iter().map(shift).collect::<Vec<_>>());
+
+        let function = AstIndex::new(&program).iter()
+            .find(|&(_, entry)| entry.kind == "FunctionDefinition")
+            .map(|(_, entry)| entry.start)
+            .unwrap();
+
+        assert_eq!(files.display(function).unwrap().to_string(), "b.sol:3:5");
+    }
+
+    #[test]
+    fn index_positions() {
+        let source = "contract Foo { uint constant a = 1; function bar() { { a; } assembly { x := add(a, x) } } }";
+        let program = parse(source).unwrap();
+        let index = AstIndex::new(&program);
+
+        let kinds: Vec<_> = index.iter().map(|(_, entry)| entry.kind).collect();
+
+        assert_eq!(kinds, [
+            "ContractDefinition", "Identifier",
+            "StateVariableDeclaration", "ElementaryTypeName", "Flag", "Identifier", "PrimitiveExpression",
+            "FunctionDefinition", "Identifier", "Block",
+            "BlockStatement", "ExpressionStatement", "IdentifierExpression",
+            "InlineAssemblyStatement", "InlineAssemblyBlock",
+            "AssemblyAssignment", "Identifier", "FunctionalAssemblyExpression", "Identifier", "Identifier", "Identifier",
+        ]);
+
+        for offset in 0..source.len() as u32 + 1 {
+            let innermost = index.iter()
+                .filter(|&(_, entry)| entry.start <= offset && offset < entry.end)
+                .last()
+                .map(|(id, _)| id);
+
+            assert_eq!(index.node_at(offset), innermost);
+        }
+    }
+
+    #[test]
+    fn parse_file_errors() {
+        let mut files = SourceFiles::new();
+
+        files.add("a.sol", "contract Foo {}");
+
+        let b = files.add("b.sol", "contract Bar {\n    function() public external;\n}");
+        let errors = match parse_file(&files, b) {
+            Ok(_)       => panic!("Duplicate visibility must not parse"),
+            Err(errors) => errors,
+        };
+
+        assert_eq!(files.resolve(errors[0].span.start as u32), Some(Location { file: b, offset: 37 }));
+        assert_eq!(&*errors[0].raw, "external");
+        assert_eq!(Renderer::for_file(&files, b).render(&errors[0]), "\
+error[E0004]: visibility is already declared, found another `external`
+ --> b.sol:2:23
+  |
+2 |     function() public external;
+  |                ------ ^^^^^^^^
+  |                |
+  |                previous visibility declared here
+");
+    }
+
+    fn first_part<'ast>(program: &Program<'ast>) -> ContractPartNode<'ast> {
+        match program.body().iter().next().unwrap().value {
+            SourceUnit::ContractDefinition(contract) => *contract.body.iter().next().unwrap(),
+            _ => panic!("Expected a contract"),
+        }
+    }
+
+    #[test]
+    fn source_text() {
+        let source = "contract Foo {\n    function wow() public { /* such */ }\n}";
+        let program = parse(source).unwrap();
+        let unit = *program.body().iter().next().unwrap();
+
+        assert_eq!(program.source(), source);
+        assert_eq!(program.text(&unit), source);
+        assert_eq!(program.text(&first_part(&program)), "function wow() public { /* such */ }");
+    }
+
+    #[test]
+    fn source_text_of_file() {
+        let mut files = SourceFiles::new();
+
+        files.add("a.sol", "contract Foo {}");
+
+        let b = files.add("b.sol", "contract Bar {\n    uint doge;\n}");
+        let program = parse_file(&files, b).unwrap();
+
+        assert_eq!(program.source(), files.source(b));
+        assert_eq!(program.text(&first_part(&program)), "uint doge;");
+    }
+
+    #[test]
+    fn parse_owned_source() {
+        let source = include_str!("../../lunarity/benches/second-price-auction.sol");
+        let owned = parse_owned(source.to_owned()).unwrap();
+        let borrowed = parse(source).unwrap();
+
+        assert_eq!(owned.source(), source);
+        assert_eq!(owned.body(), borrowed.body());
+        assert!(parse_owned("contract {".into()).is_err());
+    }
+
+    #[test]
+    fn parse_in_arena() {
+        let source = include_str!("../../lunarity/benches/second-price-auction.sol");
+        let arena = Arena::new();
+        let first = parse_in(source, &arena).unwrap();
+        let second = parse_in("contract Foo {}", &arena).unwrap();
+
+        assert_eq!(first, parse(source).unwrap().body());
+        assert_eq!(second.iter().count(), 1);
+        assert!(parse_in("contract {", &arena).is_err());
+    }
+
+    #[test]
+    fn structurally_equal_programs() {
+        let a = parse("contract Foo { function f(uint a) returns (uint) { return a * 2; } }").unwrap();
+        let b = parse("// Copy\ncontract Foo {\n    function f(uint b) returns (uint) {\n        return b*2;\n    }\n}").unwrap();
+        let options = StructuralOptions {
+            normalize_locals: true,
+        };
+
+        assert!(!structurally_eq(&a, &b, StructuralOptions::default()));
+        assert!(structurally_eq(&a, &b, options));
+    }
+
+    #[test]
+    fn dump_program() {
+        let program = parse("contract Foo {\n    uint constant x = 2;\n    event E(uint indexed a);\n    function f(uint a) returns (uint) { { a; } }\n}").unwrap();
+
+        assert_eq!(program.dump(), "\
+ContractDefinition 0..119 name=Foo
+  StateVariableDeclaration 19..39 name=x constant
+    ElementaryTypeName 19..23 type=uint256
+    PrimitiveExpression 37..38 value=2
+  EventDefinition 44..68 name=E
+    IndexedParameter 52..66 name=a indexed
+      ElementaryTypeName 52..56 type=uint256
+  FunctionDefinition 73..117 name=f
+    Parameter 84..90 name=a
+      ElementaryTypeName 84..88 type=uint256
+    Parameter 101..105 returns
+      ElementaryTypeName 101..105 type=uint256
+    Block 107..117
+      BlockStatement 109..115
+        ExpressionStatement 111..113
+          IdentifierExpression 111..112 name=a
+");
+
+        assert_eq!(program.dump_with_options(DumpOptions {
+            line_col: true,
+            subtree: Some("EventDefinition"),
+            ..DumpOptions::default()
+        }), "\
+EventDefinition 3:5-3:29 name=E
+  IndexedParameter 3:13-3:27 name=a indexed
+    ElementaryTypeName 3:13-3:17 type=uint256
+");
+    }
+
+    #[test]
+    fn query_program() {
+        let source = "contract Foo {
+    function a() public onlyOwner {}
+    function b() public { while (true) { msg.sender.call.value(1)(); } }
+    function c() internal {}
+}";
+        let program = parse(source).unwrap();
+        let texts = |query: &str| -> Vec<&str> {
+            program.query(query).unwrap().iter().map(|m| &source[m.start as usize..m.end as usize]).collect()
+        };
+
+        assert_eq!(texts("FunctionDefinition[visibility=public]:not(:has(> ModifierInvocation))"), vec![
+            "function b() public { while (true) { msg.sender.call.value(1)(); } }",
+        ]);
+
+        let query = ":is(ForStatement, WhileStatement, DoWhileStatement)@loop \
+                     CallExpression:has(> MemberAccessExpression[member=value] > [member=call])";
+        let matches = program.query(query).unwrap();
+
+        assert_eq!(texts(query), vec!["msg.sender.call.value(1)"]);
+        assert_eq!(matches[0].capture("loop").map(|loop_| loop_.kind), Some("WhileStatement"));
+    }
 }
@@ -65,3 +65,36 @@ where
 
     assert_eq!(got, expected, "Expected {} units, got {}", expected, got);
 }
+
+/// Debug output of the AST with all the `(start:end) ` locations removed.
+pub fn without_spans(program: &Program) -> String {
+    remove_spans(&format!("{:?}", program.body()))
+}
+
+/// Remove all the `(start:end) ` locations from the debug output of nodes.
+pub fn remove_spans(debug: &str) -> String {
+    let mut out = String::with_capacity(debug.len());
+    let mut rest = debug;
+
+    while let Some(index) = rest.find('(') {
+        out.push_str(&rest[..index]);
+        rest = &rest[index..];
+
+        let span = rest[1..].find(") ").map(|end| &rest[1..end + 1]).filter(|span| {
+            let mut parts = span.split(':');
+
+            parts.clone().count() == 2 && parts.all(|part| !part.is_empty() && part.bytes().all(|byte| byte.is_ascii_digit()))
+        });
+
+        match span {
+            Some(span) => rest = &rest[span.len() + 3..],
+            None => {
+                out.push('(');
+                rest = &rest[1..];
+            },
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
@@ -25,8 +25,50 @@ pub const P4: Precedence = Precedence(4);
 pub const P3: Precedence = Precedence(3);
 pub const P2: Precedence = Precedence(2);
 
+const PRIMARY: Precedence = Precedence(1);
+
 const INVALID: Precedence = Precedence(100);
 
+impl Precedence {
+    /// Precedence of the expression as produced by the parser. Primary
+    /// expressions bind tighter than any operator. Prefix operators bind
+    /// looser than member access, calls, indexing and postfix operators,
+    /// but tighter than the exponent, which is written as `(-a) ** b`.
+    pub fn of(expression: &Expression) -> Self {
+        match *expression {
+            Expression::PrefixExpression(_)       => P3,
+            Expression::PostfixExpression(_)      |
+            Expression::CallExpression(_)         |
+            Expression::MemberAccessExpression(_) |
+            Expression::IndexAccessExpression(_)  => P2,
+            Expression::ConditionalExpression(_)  => P14,
+            Expression::AssignmentExpression(_)   => TOP,
+            Expression::BinaryExpression(ref binary) => match binary.operator.value {
+                BinaryOperator::Exponent       => P3,
+                BinaryOperator::Multiplication |
+                BinaryOperator::Division       |
+                BinaryOperator::Remainder      => P4,
+                BinaryOperator::Addition       |
+                BinaryOperator::Subtraction    => P5,
+                BinaryOperator::BitShiftLeft   |
+                BinaryOperator::BitShiftRight  => P6,
+                BinaryOperator::BitAnd         => P7,
+                BinaryOperator::BitXor         => P8,
+                BinaryOperator::BitOr          => P9,
+                BinaryOperator::Lesser         |
+                BinaryOperator::LesserEquals   |
+                BinaryOperator::Greater        |
+                BinaryOperator::GreaterEquals  => P10,
+                BinaryOperator::Equality       |
+                BinaryOperator::Inequality     => P11,
+                BinaryOperator::LogicalAnd     => P12,
+                BinaryOperator::LogicalOr      => P13,
+            },
+            _ => PRIMARY,
+        }
+    }
+}
+
 static NESTED_LUT: [NestedHandler; Token::SIZE] = lookup! {
     Token::Accessor               => NestedHandler(P2, MEMBER),
     Token::ParenOpen              => NestedHandler(P2, CALL),
@@ -79,7 +121,7 @@ impl NestedHandler {
 }
 
 const CALL: HandlerFn = |par, callee| {
-    par.lexer.advance();
+    par.advance();
 
     let arguments = par.expression_list();
     let end       = par.expect_end(Token::ParenClose);
@@ -91,7 +133,7 @@ const CALL: HandlerFn = |par, callee| {
 };
 
 const MEMBER: HandlerFn = |par, object| {
-    par.lexer.advance();
+    par.advance();
 
     let member = par.expect_str_node(Token::Identifier);
 
@@ -102,7 +144,7 @@ const MEMBER: HandlerFn = |par, object| {
 };
 
 const INDEX: HandlerFn = |par, array| {
-    par.lexer.advance();
+    par.advance();
 
     let index = par.expression(TOP);
     let end   = par.expect_end(Token::BracketClose);
@@ -132,7 +174,7 @@ const DEC: HandlerFn = |par, operand| {
 };
 
 const CONDITIONAL: HandlerFn = |par, test| {
-    par.lexer.advance();
+    par.advance();
 
     let consequent = expect!(par, par.expression(P14));
 
@@ -246,10 +288,12 @@ mod test {
 
         "#, [
             m.node(14, 246, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 232, FunctionDefinition {
+                        doc: None,
                         name: None,
                         params: NodeList::empty(),
                         visibility: None,
@@ -321,10 +365,12 @@ mod test {
 
         "#, [
             m.node(14, 611, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 597, FunctionDefinition {
+                        doc: None,
                         name: None,
                         params: NodeList::empty(),
                         visibility: None,
@@ -461,10 +507,12 @@ mod test {
 
         "#, [
             m.node(14, 398, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 384, FunctionDefinition {
+                        doc: None,
                         name: None,
                         params: NodeList::empty(),
                         visibility: None,
@@ -551,10 +599,12 @@ mod test {
 
         "#, [
             m.node(14, 169, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 155, FunctionDefinition {
+                        doc: None,
                         name: None,
                         params: NodeList::empty(),
                         visibility: None,
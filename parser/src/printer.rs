@@ -0,0 +1,1521 @@
+//! Printing the AST back to Solidity source.
+//!
+//! The printed source parses back to the same AST, locations aside.
+//! Parentheses are added wherever the precedence of operators requires
+//! them, which only happens for ASTs that weren't produced by the parser,
+//! since the parser keeps parentheses as `TupleExpression`s. For the same
+//! reason an `if` statement without `else` is wrapped in a block when it
+//! would otherwise take the `else` of the statement it's nested in.
+//!
+//! Nodes that hold no source to print, such as the `Error` nodes produced
+//! by error recovery, or array and function type names and assembly string
+//! and hex literals the AST has no content for, can't be printed. Printing
+//! an AST with any of them fails with a `PrintError` locating the first one.
+//! ASTs produced by a successful `parse` never contain them.
+//!
+//! Given the source the AST was parsed from, the `Printer` also keeps its
+//! comments and empty lines, which is what `format` is built on.
+
+use std::cmp;
+use std::fmt;
+use std::mem;
+
+use ast::*;
+use cst::{self, LeafKind, SyntaxLeaf};
+use format::{BraceStyle, FormatOptions};
+use {Precedence, P2, P14, TOP};
+
+/// Print the `Program` as Solidity source, see `Printer`.
+pub fn print(program: &Program) -> Result<String, PrintError> {
+    let mut printer = Printer::new();
+
+    printer.program(program);
+    printer.finish()
+}
+
+/// Node with no source to print, see module docs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PrintError {
+    /// Kind of the node, such as `ArrayTypeName`.
+    pub kind: &'static str,
+    pub start: u32,
+    pub end: u32,
+}
+
+impl fmt::Display for PrintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "can't print `{}` at {}..{}", self.kind, self.start, self.end)
+    }
+}
+
+/// Prints nodes of the AST to a `String`, one definition or statement
+/// per line, laid out according to `FormatOptions`. Parameters and
+/// arguments that don't fit within the line width are put one per line.
+pub struct Printer<'src> {
+    out: String,
+    depth: usize,
+    options: FormatOptions,
+
+    /// Source the AST was parsed from, empty if not known.
+    source: &'src str,
+
+    /// Comments of the source, and the index of the next one to print.
+    comments: Vec<SyntaxLeaf<'src>>,
+    next_comment: usize,
+
+    /// Offset in the source past the last printed node or comment.
+    last_end: u32,
+
+    /// Set if the next line should be preceded by an empty line.
+    blank: bool,
+
+    /// Set right after an opening brace, until something is printed within.
+    empty: bool,
+
+    /// Set while printing lists on a single line regardless of the width.
+    flat: bool,
+
+    /// First node with no source to print.
+    unprintable: Option<PrintError>,
+}
+
+impl Printer<'static> {
+    /// Create a `Printer` with default `FormatOptions`, except for keeping
+    /// the order of imports.
+    pub fn new() -> Self {
+        Printer::with_options(FormatOptions {
+            sort_imports: false,
+            ..FormatOptions::default()
+        })
+    }
+
+    pub fn with_options(options: FormatOptions) -> Self {
+        Printer::with_source("", options)
+    }
+}
+
+impl Default for Printer<'static> {
+    fn default() -> Self {
+        Printer::new()
+    }
+}
+
+impl<'src> Printer<'src> {
+    /// Create a `Printer` for an AST parsed from `source`, keeping all of
+    /// its comments, as well as single empty lines between definitions and
+    /// statements. Doc comments attached to the AST are skipped, since they
+    /// are printed as they were written along with the other comments.
+    pub fn with_source(source: &'src str, options: FormatOptions) -> Self {
+        let comments = cst::leaves(source)
+            .into_iter()
+            .filter(|leaf| matches!(leaf.kind, LeafKind::LineComment | LeafKind::BlockComment))
+            .collect();
+
+        Printer {
+            out: String::new(),
+            depth: 0,
+            options,
+            source,
+            comments,
+            next_comment: 0,
+            last_end: 0,
+            blank: false,
+            empty: false,
+            flat: false,
+            unprintable: None,
+        }
+    }
+
+    /// Get the printed source, or the first node that couldn't be printed.
+    pub fn finish(self) -> Result<String, PrintError> {
+        match self.unprintable {
+            Some(error) => Err(error),
+            None        => Ok(self.out),
+        }
+    }
+
+    /// Print all source units, separated by empty lines, except for runs
+    /// of pragma and import directives.
+    pub fn program(&mut self, program: &Program) {
+        let mut units: Vec<_> = program.body().iter().cloned().collect();
+
+        if self.options.sort_imports {
+            for run in units.split_mut(|unit| !is_import(*unit)) {
+                run.sort_by_key(|unit| match unit.value {
+                    SourceUnit::ImportDirective(import) => import_source(import),
+                    _                                   => "",
+                });
+            }
+        }
+
+        let mut last: Option<SourceUnitNode> = None;
+
+        for unit in units {
+            let spaced = matches!(last, Some(last) if !is_directive(last) || !is_directive(unit));
+
+            self.line(unit.start, spaced);
+            self.source_unit(unit);
+            self.trailing(unit.end);
+
+            last = Some(unit);
+        }
+
+        self.comments_before(u32::MAX);
+
+        if !self.out.is_empty() {
+            self.out.push('\n');
+        }
+    }
+
+    pub fn source_unit(&mut self, unit: SourceUnitNode) {
+        match unit.value {
+            SourceUnit::PragmaDirective(pragma) => {
+                self.out.push_str("pragma ");
+                self.out.push_str(pragma.version);
+                self.out.push(';');
+            },
+            SourceUnit::ImportDirective(import)      => self.import_directive(import),
+            SourceUnit::ContractDefinition(contract) => self.contract_definition(contract, unit.end),
+            SourceUnit::Error                        => self.unprintable("Error", unit.start, unit.end),
+        }
+    }
+
+    fn import_directive(&mut self, import: ImportDirective) {
+        self.out.push_str("import ");
+
+        let source = match import {
+            ImportDirective::Global { source, alias } => {
+                self.out.push_str(source.value);
+                self.alias(alias);
+                self.out.push(';');
+
+                return;
+            },
+            ImportDirective::From { symbol, alias, source } => {
+                self.out.push_str(symbol.map_or("*", |symbol| symbol.value));
+                self.alias(alias);
+
+                source
+            },
+            ImportDirective::ManyFrom { imports, source } => {
+                let mut imports: Vec<_> = imports.iter().cloned().collect();
+
+                if self.options.sort_imports {
+                    imports.sort_by_key(|import| import.value.symbol.value);
+                }
+
+                self.out.push_str("{ ");
+
+                for (index, import) in imports.iter().enumerate() {
+                    if index != 0 {
+                        self.out.push_str(", ");
+                    }
+
+                    self.out.push_str(import.value.symbol.value);
+                    self.alias(import.value.alias);
+                }
+
+                self.out.push_str(" }");
+
+                source
+            },
+        };
+
+        self.out.push_str(" from ");
+        self.out.push_str(source.value);
+        self.out.push(';');
+    }
+
+    fn alias(&mut self, alias: Option<IdentifierNode>) {
+        if let Some(alias) = alias {
+            self.out.push_str(" as ");
+            self.out.push_str(alias.value);
+        }
+    }
+
+    fn contract_definition(&mut self, contract: ContractDefinition, end: u32) {
+        self.doc(contract.doc);
+        self.out.push_str("contract ");
+        self.out.push_str(contract.name.value);
+
+        for (index, base) in contract.inherits.iter().enumerate() {
+            self.out.push_str(if index == 0 { " is " } else { ", " });
+            self.out.push_str(base.value);
+        }
+
+        self.brace(contract.body.is_empty(), end);
+        self.open();
+
+        // Parts are separated by empty lines if either spans multiple lines
+        let mut last_spaced = None;
+
+        for part in contract.body.iter() {
+            let spaced = matches!(last_spaced, Some(last_spaced) if last_spaced || is_spaced(*part));
+            self.line(part.start, spaced);
+
+            let start = self.out.len();
+
+            self.contract_part(*part);
+            self.trailing(part.end);
+
+            let lines = self.out[start..].lines().count();
+
+            last_spaced = Some(lines > 1 || is_spaced(*part));
+        }
+
+        self.close(end);
+    }
+
+    pub fn contract_part(&mut self, part: ContractPartNode) {
+        match part.value {
+            ContractPart::StateVariableDeclaration(var) => {
+                self.doc(var.doc);
+                self.type_name(var.type_name);
+
+                if let Some(visibility) = var.visibility {
+                    self.out.push(' ');
+                    self.out.push_str(visibility.value.as_str());
+                }
+
+                if var.constant.is_some() {
+                    self.out.push_str(" constant");
+                }
+
+                self.out.push(' ');
+                self.out.push_str(var.name.value);
+
+                if let Some(init) = var.init {
+                    self.out.push_str(" = ");
+                    self.expression(init);
+                }
+
+                self.out.push(';');
+            },
+            ContractPart::UsingForDeclaration(using) => {
+                self.out.push_str("using ");
+                self.out.push_str(using.id.value);
+                self.out.push_str(" for ");
+
+                match using.type_name {
+                    Some(type_name) => self.type_name(type_name),
+                    None            => self.out.push('*'),
+                }
+
+                self.out.push(';');
+            },
+            ContractPart::StructDefinition(def) => {
+                self.out.push_str("struct ");
+                self.out.push_str(def.name.value);
+                self.brace(def.body.is_empty(), part.end);
+                self.open();
+
+                for declaration in def.body.iter() {
+                    self.line(declaration.start, false);
+                    self.variable_declaration(*declaration);
+                    self.out.push(';');
+                    self.trailing(declaration.end);
+                }
+
+                self.close(part.end);
+            },
+            ContractPart::ModifierDefinition(def) => {
+                self.doc(def.doc);
+                self.out.push_str("modifier ");
+                self.out.push_str(def.name.value);
+
+                if !def.params.is_empty() {
+                    self.parameter_list(def.params);
+                }
+
+                self.brace(def.block.value.body.is_empty(), def.block.end);
+                self.block(def.block.value.body, def.block.end);
+            },
+            ContractPart::FunctionDefinition(def) => self.function_definition(def),
+            ContractPart::EventDefinition(def) => {
+                self.doc(def.doc);
+                self.out.push_str("event ");
+                self.out.push_str(def.name.value);
+
+                let params: Vec<_> = def.params.iter().cloned().collect();
+
+                self.list(&params, |printer, param: Node<IndexedParameter>| {
+                    printer.type_name(param.value.type_name);
+
+                    if param.value.indexed.is_some() {
+                        printer.out.push_str(" indexed");
+                    }
+
+                    if let Some(name) = param.value.name {
+                        printer.out.push(' ');
+                        printer.out.push_str(name.value);
+                    }
+                });
+
+                if def.anonymous.is_some() {
+                    self.out.push_str(" anonymous");
+                }
+
+                self.out.push(';');
+            },
+            ContractPart::EnumDefinition(def) => {
+                self.out.push_str("enum ");
+                self.out.push_str(def.name.value);
+
+                if def.variants.is_empty() {
+                    self.out.push_str(" {}");
+                    return;
+                }
+
+                let single_line = |printer: &mut Self| {
+                    for (index, variant) in def.variants.iter().enumerate() {
+                        printer.out.push_str(if index == 0 { " { " } else { ", " });
+                        printer.out.push_str(variant.value);
+                    }
+
+                    printer.out.push_str(" }");
+                };
+
+                if self.fits(0, single_line) {
+                    single_line(self);
+                    return;
+                }
+
+                self.brace(false, part.end);
+                self.open();
+
+                for (index, variant) in def.variants.iter().enumerate() {
+                    if index != 0 {
+                        self.out.push(',');
+                    }
+
+                    self.line(variant.start, false);
+                    self.out.push_str(variant.value);
+                }
+
+                self.close(part.end);
+            },
+            ContractPart::Error => self.unprintable("Error", part.start, part.end),
+        }
+    }
+
+    /// Print the function on a single line if it fits, otherwise with
+    /// the parameters broken into lines as needed, and then each of the
+    /// attributes on its own line.
+    fn function_definition(&mut self, def: FunctionDefinition) {
+        self.doc(def.doc);
+        self.out.push_str("function");
+
+        if let Some(name) = def.name {
+            self.out.push(' ');
+            self.out.push_str(name.value);
+        }
+
+        let reserve = if def.block.is_some() { 2 } else { 1 };
+        let broken = !self.fits(reserve, |printer| printer.function_header(def, false));
+
+        if broken {
+            self.function_header(def, true);
+        } else {
+            self.single_line(|printer| printer.function_header(def, false));
+        }
+
+        let block = match def.block {
+            Some(block) => block,
+            None        => return self.out.push(';'),
+        };
+
+        let attributes = def.visibility.is_some()
+            || def.mutability.is_some()
+            || !def.modifiers.is_empty()
+            || !def.returns.is_empty();
+
+        if broken && attributes {
+            self.newline();
+        } else {
+            self.brace(block.value.body.is_empty(), block.end);
+        }
+
+        self.block(block.value.body, block.end);
+    }
+
+    /// Print parameters and attributes of the function, in the order of
+    /// visibility, mutability, modifiers and returns.
+    fn function_header(&mut self, def: FunctionDefinition, broken: bool) {
+        self.parameter_list(def.params);
+
+        if broken {
+            self.depth += 1;
+        }
+
+        if let Some(visibility) = def.visibility {
+            self.attribute(broken);
+            self.out.push_str(visibility.value.as_str());
+        }
+
+        if let Some(mutability) = def.mutability {
+            self.attribute(broken);
+            self.out.push_str(mutability.value.as_str());
+        }
+
+        for modifier in def.modifiers.iter() {
+            self.attribute(broken);
+            self.out.push_str(modifier.value.id.value);
+
+            if !modifier.value.arguments.is_empty() {
+                self.expression_list(modifier.value.arguments);
+            }
+        }
+
+        if !def.returns.is_empty() {
+            self.attribute(broken);
+            self.out.push_str("returns ");
+            self.parameter_list(def.returns);
+        }
+
+        if broken {
+            self.depth -= 1;
+        }
+    }
+
+    fn attribute(&mut self, broken: bool) {
+        if broken {
+            self.newline();
+        } else {
+            self.out.push(' ');
+        }
+    }
+
+    fn parameter_list(&mut self, params: ParameterList) {
+        let params: Vec<_> = params.iter().cloned().collect();
+
+        self.list(&params, |printer, param: Node<Parameter>| {
+            printer.type_name(param.value.type_name);
+
+            if let Some(name) = param.value.name {
+                printer.out.push(' ');
+                printer.out.push_str(name.value);
+            }
+        });
+    }
+
+    /// Print the doc comment as `///` lines, followed by a new line. Text
+    /// preceding any tag is printed without the `@notice`.
+    fn doc(&mut self, doc: Option<DocCommentNode>) {
+        let doc = match doc {
+            Some(doc) if self.source.is_empty() => doc,
+            _                                   => return,
+        };
+
+        for (index, tag) in doc.value.tags.iter().enumerate() {
+            let DocTag { kind, text } = tag.value;
+            let mut lines = text.split('\n');
+
+            self.out.push_str("///");
+
+            let tag = match kind {
+                DocTagKind::Notice if index == 0 && !text.starts_with('@') => None,
+                DocTagKind::Title          => Some(("title", None)),
+                DocTagKind::Author         => Some(("author", None)),
+                DocTagKind::Notice         => Some(("notice", None)),
+                DocTagKind::Dev            => Some(("dev", None)),
+                DocTagKind::Param(name)    => Some(("param", Some(name))),
+                DocTagKind::Return         => Some(("return", None)),
+                DocTagKind::Inheritdoc(name) => Some(("inheritdoc", Some(name))),
+                DocTagKind::Custom(name)   => {
+                    self.out.push_str(" @custom:");
+                    self.out.push_str(name);
+
+                    None
+                },
+                DocTagKind::Unknown(name)  => Some((name, None)),
+            };
+
+            if let Some((tag, name)) = tag {
+                self.out.push_str(" @");
+                self.out.push_str(tag);
+
+                if let Some(name) = name {
+                    self.out.push(' ');
+                    self.out.push_str(name);
+                }
+            }
+
+            if let Some(line) = lines.next().filter(|line| !line.is_empty()) {
+                self.out.push(' ');
+                self.out.push_str(line);
+            }
+
+            for line in lines {
+                self.newline();
+                self.out.push_str("/// ");
+                self.out.push_str(line);
+            }
+
+            self.newline();
+        }
+    }
+
+    pub fn type_name(&mut self, type_name: TypeNameNode) {
+        match type_name.value {
+            TypeName::ElementaryTypeName(elementary) => self.elementary_type_name(elementary, width(type_name)),
+            TypeName::UserDefinedTypeName(name)      => self.out.push_str(name),
+            TypeName::Mapping(mapping) => {
+                self.out.push_str("mapping(");
+                self.elementary_type_name(mapping.from.value, width(mapping.from));
+                self.out.push_str(" => ");
+                self.type_name(mapping.to);
+                self.out.push(')');
+            },
+            TypeName::ArrayTypeName    |
+            TypeName::FunctionTypeName => self.unprintable(type_name.value.kind(), type_name.start, type_name.end),
+        }
+    }
+
+    /// Print the type as it was written, given the width of its node. Types
+    /// are single tokens, and aliases are shorter than the canonical names
+    /// they stand for, so `uint` is printed as `uint` and `uint256` as
+    /// `uint256`. Nodes without a location get the canonical names.
+    fn elementary_type_name(&mut self, elementary: ElementaryTypeName, width: u32) {
+        match elementary {
+            ElementaryTypeName::Int(32) if width == 3  => self.out.push_str("int"),
+            ElementaryTypeName::Uint(32) if width == 4 => self.out.push_str("uint"),
+            ElementaryTypeName::Byte(1) if width == 4  => self.out.push_str("byte"),
+
+            // The lexer doesn't assign sizes to `fixed` and `ufixed`
+            ElementaryTypeName::Fixed(0, 0)            => self.out.push_str("fixed"),
+            ElementaryTypeName::Ufixed(0, 0)           => self.out.push_str("ufixed"),
+            _                                          => self.out.push_str(&elementary.to_string()),
+        }
+    }
+
+    fn variable_declaration(&mut self, declaration: VariableDeclarationNode) {
+        self.type_name(declaration.value.type_name);
+
+        if let Some(location) = declaration.value.location {
+            self.out.push(' ');
+            self.out.push_str(location.value.as_str());
+        }
+
+        self.out.push(' ');
+        self.out.push_str(declaration.value.id.value);
+    }
+
+    /// Print the statements of a block ending at `end` in the source.
+    fn block(&mut self, body: StatementList, end: u32) {
+        self.open();
+
+        for statement in body.iter() {
+            self.line(statement.start, false);
+            self.statement(*statement);
+            self.trailing(statement.end);
+        }
+
+        self.close(end);
+    }
+
+    /// Print the body of a control flow statement.
+    fn body(&mut self, statement: StatementNode) {
+        match statement.value {
+            Statement::BlockStatement(block) => {
+                self.brace(block.body.is_empty(), statement.end);
+                self.block(block.body, statement.end);
+            },
+            _ => {
+                self.out.push(' ');
+                self.statement(statement);
+            },
+        }
+    }
+
+    /// Print the space or line break between a block and the keyword following it.
+    fn after_block(&mut self) {
+        match self.options.brace_style {
+            BraceStyle::SameLine => self.out.push(' '),
+            BraceStyle::NextLine => self.newline(),
+        }
+    }
+
+    pub fn statement(&mut self, statement: StatementNode) {
+        match statement.value {
+            Statement::Placeholder => self.out.push_str("_;"),
+            Statement::IfStatement(stmt) => {
+                self.out.push_str("if (");
+                self.expression(stmt.test);
+                self.out.push(')');
+
+                let consequent = stmt.consequent;
+                let wrap       = stmt.alternate.is_some() && takes_else(consequent);
+
+                if wrap {
+                    self.brace(false, consequent.end);
+                    self.open();
+                    self.line(consequent.start, false);
+                    self.statement(consequent);
+                    self.close(consequent.end);
+                } else {
+                    self.body(consequent);
+                }
+
+                if let Some(alternate) = stmt.alternate {
+                    if wrap || is_block(consequent) {
+                        self.after_block();
+                    } else {
+                        self.newline();
+                    }
+
+                    self.out.push_str("else");
+                    self.body(alternate);
+                }
+            },
+            Statement::WhileStatement(stmt) => {
+                self.out.push_str("while (");
+                self.expression(stmt.test);
+                self.out.push(')');
+                self.body(stmt.body);
+            },
+            Statement::ForStatement(stmt) => {
+                self.out.push_str("for (");
+
+                match stmt.init {
+                    Some(init) => self.simple_statement(init.value),
+                    None       => self.out.push(';'),
+                }
+
+                if let Some(test) = stmt.test {
+                    self.out.push(' ');
+                    self.expression(test);
+                }
+
+                self.out.push(';');
+
+                if let Some(update) = stmt.update {
+                    self.out.push(' ');
+                    self.expression(update);
+                }
+
+                self.out.push(')');
+                self.body(stmt.body);
+            },
+            Statement::BlockStatement(block) => self.block(block.body, statement.end),
+            Statement::InlineAssemblyStatement(stmt) => {
+                self.out.push_str("assembly");
+
+                if let Some(string) = stmt.string {
+                    self.out.push(' ');
+                    self.out.push_str(string.value);
+                }
+
+                self.brace(stmt.block.value.items.is_empty(), stmt.block.end);
+                self.assembly_block(stmt.block.value, stmt.block.end);
+            },
+            Statement::DoWhileStatement(stmt) => {
+                self.out.push_str("do");
+                self.body(stmt.body);
+
+                if is_block(stmt.body) {
+                    self.after_block();
+                } else {
+                    self.newline();
+                }
+
+                self.out.push_str("while (");
+                self.expression(stmt.test);
+                self.out.push_str(");");
+            },
+            Statement::ContinueStatement => self.out.push_str("continue;"),
+            Statement::BreakStatement    => self.out.push_str("break;"),
+            Statement::ThrowStatement    => self.out.push_str("throw;"),
+            Statement::ReturnStatement(stmt) => {
+                self.out.push_str("return");
+
+                if let Some(value) = stmt.value {
+                    self.out.push(' ');
+                    self.expression(value);
+                }
+
+                self.out.push(';');
+            },
+            Statement::VariableDefinitionStatement(stmt) => self.simple_statement(stmt.into()),
+            Statement::InferredDefinitionStatement(stmt) => self.simple_statement(stmt.into()),
+            Statement::ExpressionStatement(expression)   => self.simple_statement(expression.into()),
+            Statement::Error                             => self.unprintable("Error", statement.start, statement.end),
+        }
+    }
+
+    fn simple_statement(&mut self, statement: SimpleStatement) {
+        match statement {
+            SimpleStatement::VariableDefinitionStatement(stmt) => {
+                self.variable_declaration(stmt.declaration);
+
+                if let Some(init) = stmt.init {
+                    self.out.push_str(" = ");
+                    self.expression(init);
+                }
+            },
+            SimpleStatement::InferredDefinitionStatement(stmt) => {
+                self.out.push_str("var ");
+
+                match stmt.ids.only_element() {
+                    Some(&Some(id)) => self.out.push_str(id.value),
+                    _ => {
+                        self.out.push('(');
+
+                        for (index, id) in stmt.ids.iter().enumerate() {
+                            if index != 0 {
+                                self.out.push_str(", ");
+                            }
+
+                            if let Some(id) = *id {
+                                self.out.push_str(id.value);
+                            }
+                        }
+
+                        self.out.push(')');
+                    },
+                }
+
+                self.out.push_str(" = ");
+                self.expression(stmt.init);
+            },
+            SimpleStatement::ExpressionStatement(expression) => self.expression(expression),
+        }
+
+        self.out.push(';');
+    }
+
+    /// Print the items of an assembly block ending at `end` in the source.
+    fn assembly_block(&mut self, block: InlineAssemblyBlock, end: u32) {
+        self.open();
+
+        for item in block.items.iter() {
+            self.line(item.start, false);
+            self.assembly_item(*item);
+            self.trailing(item.end);
+        }
+
+        self.close(end);
+    }
+
+    fn assembly_item(&mut self, item: AssemblyItemNode) {
+        match item.value {
+            AssemblyItem::Identifier(id) => self.out.push_str(id),
+            AssemblyItem::FunctionalAssemblyExpression(expression) => self.assembly_call(expression),
+            AssemblyItem::InlineAssemblyBlock(block) => self.assembly_block(block, item.end),
+            AssemblyItem::AssemblyLocalBinding(binding) => {
+                self.out.push_str("let ");
+                self.out.push_str(binding.id.value);
+                self.out.push_str(" := ");
+                self.assembly_call(binding.init.value);
+            },
+            AssemblyItem::AssemblyAssignment(assignment) => {
+                self.out.push_str(assignment.id.value);
+                self.out.push_str(" := ");
+                self.assembly_call(assignment.init.value);
+            },
+            AssemblyItem::AssemblyLabel(label) => {
+                self.out.push_str(label.id.value);
+                self.out.push(':');
+            },
+            AssemblyItem::NumberLiteral(primitive) => self.primitive(primitive),
+            AssemblyItem::StringLiteral |
+            AssemblyItem::HexLiteral    => self.unprintable(item.value.kind(), item.start, item.end),
+        }
+    }
+
+    fn assembly_call(&mut self, expression: FunctionalAssemblyExpression) {
+        self.out.push_str(expression.id.value);
+        self.out.push('(');
+
+        for (index, argument) in expression.arguments.iter().enumerate() {
+            if index != 0 {
+                self.out.push_str(", ");
+            }
+
+            self.assembly_item(*argument);
+        }
+
+        self.out.push(')');
+    }
+
+    pub fn expression(&mut self, expression: ExpressionNode) {
+        match expression.value {
+            Expression::ThisExpression             => self.out.push_str("this"),
+            Expression::IdentifierExpression(name) => self.out.push_str(name),
+            Expression::PrimitiveExpression(primitive) => self.primitive(primitive),
+            Expression::PrefixExpression(prefix) => {
+                let operator = prefix.operator.value.as_str();
+
+                self.out.push_str(operator);
+
+                let start = self.out.len();
+
+                // Nested prefix expressions are parsed as operands, unlike other operators
+                match prefix.operand.value {
+                    Expression::PrefixExpression(_) => self.expression(prefix.operand),
+                    _                               => self.operand(prefix.operand, P2),
+                }
+
+                // Keep `- -a` from becoming `--a`, and `delete a` from becoming `deletea`
+                let next  = self.out[start..].chars().next();
+                let clash = operator.ends_with(&['+', '-'][..]) && next == operator.chars().last();
+
+                if clash || prefix.operator.value == PrefixOperator::Delete {
+                    self.out.insert(start, ' ');
+                }
+            },
+            Expression::PostfixExpression(postfix) => {
+                self.operand(postfix.operand, P2);
+                self.out.push_str(postfix.operator.value.as_str());
+            },
+            Expression::BinaryExpression(binary) => {
+                let precedence = Precedence::of(&expression.value);
+
+                // Binary operators are right associative in the parser
+                self.operand_looser(binary.left, precedence);
+                self.out.push(' ');
+                self.out.push_str(binary.operator.value.as_str());
+                self.out.push(' ');
+                self.operand(binary.right, precedence);
+            },
+            Expression::AssignmentExpression(assignment) => {
+                self.operand_looser(assignment.left, TOP);
+                self.out.push(' ');
+                self.out.push_str(assignment.operator.value.as_str());
+                self.out.push(' ');
+                self.expression(assignment.right);
+            },
+            Expression::TupleExpression(tuple) => self.expression_list(tuple.expressions),
+            Expression::CallExpression(call) => {
+                self.operand(call.callee, P2);
+                self.expression_list(call.arguments);
+            },
+            Expression::MemberAccessExpression(member) => {
+                self.operand(member.object, P2);
+                self.out.push('.');
+                self.out.push_str(member.member.value);
+            },
+            Expression::IndexAccessExpression(index) => {
+                self.operand(index.array, P2);
+                self.out.push('[');
+
+                if let Some(index) = index.index {
+                    self.expression(index);
+                }
+
+                self.out.push(']');
+            },
+            Expression::ConditionalExpression(conditional) => {
+                self.operand_looser(conditional.test, P14);
+                self.out.push_str(" ? ");
+                self.operand(conditional.consequent, P14);
+                self.out.push_str(" : ");
+                self.operand(conditional.alternate, P14);
+            },
+            Expression::ElementaryTypeExpression(elementary) => self.elementary_type_name(elementary, width(expression)),
+        }
+    }
+
+    /// Print an operand that can bind as loose as `precedence`.
+    fn operand(&mut self, expression: ExpressionNode, precedence: Precedence) {
+        if Precedence::of(&expression.value) > precedence {
+            self.parenthesized(expression);
+        } else {
+            self.expression(expression);
+        }
+    }
+
+    /// Print an operand that must bind tighter than `precedence`.
+    fn operand_looser(&mut self, expression: ExpressionNode, precedence: Precedence) {
+        if Precedence::of(&expression.value) >= precedence {
+            self.parenthesized(expression);
+        } else {
+            self.expression(expression);
+        }
+    }
+
+    fn parenthesized(&mut self, expression: ExpressionNode) {
+        self.out.push('(');
+        self.expression(expression);
+        self.out.push(')');
+    }
+
+    fn expression_list(&mut self, expressions: ExpressionList) {
+        let expressions: Vec<_> = expressions.iter().cloned().collect();
+
+        self.list(&expressions, |printer, expression| printer.expression(expression));
+    }
+
+    /// Print items separated by commas within parentheses, on a single line
+    /// if they fit within the line width, otherwise one item per line.
+    fn list<T, F>(&mut self, items: &[T], item: F)
+    where
+        T: Copy,
+        F: Fn(&mut Self, T),
+    {
+        let single_line = |printer: &mut Self| {
+            printer.out.push('(');
+
+            for (index, value) in items.iter().enumerate() {
+                if index != 0 {
+                    printer.out.push_str(", ");
+                }
+
+                item(printer, *value);
+            }
+
+            printer.out.push(')');
+        };
+
+        if items.is_empty() || self.fits(1, single_line) {
+            return self.single_line(single_line);
+        }
+
+        self.out.push('(');
+        self.depth += 1;
+
+        for (index, value) in items.iter().enumerate() {
+            if index != 0 {
+                self.out.push(',');
+            }
+
+            self.newline();
+            item(self, *value);
+        }
+
+        self.depth -= 1;
+        self.newline();
+        self.out.push(')');
+    }
+
+    fn primitive(&mut self, primitive: Primitive) {
+        match primitive {
+            Primitive::Bool(true)  => self.out.push_str("true"),
+            Primitive::Bool(false) => self.out.push_str("false"),
+            Primitive::HexNumber(raw) |
+            Primitive::String(raw) => self.out.push_str(raw),
+            Primitive::IntegerNumber(raw, unit) |
+            Primitive::RationalNumber(raw, unit) => {
+                self.out.push_str(raw);
+
+                if let Some(unit) = unit.as_str() {
+                    self.out.push(' ');
+                    self.out.push_str(unit);
+                }
+            },
+        }
+    }
+
+    fn unprintable(&mut self, kind: &'static str, start: u32, end: u32) {
+        if self.unprintable.is_none() {
+            self.unprintable = Some(PrintError { kind, start, end });
+        }
+    }
+
+    /// Check if whatever `print` prints on a single line, followed by `reserve`
+    /// more characters, fits within the line width. Nothing is printed.
+    fn fits<F: FnOnce(&mut Self)>(&mut self, reserve: usize, print: F) -> bool {
+        if self.flat {
+            return true;
+        }
+
+        let len    = self.out.len();
+        let column = self.column();
+
+        self.single_line(print);
+
+        let width = self.width(&self.out[len..]);
+        let fits  = !self.out[len..].contains('\n') && column + width + reserve <= self.options.max_width;
+
+        self.out.truncate(len);
+
+        fits
+    }
+
+    fn single_line<F: FnOnce(&mut Self)>(&mut self, print: F) {
+        let flat = mem::replace(&mut self.flat, true);
+
+        print(self);
+
+        self.flat = flat;
+    }
+
+    fn column(&self) -> usize {
+        let start = self.out.rfind('\n').map_or(0, |index| index + 1);
+
+        self.width(&self.out[start..])
+    }
+
+    fn width(&self, text: &str) -> usize {
+        text.chars().map(|ch| if ch == '\t' { self.options.tab_spaces } else { 1 }).sum()
+    }
+
+    /// Print the space or line break preceding an opening brace. Braces of
+    /// blocks ending at `end` that are empty stay on the same line.
+    fn brace(&mut self, empty: bool, end: u32) {
+        let empty = empty && !matches!(self.comments.get(self.next_comment), Some(comment) if comment.start < end);
+
+        if empty || self.options.brace_style == BraceStyle::SameLine {
+            self.out.push(' ');
+        } else {
+            self.newline();
+        }
+    }
+
+    fn open(&mut self) {
+        self.out.push('{');
+        self.depth += 1;
+        self.empty = true;
+    }
+
+    /// Close a block ending at `end` in the source, with the comments left
+    /// within it.
+    fn close(&mut self, end: u32) {
+        self.comments_before(end);
+        self.depth -= 1;
+
+        if !self.empty {
+            self.newline();
+        }
+
+        self.empty = false;
+        self.out.push('}');
+        self.last_end = cmp::max(self.last_end, end);
+    }
+
+    /// Start a line for an item starting at `start` in the source, preceded
+    /// by the comments before it, and an empty line if `spaced` is set or
+    /// there is one in the source.
+    fn line(&mut self, start: u32, spaced: bool) {
+        self.blank = spaced;
+        self.comments_before(start);
+        self.separate(start);
+    }
+
+    /// Print the comments starting before `offset`, each on its own line.
+    fn comments_before(&mut self, offset: u32) {
+        while let Some(comment) = self.comments.get(self.next_comment).cloned() {
+            if comment.start >= offset {
+                break;
+            }
+
+            self.next_comment += 1;
+            self.separate(comment.start);
+            self.comment(comment);
+        }
+    }
+
+    /// Print a comment following the item ending at `end` on the same line,
+    /// separated from it by nothing but whitespace and punctuation.
+    fn trailing(&mut self, end: u32) {
+        self.last_end = cmp::max(self.last_end, end);
+
+        if let Some(comment) = self.comments.get(self.next_comment).cloned() {
+            let same_line = comment.start >= end && self.source[end as usize..comment.start as usize]
+                .chars()
+                .all(|ch| ch == ';' || ch == ',' || (ch.is_whitespace() && ch != '\n'));
+
+            if same_line {
+                self.next_comment += 1;
+                self.out.push(' ');
+                self.comment(comment);
+            }
+        }
+    }
+
+    fn comment(&mut self, comment: SyntaxLeaf) {
+        self.out.push_str(comment.text.trim_end());
+        self.last_end = comment.start + comment.text.len() as u32;
+    }
+
+    /// Start a new line, unless nothing was printed yet.
+    fn separate(&mut self, start: u32) {
+        if self.out.is_empty() {
+            self.blank = false;
+            return;
+        }
+
+        if !self.empty && (self.blank || self.blank_line_before(start)) {
+            self.out.push('\n');
+        }
+
+        self.blank = false;
+        self.empty = false;
+        self.newline();
+    }
+
+    fn blank_line_before(&self, start: u32) -> bool {
+        let (last_end, start) = (self.last_end as usize, start as usize);
+
+        if last_end >= start || start > self.source.len() {
+            return false;
+        }
+
+        let gap = &self.source[last_end..start];
+
+        gap[gap.trim_end().len()..].matches('\n').count() > 1
+    }
+
+    fn newline(&mut self) {
+        self.out.push('\n');
+
+        for _ in 0..self.depth {
+            if self.options.hard_tabs {
+                self.out.push('\t');
+            } else {
+                self.out.extend((0..self.options.tab_spaces).map(|_| ' '));
+            }
+        }
+    }
+}
+
+fn is_import(unit: SourceUnitNode) -> bool {
+    matches!(unit.value, SourceUnit::ImportDirective(_))
+}
+
+fn is_directive(unit: SourceUnitNode) -> bool {
+    matches!(unit.value, SourceUnit::ImportDirective(_) | SourceUnit::PragmaDirective(_))
+}
+
+fn import_source<'ast>(import: ImportDirective<'ast>) -> &'ast str {
+    match import {
+        ImportDirective::Global { source, .. }   |
+        ImportDirective::From { source, .. }     |
+        ImportDirective::ManyFrom { source, .. } => source.value,
+    }
+}
+
+/// Check if the contract part spans multiple lines, and so should be
+/// separated from others by empty lines.
+fn is_spaced(part: ContractPartNode) -> bool {
+    matches!(
+        part.value,
+        ContractPart::FunctionDefinition(_) | ContractPart::ModifierDefinition(_) | ContractPart::StructDefinition(_)
+    )
+}
+
+#[inline]
+fn width<T>(node: Node<T>) -> u32 {
+    node.end - node.start
+}
+
+fn is_block(statement: StatementNode) -> bool {
+    matches!(statement.value, Statement::BlockStatement(_))
+}
+
+/// Check if an `else` following the statement would be taken by an
+/// `if` statement nested within it.
+fn takes_else(statement: StatementNode) -> bool {
+    match statement.value {
+        Statement::IfStatement(stmt) => match stmt.alternate {
+            Some(alternate) => takes_else(alternate),
+            None            => true,
+        },
+        Statement::WhileStatement(stmt) => takes_else(stmt.body),
+        Statement::ForStatement(stmt)   => takes_else(stmt.body),
+        _                               => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use mock::{without_spans, Mock};
+    use {parse, parse_with_options, ParserOptions};
+
+    fn assert_roundtrip(source: &str, options: ParserOptions) {
+        let program = parse_with_options(source, options).unwrap();
+        let printed = print(&program).unwrap();
+        let reparsed = match parse_with_options(&printed, options) {
+            Ok(reparsed) => reparsed,
+            Err(errors)  => panic!("Printed source doesn't parse: {:?}\n{}", errors, printed),
+        };
+
+        assert_eq!(without_spans(&program), without_spans(&reparsed), "{}", printed);
+        assert_eq!(print(&reparsed).unwrap(), printed);
+    }
+
+    const CONSTRUCTS: &str = r#"
+        pragma solidity ^0.4.17;
+        import "foo" as globalFoo;
+        import * as globalBar from "bar";
+        import doge from "moon";
+        import { doge as wow, to, the as parser } from "moon";
+
+        /**
+         * @title Doge
+         * @author Moon
+         * Many lines
+         * @custom:security none
+         */
+        contract Doge is Shiba, Inu {
+            using Math for uint;
+            using Strings for *;
+            struct Wow { uint256 such; mapping(address => bytes4) many; Doge storage doge; }
+            enum Color { Red, Green }
+            enum Empty {}
+            uint public constant x = 1 ether;
+            mapping(address => mapping(uint8 => fixed)) balances;
+
+            /// @param a The thing
+            /// @return What it is
+            event Moon(address indexed a, ufixed64x10 b, int) anonymous;
+
+            modifier only(address a) { require(msg.sender == a); _; }
+            modifier always { _; }
+
+            function() payable {}
+            function declared(uint a, bool) external view returns (uint, bool b);
+            function f(uint a) public only(a) always returns (uint) {
+                uint256 memory b = 0x10;
+                var c = -a;
+                var (d, , e) = (1, 2, 3);
+                bytes32 h;
+                if (a > 2) return a; else if (a == 1) { throw; } else b++;
+                while (true) { if (!false) break; else continue; }
+                for (uint i = 0; i < 10; i++) b += i ** 2 ** 3;
+                for (;;) {}
+                do b--; while (b > 0);
+                do { b -= 1; } while (- -b > 0);
+                delete b;
+                b = a > b ? a : b == 1 ? 2 : 3;
+                b = (a + b) * (1 - a) % 2 << 1;
+                this.f.value(1 finney)(a)[0] = msg.data.length;
+                msg.sender.transfer(uint(2.5 * 10) + 1 days, address(this));
+                "doge" != unicode"moon";
+                assembly { { a } b := add(a, c) }
+                assembly "evmasm" {}
+                return;
+            }
+        }
+
+        contract Empty {}
+    "#;
+
+    #[test]
+    fn roundtrip_constructs() {
+        assert_roundtrip(CONSTRUCTS, ParserOptions::default());
+        assert_roundtrip(CONSTRUCTS, ParserOptions {
+            doc_comments: true,
+            ..ParserOptions::default()
+        });
+    }
+
+    #[test]
+    fn roundtrip_second_price_auction() {
+        let source = include_str!("../../lunarity/benches/second-price-auction.sol");
+
+        assert_roundtrip(source, ParserOptions::default());
+        assert_roundtrip(source, ParserOptions {
+            doc_comments: true,
+            ..ParserOptions::default()
+        });
+    }
+
+    #[test]
+    fn keeps_type_spelling() {
+        let program = parse(r#"
+            contract Foo {
+                uint a; uint256 b; int c; int256 d; byte e; bytes1 f;
+                mapping(uint => int8) g;
+                function h() { b = uint256(int(a)); }
+            }
+        "#).unwrap();
+
+        assert_eq!(print(&program).unwrap(), r#"contract Foo {
+    uint a;
+    uint256 b;
+    int c;
+    int256 d;
+    byte e;
+    bytes1 f;
+    mapping(uint => int8) g;
+
+    function h() {
+        b = uint256(int(a));
+    }
+}
+"#);
+    }
+
+    #[test]
+    fn print_source() {
+        let program = parse(r#"
+            pragma solidity ^0.4.17;
+
+            /// A contract
+            contract Foo is Bar {
+                uint constant x=1;
+                function foo(uint a) public view returns (uint) {
+                    if (a > x) { return a; } else return x;
+                }
+            }
+        "#).unwrap();
+
+        assert_eq!(print(&program).unwrap(), r#"pragma solidity ^0.4.17;
+
+contract Foo is Bar {
+    uint constant x = 1;
+
+    function foo(uint a) public view returns (uint) {
+        if (a > x) {
+            return a;
+        } else return x;
+    }
+}
+"#);
+    }
+
+    fn print_expression(expression: ExpressionNode) -> String {
+        let mut printer = Printer::new();
+
+        printer.expression(expression);
+        printer.finish().unwrap()
+    }
+
+    #[test]
+    fn precedence() {
+        let m = Mock::new();
+        let id = |name| m.node(0, 0, Expression::IdentifierExpression(name));
+        let binary = |left, operator, right| m.node(0, 0, BinaryExpression {
+            left,
+            operator: m.node(0, 0, operator),
+            right,
+        });
+        let minus = |operand| m.node(0, 0, PrefixExpression {
+            operator: m.node(0, 0, PrefixOperator::Minus),
+            operand,
+        });
+
+        let sum     = binary(id("a"), BinaryOperator::Addition, id("b"));
+        let product = binary(sum, BinaryOperator::Multiplication, id("c"));
+
+        assert_eq!(print_expression(product), "(a + b) * c");
+
+        // Binary operators are right associative in the parser
+        let left  = binary(binary(id("a"), BinaryOperator::Subtraction, id("b")), BinaryOperator::Subtraction, id("c"));
+        let right = binary(id("a"), BinaryOperator::Subtraction, binary(id("b"), BinaryOperator::Subtraction, id("c")));
+
+        assert_eq!(print_expression(left), "(a - b) - c");
+        assert_eq!(print_expression(right), "a - b - c");
+
+        let member = m.node(0, 0, MemberAccessExpression {
+            object: minus(id("a")),
+            member: m.node(0, 0, "b"),
+        });
+
+        assert_eq!(print_expression(member), "(-a).b");
+        assert_eq!(print_expression(minus(minus(id("a")))), "- -a");
+        assert_eq!(print_expression(minus(sum)), "-(a + b)");
+        assert_eq!(print_expression(binary(minus(id("a")), BinaryOperator::Exponent, id("b"))), "(-a) ** b");
+
+        let conditional = |test, consequent, alternate| m.node(0, 0, ConditionalExpression {
+            test,
+            consequent,
+            alternate,
+        });
+        let assignment = m.node(0, 0, AssignmentExpression {
+            left: id("a"),
+            operator: m.node(0, 0, AssignmentOperator::Plain),
+            right: id("b"),
+        });
+
+        assert_eq!(print_expression(conditional(conditional(id("a"), id("b"), id("c")), assignment, id("d"))), "(a ? b : c) ? (a = b) : d");
+    }
+
+    #[test]
+    fn dangling_else() {
+        let m = Mock::new();
+        let throw: StatementNode = m.node(0, 0, ThrowStatement);
+
+        let inner = m.node(0, 0, IfStatement {
+            test: m.node(0, 0, Expression::IdentifierExpression("b")),
+            consequent: throw,
+            alternate: None,
+        });
+        let outer = m.node(0, 0, IfStatement {
+            test: m.node(0, 0, Expression::IdentifierExpression("a")),
+            consequent: inner,
+            alternate: Some(throw),
+        });
+
+        let mut printer = Printer::new();
+
+        printer.statement(outer);
+
+        assert_eq!(printer.finish().unwrap(), "if (a) {\n    if (b) throw;\n} else throw;");
+    }
+
+    #[test]
+    fn print_built_program() {
+        let program = Program::build(|b| {
+            let uint = || b.elementary(ElementaryTypeName::Uint(32));
+            let balances = b.mapping(ElementaryTypeName::Address, uint());
+            let owner = b.index(b.identifier_expression("balances"), Some(b.member(b.identifier_expression("msg"), "sender")));
+            let getter = b.function(
+                Some("balance"),
+                b.list(None),
+                Some(FunctionVisibility::External),
+                Some(StateMutability::View),
+                b.list(Some(b.parameter(uint(), None))),
+                Some(b.block(Some(b.return_statement(Some(owner))))),
+            );
+
+            b.list(vec![
+                b.pragma("solidity ^0.4.17"),
+                b.import_from(&[("Base", None)], "./base.sol"),
+                b.contract("Wallet", &["Base"], vec![
+                    b.state_variable(balances, None, "balances", None),
+                    b.event("Deposit", b.list(Some(b.indexed_parameter(uint(), true, Some("value")))), false),
+                    getter,
+                ]),
+            ])
+        });
+
+        let printed = print(&program).unwrap();
+
+        assert_eq!(printed, r#"pragma solidity ^0.4.17;
+import { Base } from "./base.sol";
+
+contract Wallet is Base {
+    mapping(address => uint256) balances;
+    event Deposit(uint256 indexed value);
+
+    function balance() external view returns (uint256) {
+        return balances[msg.sender];
+    }
+}
+"#);
+        assert_eq!(without_spans(&program), without_spans(&parse(&printed).unwrap()));
+    }
+
+    #[test]
+    fn unprintable_nodes() {
+        let program = Program::build(|b| {
+            let array = b.at(20, 26).node(TypeName::ArrayTypeName);
+
+            b.list(vec![
+                b.contract("Foo", &[], vec![
+                    b.state_variable(array, None, "a", None),
+                    b.at(30, 40).node(ContractPart::Error),
+                ]),
+            ])
+        });
+
+        let error = print(&program).unwrap_err();
+
+        assert_eq!(error, PrintError { kind: "ArrayTypeName", start: 20, end: 26 });
+        assert_eq!(error.to_string(), "can't print `ArrayTypeName` at 20..26");
+
+        let program = Program::build(|b| {
+            b.list(vec![
+                b.contract("Foo", &[], vec![
+                    b.function(Some("bar"), b.list(None), None, None, b.list(None), Some(b.block(vec![
+                        b.inline_assembly(vec![b.at(50, 55).node(AssemblyItem::HexLiteral)]),
+                    ]))),
+                ]),
+            ])
+        });
+
+        assert_eq!(print(&program), Err(PrintError { kind: "HexLiteral", start: 50, end: 55 }));
+    }
+}
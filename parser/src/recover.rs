@@ -0,0 +1,265 @@
+use ast::*;
+use Parser;
+use lexer::Token;
+
+impl<'ast> Parser<'ast> {
+    /// Mark the start of a new list item, returning its start position.
+    #[inline]
+    pub fn start_item(&mut self) -> u32 {
+        self.broken = false;
+
+        self.token_start()
+    }
+
+    /// Check whether the list item starting at `start` failed to parse,
+    /// or the list has simply ended.
+    #[inline]
+    pub fn needs_recovery(&mut self, start: u32) -> bool {
+        match self.lexer.token {
+            Token::BraceClose             |
+            Token::EndOfProgram           |
+            Token::UnexpectedEndOfProgram => self.token_start() != start,
+            _                             => true,
+        }
+    }
+
+    pub fn recover_source_unit(&mut self, start: u32) -> SourceUnitNode<'ast> {
+        let expected = [Token::KeywordPragma, Token::KeywordImport, Token::DeclarationContract];
+
+        let end = self.recover(start, &expected, false, |token| matches!(
+            token,
+            Token::KeywordPragma       |
+            Token::KeywordImport       |
+            Token::DeclarationContract
+        ));
+
+        self.node_at(start, end, SourceUnit::Error)
+    }
+
+    pub fn recover_contract_part(&mut self, start: u32) -> ContractPartNode<'ast> {
+        let end = self.recover(start, &[], true, |token| matches!(
+            token,
+            Token::KeywordUsing        |
+            Token::DeclarationStruct   |
+            Token::DeclarationModifier |
+            Token::DeclarationFunction |
+            Token::DeclarationEvent    |
+            Token::DeclarationEnum
+        ));
+
+        self.node_at(start, end, ContractPart::Error)
+    }
+
+    pub fn recover_statement(&mut self, start: u32) -> StatementNode<'ast> {
+        let end = self.recover(start, &[], true, |_| false);
+
+        self.node_at(start, end, Statement::Error)
+    }
+
+    /// Report an error for the list item starting at `start`, unless it has
+    /// already been reported, and get the parser back in sync if necessary.
+    /// Returns the end of the source belonging to the broken item.
+    fn recover<F>(&mut self, start: u32, expected: &[Token], nested: bool, boundary: F) -> u32
+    where
+        F: Fn(Token) -> bool,
+    {
+        if !self.broken {
+            self.error_expected(expected);
+        }
+
+        self.broken = false;
+
+        if !self.recovering {
+            // Already in sync, the item ended with a `;` or a `}`
+            return self.trimmed_end(start);
+        }
+
+        self.recovering = false;
+
+        self.skip_until(start, nested, boundary)
+    }
+
+    /// Skip tokens until a `;` (inclusive), a closing `}` of the enclosing
+    /// block if `nested` is set, or a token matching `boundary`, all of
+    /// them outside of any brackets. Returns the end of the skipped source.
+    fn skip_until<F>(&mut self, start: u32, nested: bool, boundary: F) -> u32
+    where
+        F: Fn(Token) -> bool,
+    {
+        let mut depth = 0usize;
+        let mut end   = self.trimmed_end(start);
+
+        loop {
+            match self.lexer.token {
+                Token::EndOfProgram | Token::UnexpectedEndOfProgram => break,
+
+                Token::BraceClose if depth == 0 && nested => break,
+
+                Token::Semicolon if depth == 0 => {
+                    end = self.end_then_advance();
+                    break;
+                },
+
+                Token::BraceOpen | Token::ParenOpen | Token::BracketOpen => depth += 1,
+
+                Token::BraceClose | Token::ParenClose | Token::BracketClose => {
+                    depth = depth.saturating_sub(1);
+                },
+
+                token if depth == 0 && end > start && boundary(token) => break,
+
+                _ => {},
+            }
+
+            end = self.end_then_advance();
+        }
+
+        end
+    }
+
+    /// Start of the current token with any whitespace preceding it excluded,
+    /// but never before `start`.
+    fn trimmed_end(&mut self, start: u32) -> u32 {
+        let source = self.lexer.source.as_bytes();
+        let start = (start - self.offset) as usize;
+        let mut end = self.lexer.range().start;
+
+        while end > start && source[end - 1].is_ascii_whitespace() {
+            end -= 1;
+        }
+
+        self.offset + end as u32
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use {parse, parse_partial, ParserOptions, ErrorKind};
+
+    fn partial<'ast>(source: &str) -> (Program<'ast>, Vec<::Error>) {
+        parse_partial(source, ParserOptions::default())
+    }
+
+    fn parts<'ast>(program: &Program<'ast>) -> Vec<ContractPartNode<'ast>> {
+        match program.body().iter().next().map(|unit| unit.value) {
+            Some(SourceUnit::ContractDefinition(contract)) => contract.body.iter().cloned().collect(),
+            _ => panic!("Expected a contract"),
+        }
+    }
+
+    fn statements<'ast>(part: ContractPartNode<'ast>) -> Vec<StatementNode<'ast>> {
+        match part.value {
+            ContractPart::FunctionDefinition(FunctionDefinition { block: Some(block), .. }) => {
+                block.value.body.iter().cloned().collect()
+            },
+            _ => panic!("Expected a function with a body"),
+        }
+    }
+
+    #[test]
+    fn valid_source_has_no_errors() {
+        let source = include_str!("../../lunarity/benches/second-price-auction.sol");
+        let (program, errors) = partial(source);
+
+        assert_eq!(errors, Vec::new());
+        assert!(parse(source).is_ok());
+        assert_eq!(program.body().iter().count(), 4);
+    }
+
+    #[test]
+    fn recover_statements() {
+        let source = "contract Foo { function bar() { a = ; b = 1; c + ) d; e = 2; } }";
+        let (program, errors) = partial(source);
+        let parts = parts(&program);
+        let statements = statements(parts[0]);
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(&source[errors[0].span.clone()], ";");
+        assert_eq!(&source[errors[1].span.clone()], ")");
+
+        assert_eq!(statements.len(), 4);
+        assert_eq!(statements[0].value, Statement::Error);
+        assert_eq!(&source[statements[0].start as usize..statements[0].end as usize], "a = ;");
+        assert_eq!(&source[statements[1].start as usize..statements[1].end as usize], "b = 1;");
+        assert_eq!(statements[2].value, Statement::Error);
+        assert_eq!(&source[statements[2].start as usize..statements[2].end as usize], "c + ) d;");
+        assert_eq!(&source[statements[3].start as usize..statements[3].end as usize], "e = 2;");
+    }
+
+    #[test]
+    fn recover_unterminated_statement() {
+        let source = "contract Foo { function bar() { a + } function baz() {} }";
+        let (program, errors) = partial(source);
+        let parts = parts(&program);
+        let statements = statements(parts[0]);
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(parts.len(), 2);
+        assert_eq!(statements[0].value, Statement::Error);
+        assert_eq!(&source[statements[0].start as usize..statements[0].end as usize], "a +");
+    }
+
+    #[test]
+    fn recover_contract_parts() {
+        let source = "contract Foo { 123 { x } uint a; ) function bar() {} uint b; }";
+        let (program, errors) = partial(source);
+        let parts = parts(&program);
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(parts.len(), 4);
+        assert_eq!(parts[0].value, ContractPart::Error);
+        assert_eq!(&source[parts[0].start as usize..parts[0].end as usize], "123 { x } uint a;");
+        assert_eq!(parts[1].value, ContractPart::Error);
+        assert_eq!(&source[parts[1].start as usize..parts[1].end as usize], ")");
+
+        match parts[2].value {
+            ContractPart::FunctionDefinition(_) => {},
+            _ => panic!("Expected a function"),
+        }
+
+        match parts[3].value {
+            ContractPart::StateVariableDeclaration(_) => {},
+            _ => panic!("Expected a state variable"),
+        }
+    }
+
+    #[test]
+    fn recover_source_units() {
+        let source = "uint a; } contract Foo {} garbage contract Bar {}";
+        let (program, errors) = partial(source);
+        let units: Vec<_> = program.body().iter().cloned().collect();
+
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors[0].expected, vec![Token::KeywordPragma, Token::KeywordImport, Token::DeclarationContract]);
+        assert_eq!(units.len(), 5);
+        assert_eq!(units[0].value, SourceUnit::Error);
+        assert_eq!(&source[units[0].start as usize..units[0].end as usize], "uint a;");
+        assert_eq!(units[1].value, SourceUnit::Error);
+        assert_eq!(&source[units[1].start as usize..units[1].end as usize], "}");
+        assert_eq!(units[3].value, SourceUnit::Error);
+        assert_eq!(&source[units[3].start as usize..units[3].end as usize], "garbage");
+    }
+
+    #[test]
+    fn no_error_cascades() {
+        let source = "contract Foo { function bar() { a = 1 2 3; b = 2 } }";
+        let (_, errors) = partial(source);
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].kind, ErrorKind::MissingSemicolon);
+        assert_eq!(&source[errors[0].span.clone()], "2");
+        assert_eq!(errors[1].kind, ErrorKind::MissingSemicolon);
+        assert_eq!(&source[errors[1].span.clone()], "}");
+    }
+
+    #[test]
+    fn unclosed_contract() {
+        let source = "contract Foo { function bar() {}";
+        let (program, errors) = partial(source);
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, ErrorKind::UnexpectedEndOfProgram);
+        assert_eq!(parts(&program).len(), 1);
+    }
+}
@@ -18,7 +18,7 @@ impl<'ast> Parser<'ast> {
         let start = self.start_then_advance();
 
         if self.lexer.token != Token::Identifier || self.lexer.slice() != "solidity" {
-            self.error();
+            self.error_expected(&[Token::Identifier]);
         }
 
         let version = ::lexer::read_pragma(&mut self.lexer);
@@ -34,7 +34,7 @@ impl<'ast> Parser<'ast> {
 
         let symbol = match self.lexer.token {
             Token::OperatorMultiplication => {
-                self.lexer.advance();
+                self.advance();
 
                 None
             },
@@ -70,7 +70,7 @@ impl<'ast> Parser<'ast> {
     }
 
     fn import_directive_from_many(&mut self, start: u32) -> Option<SourceUnitNode<'ast>> {
-        self.lexer.advance();
+        self.advance();
 
         let imports = ListBuilder::new(self.arena, self.import_node());
 
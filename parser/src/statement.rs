@@ -1,7 +1,8 @@
 use toolshed::list::{List, GrowableList, ListBuilder};
 
 use ast::*;
-use {Parser, TOP, StatementTypeNameContext};
+use {Parser, ErrorKind, TOP, StatementTypeNameContext};
+use expression::EXPRESSION;
 use lexer::Token;
 
 /// A trait that allows for extra statements to be parsed in a specific context.
@@ -16,6 +17,48 @@ pub trait StatementContext<'ast> {
     }
 }
 
+/// Tokens that can begin a statement.
+pub const STATEMENT: &[Token] = &[
+    Token::BraceOpen,
+    Token::KeywordIf,
+    Token::KeywordWhile,
+    Token::KeywordFor,
+    Token::KeywordDo,
+    Token::KeywordReturn,
+    Token::KeywordThrow,
+    Token::KeywordAssembly,
+    Token::DeclarationVar,
+    Token::KeywordContinue,
+    Token::KeywordBreak,
+    Token::KeywordMapping,
+    Token::KeywordThis,
+    Token::Identifier,
+    Token::IdentifierBuiltin,
+    Token::ParenOpen,
+    Token::OperatorLogicalNot,
+    Token::OperatorBitNot,
+    Token::KeywordDelete,
+    Token::OperatorIncrement,
+    Token::OperatorDecrement,
+    Token::OperatorAddition,
+    Token::OperatorSubtraction,
+    Token::LiteralTrue,
+    Token::LiteralFalse,
+    Token::LiteralHex,
+    Token::LiteralInteger,
+    Token::LiteralRational,
+    Token::LiteralString,
+    Token::TypeBool,
+    Token::TypeAddress,
+    Token::TypeString,
+    Token::TypeByte,
+    Token::TypeBytes,
+    Token::TypeInt,
+    Token::TypeUint,
+    Token::TypeFixed,
+    Token::TypeUfixed,
+];
+
 pub struct FunctionContext;
 pub struct ModifierContext;
 
@@ -70,26 +113,44 @@ impl<'ast> Parser<'ast> {
     where
         Context: StatementContext<'ast>,
     {
-        if let statement @ Some(_) = Context::pre_parse(self) {
-            return statement;
-        }
-
-        match self.lexer.token {
-            Token::BraceOpen       => Some(self.block::<Context, _>()),
-            Token::KeywordIf       => self.if_statement::<Context>(),
-            Token::KeywordWhile    => self.while_statement::<Context>(),
-            Token::KeywordFor      => self.for_statement::<Context>(),
-            Token::KeywordDo       => self.do_while_statement::<Context>(),
-            Token::KeywordReturn   => self.return_statement(),
-            Token::KeywordThrow    => self.token_statement(ThrowStatement),
-            Token::KeywordAssembly => self.inline_assembly_statement(),
-            Token::DeclarationVar  => self.inferred_definition_statement(),
+        self.descend(|par| {
+            if let statement @ Some(_) = Context::pre_parse(par) {
+                return statement;
+            }
 
-            _ => match self.variable_definition_statement() {
-                None => self.expression_statement(),
-                node => node,
+            match par.lexer.token {
+                Token::BraceOpen       => Some(par.block::<Context, _>()),
+                Token::KeywordIf       => par.if_statement::<Context>(),
+                Token::KeywordWhile    => par.while_statement::<Context>(),
+                Token::KeywordFor      => par.for_statement::<Context>(),
+                Token::KeywordDo       => par.do_while_statement::<Context>(),
+                Token::KeywordReturn   => par.return_statement(),
+                Token::KeywordThrow    => par.token_statement(ThrowStatement),
+                Token::KeywordAssembly => par.inline_assembly_statement(),
+                Token::DeclarationVar  => par.inferred_definition_statement(),
+
+                // Loop contexts handle these in `pre_parse`
+                Token::KeywordContinue => {
+                    par.error_kind(ErrorKind::ContinueOutsideLoop);
+                    par.token_statement(ContinueStatement)
+                },
+                Token::KeywordBreak => {
+                    par.error_kind(ErrorKind::BreakOutsideLoop);
+                    par.token_statement(BreakStatement)
+                },
+
+                // Modifier contexts handle this in `pre_parse`
+                Token::Identifier if par.lexer.slice() == "_" => {
+                    par.error_kind(ErrorKind::PlaceholderOutsideModifier);
+                    par.token_statement(Placeholder)
+                },
+
+                _ => match par.variable_definition_statement() {
+                    None => par.expression_statement(),
+                    node => node,
+                }
             }
-        }
+        })
     }
 
     pub fn simple_statement(&mut self) -> Option<SimpleStatementNode<'ast>> {
@@ -112,7 +173,15 @@ impl<'ast> Parser<'ast> {
         let start = self.start_then_advance();
         let body  = GrowableList::new();
 
-        while let Some(statement) = self.statement::<Context>() {
+        loop {
+            let start = self.start_item();
+
+            let statement = match self.statement::<Context>() {
+                Some(statement) if !self.broken => statement,
+                None if !self.needs_recovery(start) => break,
+                _ => self.recover_statement(start),
+            };
+
             body.push(self.arena, statement);
         }
 
@@ -153,7 +222,7 @@ impl<'ast> Parser<'ast> {
             alternate = self.statement::<Context>();
 
             if alternate.is_none() {
-                self.error();
+                self.error_expected(STATEMENT);
             }
         } else {
             alternate = None;
@@ -257,7 +326,7 @@ impl<'ast> Parser<'ast> {
         let string = self.allow_str_node(Token::LiteralString);
 
         if self.lexer.token != Token::BraceOpen {
-            self.error();
+            self.error_expected(&[Token::BraceOpen]);
         }
 
         let block = expect!(self, self.inline_assembly_block());
@@ -291,7 +360,7 @@ impl<'ast> Parser<'ast> {
             init = self.expression(TOP);
 
             if init.is_none() {
-                self.error();
+                self.error_expected(EXPRESSION);
             }
         } else {
             init = None
@@ -351,6 +420,7 @@ impl<'ast> Parser<'ast> {
 mod test {
     use super::*;
     use mock::{Mock, assert_units};
+    use {parse_partial, ParserOptions};
 
     #[test]
     fn empty_block() {
@@ -364,10 +434,12 @@ mod test {
 
         "#, [
             m.node(14, 76, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 62, FunctionDefinition {
+                        doc: None,
                         name: m.node(54, 57, "wow"),
                         params: NodeList::empty(),
                         visibility: None,
@@ -386,8 +458,7 @@ mod test {
     #[test]
     fn no_placeholder_in_functions() {
         let m = Mock::new();
-
-        assert_units(r#"
+        let source = r#"
 
             contract Foo {
                 function bar() {
@@ -395,27 +466,34 @@ mod test {
                 }
             }
 
-        "#, [
-            m.node(14, 116, ContractDefinition {
-                name: m.node(23, 26, "Foo"),
-                inherits: NodeList::empty(),
-                body: m.list([
-                    m.node(45, 102, FunctionDefinition {
-                        name: m.node(54, 57, "bar"),
-                        params: NodeList::empty(),
-                        visibility: None,
-                        mutability: None,
-                        modifiers: NodeList::empty(),
-                        returns: NodeList::empty(),
-                        block: m.node(60, 102, Block {
-                            body: m.list([
-                                m.stmt_expr(82, 83, 84,"_")
-                            ]),
-                        })
-                    }),
-                ]),
-            }),
-        ]);
+        "#;
+
+        let (program, errors) = parse_partial(source, ParserOptions::default());
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, ErrorKind::PlaceholderOutsideModifier);
+        assert_eq!(&source[errors[0].span.clone()], "_");
+        assert_eq!(program.body().iter().next(), Some(&m.node(14, 116, ContractDefinition {
+            doc: None,
+            name: m.node(23, 26, "Foo"),
+            inherits: NodeList::empty(),
+            body: m.list([
+                m.node(45, 102, FunctionDefinition {
+                    doc: None,
+                    name: m.node(54, 57, "bar"),
+                    params: NodeList::empty(),
+                    visibility: None,
+                    mutability: None,
+                    modifiers: NodeList::empty(),
+                    returns: NodeList::empty(),
+                    block: m.node(60, 102, Block {
+                        body: m.list([
+                            m.node(82, 84, Placeholder),
+                        ]),
+                    })
+                }),
+            ]),
+        })));
     }
 
     #[test]
@@ -448,10 +526,12 @@ mod test {
 
         "#, [
             m.node(14, 533, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 519, FunctionDefinition {
+                        doc: None,
                         name: m.node(54, 57, "bar"),
                         params: NodeList::empty(),
                         visibility: None,
@@ -527,10 +607,12 @@ mod test {
 
         "#, [
             m.node(14, 193, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 179, FunctionDefinition {
+                        doc: None,
                         name: m.node(54, 57, "bar"),
                         params: NodeList::empty(),
                         visibility: None,
@@ -571,10 +653,12 @@ mod test {
 
         "#, [
             m.node(14, 216, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 202, FunctionDefinition {
+                        doc: None,
                         name: m.node(54, 57, "bar"),
                         params: NodeList::empty(),
                         visibility: None,
@@ -629,10 +713,12 @@ mod test {
 
         "#, [
             m.node(14, 125, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 111, FunctionDefinition {
+                        doc: None,
                         name: m.node(54, 57, "bar"),
                         params: NodeList::empty(),
                         visibility: None,
@@ -673,10 +759,12 @@ mod test {
 
         "#, [
             m.node(14, 197, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 183, FunctionDefinition {
+                        doc: None,
                         name: m.node(54, 57, "bar"),
                         params: NodeList::empty(),
                         visibility: None,
@@ -721,10 +809,12 @@ mod test {
 
         "#, [
             m.node(14, 268, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 254, FunctionDefinition {
+                        doc: None,
                         name: m.node(54, 57, "bar"),
                         params: NodeList::empty(),
                         visibility: None,
@@ -800,10 +890,12 @@ mod test {
 
         "#, [
             m.node(14, 180, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 166, FunctionDefinition {
+                        doc: None,
                         name: m.node(54, 57, "bar"),
                         params: NodeList::empty(),
                         visibility: None,
@@ -843,10 +935,12 @@ mod test {
 
         "#, [
             m.node(14, 212, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 198, FunctionDefinition {
+                        doc: None,
                         name: m.node(54, 57, "wow"),
                         params: NodeList::empty(),
                         visibility: None,
@@ -904,10 +998,12 @@ mod test {
 
         "#, [
             m.node(14, 253, ContractDefinition {
+                doc: None,
                 name: m.node(23, 26, "Foo"),
                 inherits: NodeList::empty(),
                 body: m.list([
                     m.node(45, 239, FunctionDefinition {
+                        doc: None,
                         name: m.node(54, 57, "wow"),
                         params: NodeList::empty(),
                         visibility: None,
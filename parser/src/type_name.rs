@@ -2,6 +2,21 @@ use ast::*;
 use Parser;
 use lexer::Token;
 
+/// Tokens that can begin a type name outside of statements.
+pub const TYPE_NAME: &[Token] = &[
+    Token::KeywordMapping,
+    Token::Identifier,
+    Token::TypeBool,
+    Token::TypeAddress,
+    Token::TypeString,
+    Token::TypeByte,
+    Token::TypeBytes,
+    Token::TypeInt,
+    Token::TypeUint,
+    Token::TypeFixed,
+    Token::TypeUfixed,
+];
+
 pub trait TypeNameContext<'ast> {
     fn parse(&mut Parser<'ast>) -> Option<TypeNameNode<'ast>>;
 }
@@ -35,7 +50,7 @@ impl<'ast> Parser<'ast> {
     where
         Context: TypeNameContext<'ast>,
     {
-        Context::parse(self)
+        self.descend(Context::parse)
     }
 
     pub fn elementary_type_name<E>(&mut self) -> Option<Node<'ast, E>>
@@ -87,7 +102,7 @@ impl<'ast> Parser<'ast> {
         let (start, end) = self.loc();
         let identifier = self.lexer.slice();
 
-        self.lexer.advance();
+        self.advance();
 
         self.node_at(start, end, identifier)
     }